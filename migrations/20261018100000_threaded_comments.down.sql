@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_comments_parent;
+ALTER TABLE Comments DROP COLUMN IF EXISTS deleted;
+ALTER TABLE Comments DROP COLUMN IF EXISTS parent_id;
@@ -0,0 +1,5 @@
+ALTER TABLE Comments
+    ADD COLUMN parent_id UUID NULL REFERENCES Comments(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    ADD COLUMN deleted BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS idx_comments_parent ON Comments(parent_id);
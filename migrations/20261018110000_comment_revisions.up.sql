@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS CommentRevisions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    comment_id UUID NOT NULL REFERENCES Comments(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    body text NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL default NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_commentrevisions_comment ON CommentRevisions(comment_id);
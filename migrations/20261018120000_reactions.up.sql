@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS Reactions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES Users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    article_id UUID NULL REFERENCES Articles(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    comment_id UUID NULL REFERENCES Comments(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    kind text NOT NULL CHECK (kind IN ('like', 'insightful', 'celebrate')),
+    created_at TIMESTAMPTZ NOT NULL default NOW(),
+    CHECK ((article_id IS NULL) <> (comment_id IS NULL))
+);
+
+create unique index idx_reactions_article_user on Reactions(article_id, user_id, kind) where article_id is not null;
+create unique index idx_reactions_comment_user on Reactions(comment_id, user_id, kind) where comment_id is not null;
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS Bookmarks (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    article_id UUID NOT NULL REFERENCES Articles(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    user_id UUID NOT NULL REFERENCES Users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    archived BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL default NOW(),
+    updated_at TIMESTAMPTZ NOT NULL default NOW()
+);
+
+create unique index idx_bookmarks_user_article on Bookmarks(user_id, article_id);
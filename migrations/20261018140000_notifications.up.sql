@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS Notifications (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES Users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    actor_id UUID NOT NULL REFERENCES Users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    kind text NOT NULL CHECK (kind IN ('follow', 'favorite', 'comment', 'reply')),
+    article_id UUID NULL REFERENCES Articles(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    read_at TIMESTAMPTZ NULL,
+    created_at TIMESTAMPTZ NOT NULL default NOW()
+);
+
+create index idx_notifications_user_unread on Notifications(user_id) where read_at is null;
+create index idx_notifications_user_created on Notifications(user_id, created_at desc);
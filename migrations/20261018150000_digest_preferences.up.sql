@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS DigestPreferences (
+    user_id UUID PRIMARY KEY REFERENCES Users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    frequency text NOT NULL DEFAULT 'never' CHECK (frequency IN ('never', 'daily', 'weekly')),
+    tags text[] NOT NULL DEFAULT '{}',
+    last_sent_at TIMESTAMPTZ NULL,
+    created_at TIMESTAMPTZ NOT NULL default NOW(),
+    updated_at TIMESTAMPTZ NOT NULL default NOW()
+);
+
+create index idx_digestpreferences_due on DigestPreferences(last_sent_at) where frequency <> 'never';
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS Uploads (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    key text NOT NULL,
+    user_id UUID NOT NULL REFERENCES Users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    kind text NOT NULL,
+    content_type text NOT NULL,
+    size bigint NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL default NOW()
+);
+
+create unique index idx_uploads_user_key_kind on Uploads(user_id, key, kind);
+create index idx_uploads_key on Uploads(key);
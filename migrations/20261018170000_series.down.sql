@@ -0,0 +1,2 @@
+ALTER TABLE Articles DROP COLUMN IF EXISTS series_position, DROP COLUMN IF EXISTS series_id;
+DROP TABLE IF EXISTS Series;
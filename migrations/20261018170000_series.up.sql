@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS Series (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES Users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    title text NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL default NOW(),
+    updated_at TIMESTAMPTZ NOT NULL default NOW()
+);
+
+create index idx_series_user on Series(user_id);
+
+ALTER TABLE Articles
+    ADD COLUMN series_id UUID NULL REFERENCES Series(id) ON DELETE SET NULL ON UPDATE CASCADE,
+    ADD COLUMN series_position int NULL;
+
+create index idx_articles_series on Articles(series_id, series_position);
@@ -0,0 +1,6 @@
+DELETE FROM Notifications WHERE kind = 'invite';
+ALTER TABLE Notifications DROP CONSTRAINT IF EXISTS notifications_kind_check;
+ALTER TABLE Notifications ADD CONSTRAINT notifications_kind_check
+    CHECK (kind IN ('follow', 'favorite', 'comment', 'reply'));
+
+DROP TABLE IF EXISTS ArticleAuthors;
@@ -0,0 +1,21 @@
+-- Articles.author_id stays the owner; editors are invited and can edit once they accept
+CREATE TABLE IF NOT EXISTS ArticleAuthors (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    article_id UUID NOT NULL REFERENCES Articles(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    user_id UUID NOT NULL REFERENCES Users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    role text NOT NULL CHECK (role IN ('owner', 'editor')),
+    invited_by UUID NULL REFERENCES Users(id) ON DELETE SET NULL ON UPDATE CASCADE,
+    -- Invitations are pending until accepted
+    accepted_at TIMESTAMPTZ NULL,
+    created_at TIMESTAMPTZ NOT NULL default NOW()
+);
+
+create unique index idx_article_authors_article_user on ArticleAuthors(article_id, user_id);
+create index idx_article_authors_user on ArticleAuthors(user_id);
+
+INSERT INTO ArticleAuthors(article_id, user_id, role, accepted_at)
+SELECT id, author_id, 'owner', created_at FROM Articles;
+
+ALTER TABLE Notifications DROP CONSTRAINT IF EXISTS notifications_kind_check;
+ALTER TABLE Notifications ADD CONSTRAINT notifications_kind_check
+    CHECK (kind IN ('follow', 'favorite', 'comment', 'reply', 'invite'));
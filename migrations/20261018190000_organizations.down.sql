@@ -0,0 +1,4 @@
+ALTER TABLE Articles DROP COLUMN IF EXISTS organization_id;
+DROP TABLE IF EXISTS OrganizationFollows;
+DROP TABLE IF EXISTS OrganizationMembers;
+DROP TABLE IF EXISTS Organizations;
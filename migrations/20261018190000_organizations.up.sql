@@ -0,0 +1,32 @@
+CREATE TABLE IF NOT EXISTS Organizations (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    slug text NOT NULL UNIQUE,
+    name text NOT NULL,
+    description text NOT NULL DEFAULT '',
+    image text NULL,
+    created_at TIMESTAMPTZ NOT NULL default NOW()
+);
+
+CREATE TABLE IF NOT EXISTS OrganizationMembers (
+    organization_id UUID NOT NULL REFERENCES Organizations(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    user_id UUID NOT NULL REFERENCES Users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    -- Owners manage members, editors edit every article of the organization and
+    -- writers only publish their own under it
+    role text NOT NULL CHECK (role IN ('owner', 'editor', 'writer')),
+    created_at TIMESTAMPTZ NOT NULL default NOW(),
+    PRIMARY KEY (organization_id, user_id)
+);
+
+create index idx_organization_members_user on OrganizationMembers(user_id);
+
+CREATE TABLE IF NOT EXISTS OrganizationFollows (
+    follower_id UUID NOT NULL REFERENCES Users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    organization_id UUID NOT NULL REFERENCES Organizations(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL default NOW(),
+    PRIMARY KEY (follower_id, organization_id)
+);
+
+ALTER TABLE Articles ADD COLUMN organization_id UUID NULL
+    REFERENCES Organizations(id) ON DELETE SET NULL ON UPDATE CASCADE;
+
+create index idx_articles_organization on Articles(organization_id);
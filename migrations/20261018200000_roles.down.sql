@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS ModerationLog;
+ALTER TABLE Comments DROP COLUMN IF EXISTS hidden_at;
+ALTER TABLE Articles DROP COLUMN IF EXISTS hidden_at;
+ALTER TABLE Users DROP COLUMN IF EXISTS role;
@@ -0,0 +1,18 @@
+ALTER TABLE Users ADD COLUMN role text NOT NULL DEFAULT 'user'
+    CHECK (role IN ('user', 'moderator', 'admin'));
+
+-- Hidden content stays in place for its authors and moderators only
+ALTER TABLE Articles ADD COLUMN hidden_at TIMESTAMPTZ NULL;
+ALTER TABLE Comments ADD COLUMN hidden_at TIMESTAMPTZ NULL;
+
+CREATE TABLE IF NOT EXISTS ModerationLog (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    actor_id UUID NULL REFERENCES Users(id) ON DELETE SET NULL ON UPDATE CASCADE,
+    action text NOT NULL,
+    -- No foreign key, deleted content keeps its entries
+    target_id UUID NOT NULL,
+    details text NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL default NOW()
+);
+
+create index idx_moderation_log_created_at on ModerationLog(created_at DESC);
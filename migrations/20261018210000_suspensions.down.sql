@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_comments_created_at;
+DROP INDEX IF EXISTS idx_articles_created_at;
+DROP INDEX IF EXISTS idx_users_created_at;
+ALTER TABLE Users DROP COLUMN IF EXISTS status;
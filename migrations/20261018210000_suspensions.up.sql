@@ -0,0 +1,7 @@
+-- Suspended users cannot log in
+ALTER TABLE Users ADD COLUMN status TEXT NOT NULL DEFAULT 'active'
+    CONSTRAINT users_status_check CHECK (status IN ('active', 'suspended'));
+
+create index idx_users_created_at on Users(created_at);
+create index idx_articles_created_at on Articles(created_at);
+create index idx_comments_created_at on Comments(created_at);
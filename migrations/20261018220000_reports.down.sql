@@ -0,0 +1,3 @@
+ALTER TABLE Comments DROP COLUMN IF EXISTS hidden_by_reports;
+ALTER TABLE Articles DROP COLUMN IF EXISTS hidden_by_reports;
+DROP TABLE IF EXISTS Reports;
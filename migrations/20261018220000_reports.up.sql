@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS Reports (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    reporter_id UUID NOT NULL REFERENCES Users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    target_kind text NOT NULL CHECK (target_kind IN ('article', 'comment', 'user')),
+    -- No foreign key, it points to a different table depending on target_kind
+    target_id UUID NOT NULL,
+    reason text NOT NULL CHECK (reason IN ('spam', 'abuse', 'harassment', 'other')),
+    details text NOT NULL DEFAULT '',
+    status text NOT NULL DEFAULT 'open' CHECK (status IN ('open', 'resolved', 'dismissed')),
+    resolved_by UUID NULL REFERENCES Users(id) ON DELETE SET NULL ON UPDATE CASCADE,
+    resolved_at TIMESTAMPTZ NULL,
+    created_at TIMESTAMPTZ NOT NULL default NOW()
+);
+
+-- A user can only have one open report on the same target
+create unique index idx_reports_open_reporter_target on Reports(reporter_id, target_kind, target_id)
+    WHERE status = 'open';
+create index idx_reports_open_target on Reports(target_kind, target_id) WHERE status = 'open';
+
+-- Content hidden for reaching the report threshold, which dismissing its reports restores
+ALTER TABLE Articles ADD COLUMN hidden_by_reports BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE Comments ADD COLUMN hidden_by_reports BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,17 @@
+-- Blocked users cannot comment on, follow or react to the content of the blocker
+CREATE TABLE IF NOT EXISTS Blocks (
+    blocker_id UUID NOT NULL REFERENCES Users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    blocked_id UUID NOT NULL REFERENCES Users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL default NOW(),
+    PRIMARY KEY (blocker_id, blocked_id)
+);
+
+-- Muted users are left out of the feeds and comment threads of the muter
+CREATE TABLE IF NOT EXISTS Mutes (
+    muter_id UUID NOT NULL REFERENCES Users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    muted_id UUID NOT NULL REFERENCES Users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL default NOW(),
+    PRIMARY KEY (muter_id, muted_id)
+);
+
+create index idx_blocks_blocked on Blocks(blocked_id);
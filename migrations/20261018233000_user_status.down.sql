@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS idx_users_delete_after;
+ALTER TABLE Users DROP COLUMN IF EXISTS delete_after;
+UPDATE Users SET status = 'suspended' WHERE status = 'deleted';
+UPDATE Users SET status = 'active' WHERE status = 'deactivated';
+ALTER TABLE Users DROP CONSTRAINT users_status_check;
+ALTER TABLE Users ADD CONSTRAINT users_status_check CHECK (status IN ('active', 'suspended'));
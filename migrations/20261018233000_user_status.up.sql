@@ -0,0 +1,9 @@
+-- Users are never deleted, as it would cascade to everything they wrote. Their
+-- status changes instead, and deleted accounts are scrubbed of personal data.
+ALTER TABLE Users DROP CONSTRAINT users_status_check;
+ALTER TABLE Users ADD CONSTRAINT users_status_check
+    CHECK (status IN ('active', 'suspended', 'deactivated', 'deleted'));
+-- End of the grace period of a deactivated account, after which it is deleted
+ALTER TABLE Users ADD COLUMN delete_after TIMESTAMPTZ NULL;
+
+create index idx_users_delete_after on Users(delete_after) WHERE status = 'deactivated';
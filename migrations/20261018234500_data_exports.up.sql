@@ -0,0 +1,12 @@
+-- Archives of everything a user put on the site, built in the background
+CREATE TABLE IF NOT EXISTS DataExports (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES Users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    status text NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'ready', 'failed')),
+    created_at TIMESTAMPTZ NOT NULL default NOW(),
+    finished_at TIMESTAMPTZ NULL
+);
+
+create index idx_dataexports_user on DataExports(user_id, created_at);
+-- A user can only have one export being built at a time
+create unique index idx_dataexports_pending_user on DataExports(user_id) WHERE status = 'pending';
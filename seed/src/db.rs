@@ -0,0 +1,372 @@
+use std::fmt;
+
+use log::{info, warn};
+use sqlx::{Acquire, PgConnection, PgPool, Postgres, Transaction};
+use thedeveloper_leptos::models::{Article, CoAuthor, User};
+
+use crate::fixtures::{ArticleFixture, CommentFixture, FavoriteFixture, FollowFixture, Fixtures, UserFixture};
+
+/// Tables emptied by `--reset`, the ones referencing others first. Postgres only
+/// truncates a referenced table along with the tables referencing it, so they all
+/// go in one statement.
+const CONTENT_TABLES: &[&str] = &[
+    "CommentRevisions",
+    "Reactions",
+    "Comments",
+    "ArticleTags",
+    "FavArticles",
+    "Bookmarks",
+    "Notifications",
+    "ArticleAuthors",
+    "Reports",
+    "Articles",
+    "Series",
+    "OrganizationFollows",
+    "OrganizationMembers",
+    "Organizations",
+    "Follows",
+    "Blocks",
+    "Mutes",
+    "DigestPreferences",
+    "Uploads",
+    "DataExports",
+    "ModerationLog",
+    "Users",
+];
+
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// Rows written per transaction.
+    pub batch_size: usize,
+    /// Rolls everything back once the report is made.
+    pub dry_run: bool,
+    /// Empties the content tables first.
+    pub reset: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Change {
+    Created,
+    Updated,
+    Unchanged,
+    /// A user or article it refers to does not exist.
+    Skipped,
+}
+
+#[derive(Debug, Default)]
+pub struct Changes {
+    created: usize,
+    updated: usize,
+    unchanged: usize,
+    skipped: usize,
+}
+
+impl Changes {
+    fn add(&mut self, change: Change) {
+        match change {
+            Change::Created => self.created += 1,
+            Change::Updated => self.updated += 1,
+            Change::Unchanged => self.unchanged += 1,
+            Change::Skipped => self.skipped += 1,
+        }
+    }
+}
+
+/// What a load changed, or would change on a dry run.
+#[derive(Debug, Default)]
+pub struct Report {
+    pub reset: bool,
+    pub users: Changes,
+    pub articles: Changes,
+    pub comments: Changes,
+    pub follows: Changes,
+    pub favorites: Changes,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.reset {
+            writeln!(f, "emptied: {}", CONTENT_TABLES.join(", "))?;
+        }
+        for (name, changes) in [
+            ("users", &self.users),
+            ("articles", &self.articles),
+            ("comments", &self.comments),
+            ("follows", &self.follows),
+            ("favorites", &self.favorites),
+        ] {
+            writeln!(
+                f,
+                "{name}: {} created, {} updated, {} unchanged, {} skipped",
+                changes.created, changes.updated, changes.unchanged, changes.skipped
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the fixtures, creating what is missing and updating what differs, so
+/// loading the same fixtures again changes nothing. Users are matched on their
+/// username, which the other fixtures refer to them by, and articles on their
+/// slug. Pending migrations run first. Each batch is written in a transaction,
+/// and the first error stops the load.
+pub async fn load(
+    fixtures: &Fixtures,
+    pool: &PgPool,
+    options: Options,
+) -> Result<Report, Box<dyn std::error::Error>> {
+    // A dry run writes in one transaction it never commits, so later batches still
+    // see the rows of earlier ones
+    let mut dry_run = if options.dry_run { Some(pool.begin().await?) } else { None };
+    let batch_size = options.batch_size.max(1);
+    let mut report = Report::default();
+
+    // Rolled back with the rest on a dry run, which still sees the current schema
+    let migrator = sqlx::migrate!("../migrations");
+    match dry_run.as_mut() {
+        Some(transaction) => migrator.run(transaction.as_mut()).await?,
+        None => migrator.run(pool).await?,
+    }
+
+    if options.reset {
+        let mut transaction = begin(pool, &mut dry_run).await?;
+        sqlx::query(&format!("TRUNCATE {}", CONTENT_TABLES.join(", ")))
+            .execute(transaction.as_mut())
+            .await?;
+        transaction.commit().await?;
+        report.reset = true;
+    }
+
+    for (index, batch) in fixtures.users.chunks(batch_size).enumerate() {
+        let mut transaction = begin(pool, &mut dry_run).await?;
+        for user in batch {
+            let change = upsert_user(transaction.as_mut(), user)
+                .await
+                .map_err(|x| format!("user {}: {x}", user.username))?;
+            report.users.add(change);
+        }
+        transaction.commit().await?;
+        info!("Wrote the users of batch {}", index + 1);
+    }
+
+    for (index, batch) in fixtures.articles.chunks(batch_size).enumerate() {
+        let mut transaction = begin(pool, &mut dry_run).await?;
+        for article in batch {
+            let change = upsert_article(transaction.as_mut(), article)
+                .await
+                .map_err(|x| format!("article {}: {x}", article.slug))?;
+            report.articles.add(change);
+        }
+        transaction.commit().await?;
+        info!("Wrote the articles of batch {}", index + 1);
+    }
+
+    for (index, batch) in fixtures.comments.chunks(batch_size).enumerate() {
+        let mut transaction = begin(pool, &mut dry_run).await?;
+        for comment in batch {
+            let change = insert_comment(transaction.as_mut(), comment)
+                .await
+                .map_err(|x| format!("comment of {} on {}: {x}", comment.author, comment.article))?;
+            report.comments.add(change);
+        }
+        transaction.commit().await?;
+        info!("Wrote the comments of batch {}", index + 1);
+    }
+
+    for (index, batch) in fixtures.follows.chunks(batch_size).enumerate() {
+        let mut transaction = begin(pool, &mut dry_run).await?;
+        for follow in batch {
+            let change = insert_follow(transaction.as_mut(), follow)
+                .await
+                .map_err(|x| format!("{} following {}: {x}", follow.follower, follow.influencer))?;
+            report.follows.add(change);
+        }
+        transaction.commit().await?;
+        info!("Wrote the follows of batch {}", index + 1);
+    }
+
+    for (index, batch) in fixtures.favorites.chunks(batch_size).enumerate() {
+        let mut transaction = begin(pool, &mut dry_run).await?;
+        for favorite in batch {
+            let change = insert_favorite(transaction.as_mut(), favorite)
+                .await
+                .map_err(|x| format!("{} favoriting {}: {x}", favorite.user, favorite.article))?;
+            report.favorites.add(change);
+        }
+        transaction.commit().await?;
+        info!("Wrote the favorites of batch {}", index + 1);
+    }
+
+    if let Some(transaction) = dry_run {
+        transaction.rollback().await?;
+    }
+    Ok(report)
+}
+
+/// The transaction of a batch, a savepoint in the one of the dry run if any.
+async fn begin<'a>(
+    pool: &'a PgPool,
+    dry_run: &'a mut Option<Transaction<'static, Postgres>>,
+) -> Result<Transaction<'a, Postgres>, sqlx::Error> {
+    match dry_run {
+        Some(transaction) => transaction.begin().await,
+        None => pool.begin().await,
+    }
+}
+
+async fn find_user(connection: &mut PgConnection, username: &str) -> Result<Option<uuid::Uuid>, sqlx::Error> {
+    sqlx::query!("SELECT id FROM Users WHERE username = $1", username)
+        .fetch_optional(connection)
+        .await
+        .map(|x| x.map(|x| x.id))
+}
+
+async fn find_article(connection: &mut PgConnection, slug: &str) -> Result<Option<uuid::Uuid>, sqlx::Error> {
+    sqlx::query!("SELECT id FROM Articles WHERE slug = $1", slug)
+        .fetch_optional(connection)
+        .await
+        .map(|x| x.map(|x| x.id))
+}
+
+/// Passwords are only set on creation, hashing gives a different value each time.
+async fn upsert_user(connection: &mut PgConnection, user: &UserFixture) -> Result<Change, sqlx::Error> {
+    let saved = sqlx::query!(
+        "
+        INSERT INTO Users(name, username, email, password, bio, image, email_hash)
+        VALUES ($1, $2, $3, crypt($4, gen_salt('bf')), $5, $6, $7)
+        ON CONFLICT (username) DO UPDATE SET
+            name = EXCLUDED.name, bio = EXCLUDED.bio, image = EXCLUDED.image, updated_at = NOW()
+        WHERE (Users.name, Users.bio, Users.image)
+            IS DISTINCT FROM (EXCLUDED.name, EXCLUDED.bio, EXCLUDED.image)
+        RETURNING (xmax = 0) AS \"created!\"",
+        user.name,
+        user.username,
+        user.email,
+        user.password,
+        user.bio,
+        user.image,
+        User::hash_email(&user.email)
+    )
+    .fetch_optional(connection)
+    .await?;
+    Ok(match saved {
+        Some(x) if x.created => Change::Created,
+        Some(_) => Change::Updated,
+        None => Change::Unchanged,
+    })
+}
+
+/// Articles keep their author and publication date once created.
+async fn upsert_article(connection: &mut PgConnection, article: &ArticleFixture) -> Result<Change, sqlx::Error> {
+    let Some(author_id) = find_user(connection, &article.author).await? else {
+        warn!("Unknown author {} for {}, skipping it", article.author, article.slug);
+        return Ok(Change::Skipped);
+    };
+    let front_matter = &article.front_matter;
+    let created_at = front_matter
+        .date
+        .as_deref()
+        .and_then(|x| chrono::DateTime::parse_from_rfc3339(x).ok())
+        .map(|x| x.with_timezone(&chrono::Utc));
+
+    let saved = sqlx::query!(
+        "
+        INSERT INTO Articles(slug, title, description, body, tags, author_id, cover_image, reading_time, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, COALESCE($9, NOW()), COALESCE($9, NOW()))
+        ON CONFLICT (slug) DO UPDATE SET
+            title = EXCLUDED.title, description = EXCLUDED.description, body = EXCLUDED.body,
+            tags = EXCLUDED.tags, cover_image = EXCLUDED.cover_image,
+            reading_time = EXCLUDED.reading_time, updated_at = NOW()
+        WHERE (Articles.title, Articles.description, Articles.body, Articles.tags, Articles.cover_image)
+            IS DISTINCT FROM (EXCLUDED.title, EXCLUDED.description, EXCLUDED.body, EXCLUDED.tags, EXCLUDED.cover_image)
+        RETURNING (xmax = 0) AS \"created!\"",
+        article.slug,
+        front_matter.title,
+        front_matter.description,
+        article.body,
+        &front_matter.tags,
+        author_id,
+        front_matter.cover_image,
+        Article::reading_time_of(&article.body),
+        created_at
+    )
+    .fetch_optional(&mut *connection)
+    .await?;
+    Ok(match saved {
+        Some(x) if x.created => {
+            CoAuthor::add_owner(connection, &article.slug, author_id).await?;
+            Change::Created
+        }
+        Some(_) => Change::Updated,
+        None => Change::Unchanged,
+    })
+}
+
+/// Nothing is unique to a comment, so the same body by the same user on the same
+/// article counts as the same comment.
+async fn insert_comment(connection: &mut PgConnection, comment: &CommentFixture) -> Result<Change, sqlx::Error> {
+    let (Some(user_id), Some(article_id)) = (
+        find_user(connection, &comment.author).await?,
+        find_article(connection, &comment.article).await?,
+    ) else {
+        warn!("Unknown user or article for the comment of {}, skipping it", comment.author);
+        return Ok(Change::Skipped);
+    };
+    let inserted = sqlx::query!(
+        "
+        INSERT INTO Comments(article_id, user_id, body)
+        SELECT $1::uuid, $2::uuid, $3::text
+        WHERE NOT EXISTS(
+            SELECT 1 FROM Comments WHERE article_id = $1 AND user_id = $2 AND body = $3
+        )",
+        article_id,
+        user_id,
+        comment.body
+    )
+    .execute(connection)
+    .await?;
+    Ok(created_or_unchanged(inserted.rows_affected()))
+}
+
+async fn insert_follow(connection: &mut PgConnection, follow: &FollowFixture) -> Result<Change, sqlx::Error> {
+    let (Some(follower_id), Some(influencer_id)) = (
+        find_user(connection, &follow.follower).await?,
+        find_user(connection, &follow.influencer).await?,
+    ) else {
+        warn!("Unknown user in {} following {}, skipping it", follow.follower, follow.influencer);
+        return Ok(Change::Skipped);
+    };
+    let inserted = sqlx::query!(
+        "INSERT INTO Follows(follower_id, influencer_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        follower_id,
+        influencer_id
+    )
+    .execute(connection)
+    .await?;
+    Ok(created_or_unchanged(inserted.rows_affected()))
+}
+
+async fn insert_favorite(connection: &mut PgConnection, favorite: &FavoriteFixture) -> Result<Change, sqlx::Error> {
+    let (Some(user_id), Some(article_id)) = (
+        find_user(connection, &favorite.user).await?,
+        find_article(connection, &favorite.article).await?,
+    ) else {
+        warn!("Unknown user or article in {} favoriting {}, skipping it", favorite.user, favorite.article);
+        return Ok(Change::Skipped);
+    };
+    let inserted = sqlx::query!(
+        "INSERT INTO FavArticles(user_id, article_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        user_id,
+        article_id
+    )
+    .execute(connection)
+    .await?;
+    Ok(created_or_unchanged(inserted.rows_affected()))
+}
+
+fn created_or_unchanged(rows: u64) -> Change {
+    match rows {
+        0 => Change::Unchanged,
+        _ => Change::Created,
+    }
+}
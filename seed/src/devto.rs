@@ -0,0 +1,110 @@
+//! Fixtures from the latest articles of dev.to, with their authors.
+
+use std::{collections::HashSet, time::Duration};
+
+use log::{debug, error};
+use reqwest::Client;
+use serde::Deserialize;
+use thedeveloper_leptos::front_matter::FrontMatter;
+use tokio::time::sleep;
+
+use crate::fixtures::{ArticleFixture, Fixtures, UserFixture};
+
+#[derive(Deserialize, Debug)]
+struct Article {
+    id: i32,
+    user: User,
+}
+
+#[derive(Deserialize, Debug)]
+struct ArticleDetail {
+    title: String,
+    slug: String,
+    tags: Vec<String>,
+    description: String,
+    body_html: Option<String>,
+    cover_image: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct User {
+    username: String,
+    name: String,
+    summary: Option<String>,
+    profile_image: Option<String>,
+}
+
+pub async fn fetch(pages: u32) -> Result<Fixtures, Box<dyn std::error::Error>> {
+    let client = Client::new();
+    let mut fixtures = Fixtures::default();
+    let mut usernames = HashSet::new();
+
+    for page in 1..=pages {
+        let dev_url = format!(
+            "https://dev.to/api/articles/latest?page={}&per_page=50",
+            page
+        );
+
+        debug!("Fetching articles from: {}", &dev_url);
+
+        let response = client
+            .get(&dev_url)
+            .header("User-Agent", "Mozilla/5.0 (compatible; MyRustApp/1.0)")
+            .send()
+            .await?;
+
+        let articles: Vec<Article> = match response.json().await {
+            Ok(articles) => articles,
+            Err(e) => {
+                error!("Failed to parse JSON for articles: {}", e);
+                continue;
+            }
+        };
+
+        for article in articles {
+            let article_url = format!("https://dev.to/api/articles/{}", article.id);
+            let detailed_article: ArticleDetail = match client
+                .get(&article_url)
+                .header("User-Agent", "Mozilla/5.0 (compatible; MyRustApp/1.0)")
+                .send()
+                .await?
+                .json()
+                .await
+            {
+                Ok(article) => article,
+                Err(e) => {
+                    error!("Failed to fetch article details: {}", e);
+                    continue;
+                }
+            };
+
+            if usernames.insert(article.user.username.clone()) {
+                fixtures.users.push(UserFixture {
+                    email: format!("{}@example.com", article.user.username),
+                    password: crate::generate::PASSWORD.to_string(),
+                    bio: article.user.summary.filter(|x| !x.is_empty()),
+                    image: article.user.profile_image,
+                    username: article.user.username.clone(),
+                    name: article.user.name,
+                });
+            }
+            fixtures.articles.push(ArticleFixture {
+                author: article.user.username,
+                slug: detailed_article.slug,
+                front_matter: FrontMatter {
+                    title: detailed_article.title,
+                    description: detailed_article.description,
+                    tags: detailed_article.tags,
+                    date: None,
+                    cover_image: detailed_article.cover_image,
+                },
+                body: detailed_article.body_html.unwrap_or_default(),
+            });
+
+            // Sleep between article detail requests to avoid rate-limiting
+            sleep(Duration::from_secs(1)).await;
+        }
+    }
+
+    Ok(fixtures)
+}
@@ -0,0 +1,138 @@
+//! Seed data as files: `fixtures.json` for the users and what links them, and one
+//! Markdown file per article in `articles/<author username>/<slug>.md`, in the
+//! format the article importer reads.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use thedeveloper_leptos::front_matter::FrontMatter;
+
+const FIXTURES_FILE: &str = "fixtures.json";
+const ARTICLES_DIR: &str = "articles";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Fixtures {
+    pub users: Vec<UserFixture>,
+    /// Read from and written to the Markdown files.
+    #[serde(skip)]
+    pub articles: Vec<ArticleFixture>,
+    #[serde(default)]
+    pub comments: Vec<CommentFixture>,
+    #[serde(default)]
+    pub follows: Vec<FollowFixture>,
+    #[serde(default)]
+    pub favorites: Vec<FavoriteFixture>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserFixture {
+    pub username: String,
+    pub name: String,
+    pub email: String,
+    pub password: String,
+    #[serde(default)]
+    pub bio: Option<String>,
+    #[serde(default)]
+    pub image: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArticleFixture {
+    /// Username of the author.
+    pub author: String,
+    pub slug: String,
+    pub front_matter: FrontMatter,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentFixture {
+    /// Slug of the commented article.
+    pub article: String,
+    /// Username of the commenter.
+    pub author: String,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowFixture {
+    pub follower: String,
+    pub influencer: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FavoriteFixture {
+    pub user: String,
+    /// Slug of the favorited article.
+    pub article: String,
+}
+
+impl Fixtures {
+    /// Reads the fixtures of `dir`. Either part may be missing, so a folder of
+    /// Markdown files alone is enough when its authors already exist.
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let fixtures_file = dir.join(FIXTURES_FILE);
+        let mut fixtures: Self = if fixtures_file.exists() {
+            let file = std::fs::read_to_string(&fixtures_file)
+                .map_err(|x| format!("{}: {x}", fixtures_file.display()))?;
+            serde_json::from_str(&file).map_err(|x| format!("{}: {x}", fixtures_file.display()))?
+        } else {
+            Self::default()
+        };
+
+        let articles_dir = dir.join(ARTICLES_DIR);
+        if articles_dir.exists() {
+            for author in read_dir(&articles_dir)? {
+                if !author.is_dir() {
+                    continue;
+                }
+                let username = file_name(&author);
+                for file in read_dir(&author)? {
+                    if file.extension().and_then(|x| x.to_str()) != Some("md") {
+                        continue;
+                    }
+                    let content =
+                        std::fs::read_to_string(&file).map_err(|x| format!("{}: {x}", file.display()))?;
+                    let (front_matter, body) =
+                        FrontMatter::parse(&content).map_err(|x| format!("{}: {x}", file.display()))?;
+                    fixtures.articles.push(ArticleFixture {
+                        author: username.clone(),
+                        slug: file.file_stem().map(|x| x.to_string_lossy().into_owned()).unwrap_or_default(),
+                        front_matter,
+                        body,
+                    });
+                }
+            }
+        }
+        Ok(fixtures)
+    }
+
+    /// Writes the fixtures to `dir`, in the layout [`Fixtures::load`] reads.
+    pub fn write(&self, dir: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        let json = serde_json::to_string_pretty(self).expect("fixtures are always serializable");
+        std::fs::write(dir.join(FIXTURES_FILE), json + "\n")?;
+        for article in &self.articles {
+            let author_dir = dir.join(ARTICLES_DIR).join(&article.author);
+            std::fs::create_dir_all(&author_dir)?;
+            std::fs::write(
+                author_dir.join(format!("{}.md", article.slug)),
+                article.front_matter.render(&article.body),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The entries of `dir`, sorted so fixtures load in the same order everywhere.
+fn read_dir(dir: &Path) -> Result<Vec<std::path::PathBuf>, String> {
+    let mut entries = std::fs::read_dir(dir)
+        .and_then(|x| x.map(|x| x.map(|x| x.path())).collect::<Result<Vec<_>, _>>())
+        .map_err(|x| format!("{}: {x}", dir.display()))?;
+    entries.sort();
+    Ok(entries)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|x| x.to_string_lossy().into_owned()).unwrap_or_default()
+}
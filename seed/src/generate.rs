@@ -0,0 +1,213 @@
+//! Made-up users and articles, the same ones for the same seed, so a database can
+//! be filled without the network.
+
+use std::collections::BTreeSet;
+
+use chrono::{TimeZone, Utc};
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use thedeveloper_leptos::front_matter::FrontMatter;
+use thedeveloper_leptos::models::{Article, User};
+
+use crate::fixtures::{ArticleFixture, CommentFixture, FavoriteFixture, FollowFixture, Fixtures, UserFixture};
+
+/// Password of every generated user.
+pub const PASSWORD: &str = "seed-password";
+
+const FIRST_NAMES: &[&str] = &[
+    "Ada", "Alan", "Barbara", "Brian", "Dennis", "Donald", "Edsger", "Frances", "Grace", "Guido", "Hedy",
+    "John", "Ken", "Linus", "Margaret", "Niklaus", "Radia", "Rob", "Sophie", "Tim",
+];
+const LAST_NAMES: &[&str] = &[
+    "Allen", "Berners", "Cerf", "Dijkstra", "Hamilton", "Hopper", "Kernighan", "Knuth", "Lamarr", "Liskov",
+    "Lovelace", "Perlman", "Pike", "Ritchie", "Rossum", "Thompson", "Torvalds", "Turing", "Wilson", "Wirth",
+];
+const ADJECTIVES: &[&str] = &[
+    "Practical", "Gentle", "Pragmatic", "Modern", "Painless", "Fearless", "Idiomatic", "Minimal", "Fast",
+    "Reliable",
+];
+const SUBJECTS: &[&str] = &[
+    "error handling", "testing", "async code", "caching", "logging", "state management", "deployments",
+    "migrations", "benchmarks", "code reviews", "pagination", "background jobs",
+];
+const TECHNOLOGIES: &[&str] = &[
+    "Rust", "Postgres", "Leptos", "Axum", "WebAssembly", "Docker", "Tokio", "SQL", "CSS", "Linux",
+];
+const TAGS: &[&str] = &[
+    "rust", "webdev", "postgres", "wasm", "tutorial", "beginners", "performance", "devops", "testing",
+    "career",
+];
+const WORDS: &[&str] = &[
+    "the", "a", "code", "server", "query", "function", "type", "error", "test", "build", "request", "value",
+    "should", "can", "never", "always", "returns", "handles", "keeps", "makes", "simple", "small", "fast",
+    "safe", "clear", "with", "without", "before", "after", "every", "each", "change", "review", "team",
+];
+
+/// How much of each to generate.
+#[derive(Debug, Clone, Copy)]
+pub struct Counts {
+    pub users: usize,
+    pub articles: usize,
+    pub comments: usize,
+    pub follows: usize,
+    pub favorites: usize,
+}
+
+/// Fixtures made from `seed` alone.
+pub fn generate(seed: u64, counts: Counts) -> Fixtures {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    let users: Vec<UserFixture> = (0..counts.users)
+        .map(|_| {
+            let name = format!("{} {}", pick(&mut rng, FIRST_NAMES), pick(&mut rng, LAST_NAMES));
+            let username = User::generate_username_with(&name, &mut rng);
+            UserFixture {
+                email: format!("{}@example.com", username.to_lowercase()),
+                bio: Some(format!("{} writing about {}.", name, pick(&mut rng, TECHNOLOGIES))),
+                image: None,
+                password: PASSWORD.to_string(),
+                username,
+                name,
+            }
+        })
+        .collect();
+    if users.is_empty() {
+        return Fixtures::default();
+    }
+
+    // Dates spread over a year, not around now, so they are the same on every run
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let articles: Vec<ArticleFixture> = (0..counts.articles)
+        .map(|index| {
+            let title = format!(
+                "{} {} with {}",
+                pick(&mut rng, ADJECTIVES),
+                pick(&mut rng, SUBJECTS),
+                pick(&mut rng, TECHNOLOGIES)
+            );
+            let date = start + chrono::Duration::seconds(rng.gen_range(0..365 * 24 * 3600));
+            let tag_count = rng.gen_range(1..=3);
+            let front_matter = FrontMatter {
+                description: sentence(&mut rng),
+                tags: TAGS.choose_multiple(&mut rng, tag_count).map(|x| x.to_string()).collect(),
+                date: Some(date.to_rfc3339()),
+                cover_image: None,
+                title,
+            };
+            ArticleFixture {
+                author: users.choose(&mut rng).unwrap().username.clone(),
+                // The index keeps slugs unique when titles repeat
+                slug: format!("{}-{index}", Article::slugify(&front_matter.title)),
+                body: body(&mut rng, &front_matter.title),
+                front_matter,
+            }
+        })
+        .collect();
+
+    let comments = if articles.is_empty() {
+        Vec::new()
+    } else {
+        (0..counts.comments)
+            .map(|_| CommentFixture {
+                article: articles.choose(&mut rng).unwrap().slug.clone(),
+                author: users.choose(&mut rng).unwrap().username.clone(),
+                body: sentence(&mut rng),
+            })
+            .collect()
+    };
+
+    let follows = pairs(&mut rng, counts.follows, users.len(), users.len(), true)
+        .into_iter()
+        .map(|(follower, influencer)| FollowFixture {
+            follower: users[follower].username.clone(),
+            influencer: users[influencer].username.clone(),
+        })
+        .collect();
+    let favorites = pairs(&mut rng, counts.favorites, users.len(), articles.len(), false)
+        .into_iter()
+        .map(|(user, article)| FavoriteFixture {
+            user: users[user].username.clone(),
+            article: articles[article].slug.clone(),
+        })
+        .collect();
+
+    Fixtures {
+        users,
+        articles,
+        comments,
+        follows,
+        favorites,
+    }
+}
+
+fn pick<'a>(rng: &mut ChaCha8Rng, words: &[&'a str]) -> &'a str {
+    words.choose(rng).unwrap()
+}
+
+fn sentence(rng: &mut ChaCha8Rng) -> String {
+    let length = rng.gen_range(6..14);
+    let mut sentence = (0..length).map(|_| pick(rng, WORDS)).collect::<Vec<_>>().join(" ");
+    sentence[..1].make_ascii_uppercase();
+    sentence.push('.');
+    sentence
+}
+
+fn body(rng: &mut ChaCha8Rng, title: &str) -> String {
+    let mut body = format!("# {title}\n");
+    for _ in 0..rng.gen_range(3..7) {
+        body.push('\n');
+        let paragraph = (0..rng.gen_range(2..6)).map(|_| sentence(rng)).collect::<Vec<_>>();
+        body.push_str(&paragraph.join(" "));
+        body.push('\n');
+    }
+    body
+}
+
+/// Up to `count` distinct pairs of indexes, in the order they were drawn.
+fn pairs(rng: &mut ChaCha8Rng, count: usize, left: usize, right: usize, distinct: bool) -> Vec<(usize, usize)> {
+    let possible = if distinct { left * right.saturating_sub(1) } else { left * right };
+    let mut seen = BTreeSet::new();
+    let mut pairs = Vec::new();
+    while pairs.len() < count.min(possible) {
+        let pair = (rng.gen_range(0..left), rng.gen_range(0..right));
+        if (distinct && pair.0 == pair.1) || !seen.insert(pair) {
+            continue;
+        }
+        pairs.push(pair);
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_fixtures() {
+        let counts = Counts {
+            users: 5,
+            articles: 8,
+            comments: 10,
+            follows: 30,
+            favorites: 6,
+        };
+        let first = generate(7, counts);
+        let second = generate(7, counts);
+        assert_eq!(
+            first.users.iter().map(|x| &x.username).collect::<Vec<_>>(),
+            second.users.iter().map(|x| &x.username).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            first.articles.iter().map(|x| x.front_matter.render(&x.body)).collect::<Vec<_>>(),
+            second.articles.iter().map(|x| x.front_matter.render(&x.body)).collect::<Vec<_>>()
+        );
+        // Only 5 * 4 users can follow each other
+        assert_eq!(first.follows.len(), 20);
+        assert!(first.follows.iter().all(|x| x.follower != x.influencer));
+        assert_ne!(
+            generate(8, counts).users.iter().map(|x| &x.username).collect::<Vec<_>>(),
+            first.users.iter().map(|x| &x.username).collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,18 @@
+/// How often deactivated accounts are checked for the end of their grace period.
+const CHECK_INTERVAL_SECS: u64 = 3600;
+
+/// Deletes the accounts whose grace period is over every hour, for as long as the
+/// app runs.
+pub fn spawn_deletion_scheduler() {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(CHECK_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            match crate::models::User::delete_expired().await {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!("{deleted} accounts deleted"),
+                Err(x) => tracing::error!("problem while deleting accounts: {x:?}"),
+            }
+        }
+    });
+}
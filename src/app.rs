@@ -18,6 +18,7 @@ pub fn App() -> impl IntoView {
 #[component]
 fn AppContent() -> impl IntoView {
     provide_meta_context();
+    crate::components::provide_site_url();
 
     let auth = use_auth();
     let user_id = auth.user_id;
@@ -51,7 +52,13 @@ fn AppContent() -> impl IntoView {
         <Stylesheet id="main-css" href="https://demo.productionready.io/main.css"/>
         <Stylesheet id="app-css" href="/pkg/thedeveloper-leptos.css"/>
 
-        <Title text="Welcome to Leptos"/>
+        <Title formatter=|text: String| {
+            if text.is_empty() {
+                crate::components::SITE_NAME.to_string()
+            } else {
+                format!("{text} — {}", crate::components::SITE_NAME)
+            }
+        }/>
 
         <Router>
             // Navigation
@@ -91,10 +98,33 @@ fn AppContent() -> impl IntoView {
                     />
                     <Route 
                         path="/article/:slug" 
+                        ssr=SsrMode::Async
                         view=move || view! { <Article user_id=user_id/> }
                     />
+                    <Route 
+                        path="/notifications" 
+                        view=|| view! { <Notifications/> }
+                    />
+                    <Route 
+                        path="/reading-list" 
+                        view=move || view! { <ReadingList user_id=user_id/> }
+                    />
+                    <Route 
+                        path="/org/:slug" 
+                        ssr=SsrMode::Async
+                        view=move || view! { <OrganizationPage user_id=user_id/> }
+                    />
+                    <Route 
+                        path="/moderation" 
+                        view=|| view! { <Moderation/> }
+                    />
+                    <Route 
+                        path="/admin" 
+                        view=|| view! { <Admin/> }
+                    />
                     <Route 
                         path="/profile/:user_id" 
+                        ssr=SsrMode::Async
                         view=move || view! { <Profile user_id=user_id/> }
                     />
                 </Routes>
@@ -81,6 +81,29 @@ pub async fn authenticate(
         .verify_password(password.as_bytes(), &parsed_hash)
         .is_ok()
     {
+        match User::status(user.id).await {
+            Ok(crate::models::UserStatus::Active) => {}
+            Ok(crate::models::UserStatus::Suspended) => {
+                tracing::info!("Suspended user tried to log in: {}", email);
+                return Ok(super::LoginMessages::Unsuccessful("This account is suspended".into()));
+            }
+            // Coming back during the grace period cancels the deletion
+            Ok(crate::models::UserStatus::Deactivated) => {
+                if let Err(e) = User::reactivate(user.id).await {
+                    tracing::error!("Database error: {:?}", e);
+                    return Err(ServerFnError::ServerError("Server error".into()));
+                }
+                tracing::info!("Deletion of {} cancelled by logging in", email);
+            }
+            Ok(crate::models::UserStatus::Deleted) => {
+                return Ok(super::LoginMessages::Unsuccessful("Invalid credentials".into()));
+            }
+            Err(e) => {
+                tracing::error!("Database error: {:?}", e);
+                return Err(ServerFnError::ServerError("Server error".into()));
+            }
+        }
+
         let response_options = expect_context::<leptos_axum::ResponseOptions>();
         let token = crate::auth::server::generate_token(user.id);
         set_auth_cookie(&response_options, &token);
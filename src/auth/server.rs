@@ -8,6 +8,11 @@ use uuid::Uuid;
 use leptos::use_context;
 
 const TOKEN_EXPIRATION_SECS: i64 = 3600; // 1 hour in seconds
+const UNSUBSCRIBE_EXPIRATION_DAYS: i64 = 180;
+const UNSUBSCRIBE_PURPOSE: &str = "unsubscribe";
+/// How long the download link of a data export works, after which the archive goes.
+pub const EXPORT_EXPIRATION_DAYS: i64 = 7;
+const EXPORT_PURPOSE: &str = "export";
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TokenClaims {
@@ -17,11 +22,11 @@ pub struct TokenClaims {
 }
 
 #[tracing::instrument(skip_all)]
-pub async fn auth_middleware<B>(req: Request<B>, next: axum::middleware::Next<B>) -> Response {
+pub async fn auth_middleware<B>(mut req: Request<B>, next: axum::middleware::Next<B>) -> Response {
     let response = match get_user_id_from_headers(req.headers()) {
         Some(user_id) => {
-            match crate::models::User::get_by_id(user_id).await {
-                Ok(_) => {
+            match crate::models::User::status(user_id).await {
+                Ok(crate::models::UserStatus::Active) => {
                     let path = req.uri().path();
                     if path.starts_with("/login") || path.starts_with("/signup") {
                         add_security_headers(
@@ -35,6 +40,16 @@ pub async fn auth_middleware<B>(req: Request<B>, next: axum::middleware::Next<B>
                         add_security_headers(next.run(req).await)
                     }
                 }
+                Ok(status) => {
+                    // Only active accounts keep their session, the request goes on as anonymous
+                    tracing::info!("rejected the session of {user_id}, whose account is {}", status.as_str());
+                    remove_auth_cookie(req.headers_mut());
+                    let mut response = handle_unauthenticated(req, next).await;
+                    response
+                        .headers_mut()
+                        .append(header::SET_COOKIE, expired_auth_cookie().parse().unwrap());
+                    add_security_headers(response)
+                }
                 Err(e) => {
                     tracing::error!("Error fetching user: {:?}", e);
                     add_security_headers(handle_unauthenticated(req, next).await)
@@ -47,6 +62,37 @@ pub async fn auth_middleware<B>(req: Request<B>, next: axum::middleware::Next<B>
     response
 }
 
+/// Drops the session from the cookies of a request, leaving the others.
+fn remove_auth_cookie(headers: &mut axum::http::HeaderMap) {
+    let Some(cookies) = headers
+        .get(header::COOKIE)
+        .and_then(|x| x.to_str().ok())
+        .map(|x| {
+            x.split("; ")
+                .filter(|cookie| !cookie.starts_with(super::AUTH_TOKEN))
+                .collect::<Vec<_>>()
+                .join("; ")
+        })
+    else {
+        return;
+    };
+    match axum::http::HeaderValue::from_str(&cookies) {
+        Ok(cookies) if !cookies.is_empty() => {
+            headers.insert(header::COOKIE, cookies);
+        }
+        _ => {
+            headers.remove(header::COOKIE);
+        }
+    }
+}
+
+fn expired_auth_cookie() -> String {
+    format!(
+        "{}=; Path=/; Secure; HttpOnly; SameSite=Strict; Max-Age=0; Expires=Thu, 01 Jan 1970 00:00:00 GMT",
+        super::AUTH_TOKEN
+    )
+}
+
 fn add_security_headers(mut response: Response) -> Response {
     let headers = response.headers_mut();
     headers.insert(
@@ -72,17 +118,13 @@ async fn handle_unauthenticated<B>(req: Request<B>, next: axum::middleware::Next
     let path = req.uri().path();
     if path.starts_with("/settings") || 
        path.starts_with("/editor") || 
+       path.starts_with("/reading-list") || 
+       path.starts_with("/notifications") || 
        path.starts_with("/api/protected") {
         Response::builder()
             .status(StatusCode::FOUND)
             .header(header::LOCATION, "/login")
-            .header(
-                header::SET_COOKIE,
-                format!(
-                    "{}=; Path=/; Secure; HttpOnly; SameSite=Strict; Max-Age=0; Expires=Thu, 01 Jan 1970 00:00:00 GMT",
-                    super::AUTH_TOKEN
-                ),
-            )
+            .header(header::SET_COOKIE, expired_auth_cookie())
             .body(axum::body::boxed(axum::body::Empty::new()))
             .unwrap()
     } else {
@@ -125,6 +167,71 @@ pub fn decode_token(
     )
 }
 
+/// Claims of the links sent by email, which act on a user's behalf without a session.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LinkClaims {
+    pub sub: String,
+    pub exp: i64,
+    pub purpose: String,
+}
+
+fn generate_link_token(sub: Uuid, purpose: &str, valid_for: chrono::Duration) -> String {
+    let claims = LinkClaims {
+        sub: sub.to_string(),
+        exp: (chrono::Utc::now() + valid_for).timestamp(),
+        purpose: purpose.to_string(),
+    };
+
+    let secret = std::env!("JWT_SECRET");
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+    .expect("Token generation failed")
+}
+
+fn decode_link_token(token: &str, purpose: &str) -> Option<Uuid> {
+    let secret = std::env!("JWT_SECRET");
+    decode::<LinkClaims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::default(),
+    )
+    .ok()
+    .filter(|x| x.claims.purpose == purpose)
+    .and_then(|x| Uuid::parse_str(&x.claims.sub).ok())
+}
+
+#[tracing::instrument]
+pub fn generate_unsubscribe_token(user_id: Uuid) -> String {
+    generate_link_token(
+        user_id,
+        UNSUBSCRIBE_PURPOSE,
+        chrono::Duration::days(UNSUBSCRIBE_EXPIRATION_DAYS),
+    )
+}
+
+/// The user an unsubscribe link was issued for, if it is genuine and still valid.
+#[tracing::instrument(skip(token))]
+pub fn decode_unsubscribe_token(token: &str) -> Option<Uuid> {
+    decode_link_token(token, UNSUBSCRIBE_PURPOSE)
+}
+
+/// Token of the download link of a data export, valid for a week after the
+/// archive is ready.
+#[tracing::instrument]
+pub fn generate_export_token(export_id: Uuid, ready_at: chrono::DateTime<chrono::Utc>) -> String {
+    let valid_for = ready_at + chrono::Duration::days(EXPORT_EXPIRATION_DAYS) - chrono::Utc::now();
+    generate_link_token(export_id, EXPORT_PURPOSE, valid_for)
+}
+
+/// The data export a download link points to, if it is genuine and still valid.
+#[tracing::instrument(skip(token))]
+pub fn decode_export_token(token: &str) -> Option<Uuid> {
+    decode_link_token(token, EXPORT_PURPOSE)
+}
+
 #[tracing::instrument]
 pub fn get_user_id_from_headers(headers: &axum::http::HeaderMap) -> Option<Uuid> {
     headers
@@ -144,4 +251,62 @@ pub fn get_user_id_from_headers(headers: &axum::http::HeaderMap) -> Option<Uuid>
 pub fn get_user_id() -> Option<Uuid> {
     use_context::<leptos_axum::RequestParts>()
         .and_then(|req| get_user_id_from_headers(&req.headers))
-}
\ No newline at end of file
+}
+
+/// Role of the logged user, or `None` when nobody is logged in.
+#[tracing::instrument]
+pub async fn current_role() -> Option<crate::models::Role> {
+    let user_id = get_user_id()?;
+    crate::models::Role::of(user_id)
+        .await
+        .map_err(|x| tracing::error!("problem while reading the role of {user_id}: {x:?}"))
+        .ok()
+}
+
+/// Whether the logged user's role grants `permission`.
+pub async fn has_permission(permission: crate::models::Permission) -> bool {
+    current_role().await.is_some_and(|x| x.can(permission))
+}
+
+/// The logged user, as long as their role grants `permission`. Meant for guarding
+/// server functions with `?`.
+#[tracing::instrument]
+pub async fn require_permission(
+    permission: crate::models::Permission,
+) -> Result<Uuid, leptos::ServerFnError> {
+    let Some(user_id) = get_user_id() else {
+        return Err(leptos::ServerFnError::ServerError("You must be logged in".into()));
+    };
+    if has_permission(permission).await {
+        Ok(user_id)
+    } else {
+        tracing::warn!("{user_id} was denied {permission:?}");
+        Err(leptos::ServerFnError::ServerError(
+            "You are not allowed to do this".into(),
+        ))
+    }
+}
+
+/// Fails when `user_id` was blocked by `owner_id`, or by the author of the article or
+/// comment they are about to interact with.
+#[tracing::instrument]
+pub async fn require_not_blocked(
+    user_id: Uuid,
+    owner_id: Option<Uuid>,
+    article_id: Option<Uuid>,
+    comment_id: Option<Uuid>,
+) -> Result<(), leptos::ServerFnError> {
+    let blocked = crate::models::Relationship::is_blocked(user_id, owner_id, article_id, comment_id)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while checking blocks of {user_id}: {x:?}");
+            leptos::ServerFnError::ServerError("Server error".into())
+        })?;
+    if blocked {
+        Err(leptos::ServerFnError::ServerError(
+            "The author blocked you from interacting with their content".into(),
+        ))
+    } else {
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use leptos::*;
 use leptos_router::*;
-use super::buttons::{ButtonFav, ButtonFollow};
+use super::buttons::{ButtonBookmark, ButtonFollow, ButtonReactions};
 
 pub type ArticleSignal = RwSignal<crate::models::Article>;
 type ArticlesType<S, T = Result<Vec<crate::models::Article>, ServerFnError>> = Resource<S, T>;
@@ -70,11 +70,19 @@ pub fn ArticlePreviewList<S: 'static + std::clone::Clone>(
 }
 
 #[component]
-fn ArticlePreview(user_id: crate::auth::UserIdSignal, article: ArticleSignal) -> impl IntoView {
+pub fn ArticlePreview(user_id: crate::auth::UserIdSignal, article: ArticleSignal) -> impl IntoView {
     view! {
         <div class="article-preview">
             <ArticleMeta user_id=user_id article=article is_preview=true />
             <A href=move || format!("/article/{}", article.with(|x| x.slug.clone())) class="preview-link">
+                <Show when=move || article.with(|x| x.cover_image.is_some()) fallback=|| ()>
+                    <super::ResponsiveImage
+                        src=Signal::derive(move || article.with(|x| x.cover_image.clone().unwrap_or_default()))
+                        kind=crate::models::UploadKind::Cover
+                        sizes="(max-width: 768px) 100vw, 730px"
+                        class="cover-image"
+                    />
+                </Show>
                 <h1>{move || article.with(|x| x.title.to_string())}</h1>
                 <p>{move || article.with(|x| x.description.to_string())}</p>
                 <span class="btn">"Read more..."</span>
@@ -114,47 +122,75 @@ pub fn ArticleMeta(
     user_id: crate::auth::UserIdSignal,
     article: ArticleSignal,
     is_preview: bool,
+    /// Whether the logged user may hide or delete the article without owning it.
+    #[prop(optional)]
+    can_moderate: bool,
 ) -> impl IntoView {
     let editor_ref = move || format!("/editor/{}", article.with(|x| x.slug.to_string()));
     let profile_ref = move || format!("/profile/{}", article.with(|x| x.author.user_id.to_string()));
     let delete_a = create_server_action::<DeleteArticleAction>();
     
-    let is_author = move || {
+    let can_edit = move || {
+        user_id.get().is_some_and(|id| article.with(|x| x.is_editable_by(id)))
+    };
+    let is_owner = move || {
         user_id.get().map_or(false, |id| id == article.with(|x| x.author.user_id))
     };
 
     view! {
         <div class="article-meta">
             <A href=profile_ref>
-                <img 
-                    src=move || article.with(|x| x.author.image.clone().unwrap_or_default())
-                    alt=move || format!("{}'s avatar", article.with(|x| x.author.name.clone()))
+                <super::ResponsiveImage
+                    src=Signal::derive(move || article.with(|x| x.author.image.clone().unwrap_or_default()))
+                    kind=crate::models::UploadKind::Avatar
+                    sizes="32px"
+                    alt=Signal::derive(move || format!("{}'s avatar", article.with(|x| x.author.name.clone())))
                 />
             </A>
             <div class="info">
                 <A href=profile_ref class="author">
                     {move || article.with(|x| x.author.name.to_string())}
                 </A>
-                <span class="date">{move || article.with(|x| x.created_at.to_string())}</span>
+                <For
+                    each=move || article.with(|x| x.co_authors.clone())
+                    key=|x| x.user_id
+                    children=|x| view! {
+                        " & "
+                        <A href=format!("/profile/{}", x.user_id) class="author">{x.name}</A>
+                    }
+                />
+                {move || article.with(|x| x.organization.clone()).map(|x| view! {
+                    " for "
+                    <A href=format!("/org/{}", x.slug) class="author">{x.name}</A>
+                })}
+                <span class="date">
+                    {move || article.with(|x| x.created_at.to_string())}
+                    {move || article.with(|x| x.reading_time.map(|x| format!(" · {x} min read")))}
+                </span>
             </div>
             <Show
                 when=move || is_preview
                 fallback=move || {
                     view! {
                         <Show
-                            when=is_author
+                            when=can_edit
                             fallback=move || {
                                 let following = article.with(|x| x.author.following);
                                 let author_id = create_rw_signal(Some(article.with(|x| x.author.user_id))).read_only();
 
                                 view! {
                                     <Show when=move || user_id.with(Option::is_some) fallback=|| ()>
-                                        <ButtonFav user_id=user_id article=article />
+                                        <ButtonReactions user_id=user_id article=article />
+                                        <ButtonBookmark user_id=user_id article=article />
                                         <ButtonFollow
                                             logged_user_id=user_id
                                             author_id=author_id
                                             following=following
                                         />
+                                        <super::ReportButton
+                                            target=crate::models::ReportTarget::Article
+                                            target_id=article.with_untracked(|x| x.id)
+                                        />
                                     </Show>
                                 }
                             }
@@ -163,35 +199,72 @@ pub fn ArticleMeta(
                                 <i class="ion-compose"></i>
                                 " Edit article"
                             </A>
-                            <ActionForm action=delete_a class="inline">
-                                <input 
-                                    type="hidden" 
-                                    name="slug" 
-                                    value=move || article.with(|x| x.slug.to_string()) 
-                                />
-                                <button 
-                                    type="submit" 
-                                    class="btn btn-sm btn-outline-danger"
-                                    disabled=move || delete_a.pending().get()
-                                >
-                                    <i class="ion-trash-a"></i>
-                                    {move || if delete_a.pending().get() {
-                                        " Deleting..."
-                                    } else {
-                                        " Delete article"
-                                    }}
-                                </button>
-                            </ActionForm>
+                            <Show when=is_owner fallback=|| ()>
+                                <ActionForm action=delete_a class="inline">
+                                    <input 
+                                        type="hidden" 
+                                        name="slug" 
+                                        value=move || article.with(|x| x.slug.to_string()) 
+                                    />
+                                    <button 
+                                        type="submit" 
+                                        class="btn btn-sm btn-outline-danger"
+                                        disabled=move || delete_a.pending().get()
+                                    >
+                                        <i class="ion-trash-a"></i>
+                                        {move || if delete_a.pending().get() {
+                                            " Deleting..."
+                                        } else {
+                                            " Delete article"
+                                        }}
+                                    </button>
+                                </ActionForm>
+                            </Show>
+                        </Show>
+                        <Show when=move || can_moderate && !is_owner() fallback=|| ()>
+                            <ModerationButtons article/>
                         </Show>
                     }
                 }
             >
-                <ButtonFav user_id=user_id article=article />
+                <ButtonReactions user_id=user_id article=article />
             </Show>
         </div>
     }
 }
 
+/// Hide and delete buttons for moderators, on articles they may not own.
+#[component]
+fn ModerationButtons(article: ArticleSignal) -> impl IntoView {
+    let hide = create_server_action::<HideArticleAction>();
+    let delete = create_server_action::<DeleteArticleAction>();
+    let result_hide = hide.value();
+
+    create_effect(move |_| match result_hide.get() {
+        Some(Ok(hidden)) => article.update(|x| x.hidden = hidden),
+        Some(Err(err)) => tracing::error!("problem while hiding the article {err:?}"),
+        None => {}
+    });
+
+    view! {
+        <ActionForm action=hide class="inline">
+            <input type="hidden" name="slug" value=move || article.with(|x| x.slug.to_string())/>
+            <input type="hidden" name="hidden" value=move || (!article.with(|x| x.hidden)).to_string()/>
+            <button type="submit" class="btn btn-sm btn-outline-warning" disabled=hide.pending()>
+                <i class="ion-eye-disabled"></i>
+                {move || if article.with(|x| x.hidden) { " Unhide" } else { " Hide" }}
+            </button>
+        </ActionForm>
+        <ActionForm action=delete class="inline">
+            <input type="hidden" name="slug" value=move || article.with(|x| x.slug.to_string())/>
+            <button type="submit" class="btn btn-sm btn-outline-danger" disabled=delete.pending()>
+                <i class="ion-trash-a"></i>
+                " Delete as moderator"
+            </button>
+        </ActionForm>
+    }
+}
+
 #[server(DeleteArticleAction, "/api")]
 #[tracing::instrument]
 pub async fn delete_article(slug: String) -> Result<(), ServerFnError> {
@@ -200,12 +273,39 @@ pub async fn delete_article(slug: String) -> Result<(), ServerFnError> {
     };
     let redirect_profile = format!("/profile/{logged_user_id}");
 
-    crate::models::Article::delete(slug, logged_user_id)
+    let deleted = crate::models::Article::delete(slug.clone(), logged_user_id)
         .await
-        .map(move |_| leptos_axum::redirect(&redirect_profile))
         .map_err(|x| {
             let err = format!("Error while deleting an article: {x:?}");
             tracing::error!("{err}");
             ServerFnError::ServerError("Could not delete the article, try again later".into())
+        })?
+        .rows_affected();
+
+    if deleted == 0 && crate::auth::has_permission(crate::models::Permission::ModerateContent).await {
+        crate::models::Article::delete_as_moderator(&slug, logged_user_id)
+            .await
+            .map_err(|x| {
+                tracing::error!("problem while deleting {slug} as a moderator: {x:?}");
+                ServerFnError::ServerError("Could not delete the article, try again later".into())
+            })?;
+        leptos_axum::redirect("/");
+        return Ok(());
+    }
+    leptos_axum::redirect(&redirect_profile);
+    Ok(())
+}
+
+#[server(HideArticleAction, "/api")]
+#[tracing::instrument]
+pub async fn hide_article(slug: String, hidden: bool) -> Result<bool, ServerFnError> {
+    let moderator_id = crate::auth::require_permission(crate::models::Permission::ModerateContent).await?;
+
+    crate::models::Article::set_hidden(&slug, moderator_id, hidden)
+        .await
+        .map(|_| hidden)
+        .map_err(|x| {
+            tracing::error!("problem while hiding {slug}: {x:?}");
+            ServerFnError::ServerError("Could not hide the article, try again later".into())
         })
 }
\ No newline at end of file
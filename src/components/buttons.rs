@@ -9,10 +9,23 @@ pub async fn follow_action(other_user_id: uuid::Uuid) -> Result<bool, ServerFnEr
             "You need to be authenticated".into(),
         ));
     };
-    toggle_follow(user_id, other_user_id).await.map_err(|x| {
+    crate::auth::require_not_blocked(user_id, Some(other_user_id), None, None).await?;
+    let following = toggle_follow(user_id, other_user_id).await.map_err(|x| {
         tracing::error!("problem while updating the database: {x:?}");
         ServerFnError::ServerError("error while updating the follow".into())
-    })
+    })?;
+    if following {
+        if let Err(x) = crate::models::Notification::for_user(
+            other_user_id,
+            user_id,
+            crate::models::NotificationKind::Follow,
+        )
+        .await
+        {
+            tracing::error!("problem while notifying a follow: {x:?}");
+        }
+    }
+    Ok(following)
 }
 
 #[cfg(feature = "ssr")]
@@ -114,6 +127,147 @@ pub fn ButtonFollow(
     }
 }
 
+#[server(FollowOrganizationAction, "/api")]
+#[tracing::instrument]
+pub async fn follow_organization_action(organization_id: uuid::Uuid) -> Result<bool, ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError(
+            "You need to be authenticated".into(),
+        ));
+    };
+    crate::models::Organization::toggle_follow(organization_id, user_id)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while updating the database: {x:?}");
+            ServerFnError::ServerError("error while updating the follow".into())
+        })
+}
+
+#[component]
+pub fn ButtonFollowOrganization(
+    logged_user_id: crate::auth::UserIdSignal,
+    organization_id: uuid::Uuid,
+    following: bool,
+) -> impl IntoView {
+    let follow = create_server_action::<FollowOrganizationAction>();
+    let result_call = follow.value();
+    let is_loading = follow.pending();
+
+    let follow_cond = move || match result_call.get() {
+        Some(Ok(x)) => x,
+        Some(Err(err)) => {
+            tracing::error!("problem while following {err:?}");
+            following
+        }
+        None => following,
+    };
+
+    view! {
+        <Show when=move || logged_user_id.with(Option::is_some) fallback=|| ()>
+            <ActionForm action=follow class="inline pull-xs-right">
+                <input type="hidden" name="organization_id" value=organization_id.to_string()/>
+                <button
+                    type="submit"
+                    class="btn btn-sm btn-outline-secondary"
+                    disabled=is_loading
+                >
+                    <Show
+                        when=move || is_loading.get()
+                        fallback=move || {
+                            view! {
+                                <Show
+                                    when=follow_cond
+                                    fallback=|| view!{<i class="ion-plus-round"></i>" Follow"}
+                                >
+                                    <i class="ion-close-round"></i>" Unfollow"
+                                </Show>
+                            }
+                        }
+                    >
+                        "Loading..."
+                    </Show>
+                </button>
+            </ActionForm>
+        </Show>
+    }
+}
+
+#[server(BlockAction, "/api")]
+#[tracing::instrument]
+pub async fn block_action(other_user_id: uuid::Uuid) -> Result<bool, ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError(
+            "You need to be authenticated".into(),
+        ));
+    };
+    if user_id == other_user_id {
+        return Err(ServerFnError::ServerError("You cannot block yourself".into()));
+    }
+    crate::models::Relationship::toggle_block(user_id, other_user_id)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while updating the database: {x:?}");
+            ServerFnError::ServerError("error while updating the block".into())
+        })
+}
+
+#[server(MuteAction, "/api")]
+#[tracing::instrument]
+pub async fn mute_action(other_user_id: uuid::Uuid) -> Result<bool, ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError(
+            "You need to be authenticated".into(),
+        ));
+    };
+    if user_id == other_user_id {
+        return Err(ServerFnError::ServerError("You cannot mute yourself".into()));
+    }
+    crate::models::Relationship::toggle_mute(user_id, other_user_id)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while updating the database: {x:?}");
+            ServerFnError::ServerError("error while updating the mute".into())
+        })
+}
+
+/// Block and mute toggles for a profile other than the logged user's.
+#[component]
+pub fn ButtonsBlockMute(
+    other_user_id: uuid::Uuid,
+    relationship: crate::models::Relationship,
+) -> impl IntoView {
+    let block = create_server_action::<BlockAction>();
+    let mute = create_server_action::<MuteAction>();
+    let block_result = block.value();
+    let mute_result = mute.value();
+
+    let blocked = move || match block_result.get() {
+        Some(Ok(x)) => x,
+        _ => relationship.blocked,
+    };
+    let muted = move || match mute_result.get() {
+        Some(Ok(x)) => x,
+        _ => relationship.muted,
+    };
+
+    view! {
+        <ActionForm action=mute class="inline pull-xs-right">
+            <input type="hidden" name="other_user_id" value=other_user_id.to_string()/>
+            <button type="submit" class="btn btn-sm btn-outline-secondary" disabled=mute.pending()>
+                <i class="ion-volume-mute"></i>
+                {move || if muted() { " Unmute" } else { " Mute" }}
+            </button>
+        </ActionForm>
+        <ActionForm action=block class="inline pull-xs-right">
+            <input type="hidden" name="other_user_id" value=other_user_id.to_string()/>
+            <button type="submit" class="btn btn-sm btn-outline-danger" disabled=block.pending()>
+                <i class="ion-android-hand"></i>
+                {move || if blocked() { " Unblock" } else { " Block" }}
+            </button>
+        </ActionForm>
+    }
+}
+
 #[server(FavAction, "/api")]
 #[tracing::instrument]
 pub async fn fav_action(article_id: uuid::Uuid) -> Result<bool, ServerFnError> {
@@ -124,10 +278,49 @@ pub async fn fav_action(article_id: uuid::Uuid) -> Result<bool, ServerFnError> {
             "You need to be authenticated".into(),
         ));
     };
-    toggle_fav(article_id, user_id).await.map_err(|x| {
+    crate::auth::require_not_blocked(user_id, None, Some(article_id), None).await?;
+    let fav = toggle_fav(article_id, user_id).await.map_err(|x| {
         tracing::error!("problem while updating the database: {x:?}");
         ServerFnError::ServerError("error while updating the favorite".into())
-    })
+    })?;
+    publish_reactions(article_id).await;
+    Ok(fav)
+}
+
+/// Pushes the new reaction totals of an article to everyone reading it.
+#[cfg(feature = "ssr")]
+async fn publish_reactions(article_id: uuid::Uuid) {
+    match crate::models::ReactionCount::totals_for_article(article_id).await {
+        Ok(totals) => {
+            crate::realtime::publish(
+                crate::realtime::Topic::Article(article_id),
+                crate::realtime::RealtimeEvent::ReactionsChanged {
+                    totals,
+                    comment_id: None,
+                },
+            )
+            .await;
+        }
+        Err(x) => tracing::error!("problem while counting reactions: {x:?}"),
+    }
+}
+
+/// Pushes the new reaction totals of a comment to everyone reading its article.
+#[cfg(feature = "ssr")]
+async fn publish_comment_reactions(comment_id: uuid::Uuid) {
+    match crate::models::ReactionCount::totals_for_comment(comment_id).await {
+        Ok((article_id, totals)) => {
+            crate::realtime::publish(
+                crate::realtime::Topic::Article(article_id),
+                crate::realtime::RealtimeEvent::ReactionsChanged {
+                    totals,
+                    comment_id: Some(comment_id),
+                },
+            )
+            .await;
+        }
+        Err(x) => tracing::error!("problem while counting comment reactions: {x:?}"),
+    }
 }
 
 #[cfg(feature = "ssr")]
@@ -150,64 +343,264 @@ async fn toggle_fav(article_id: uuid::Uuid, user_id: uuid::Uuid) -> Result<bool,
         .execute(db)
         .await
         .map(|_| false),
-        Err(sqlx::error::Error::RowNotFound) => sqlx::query!(
-            "INSERT INTO FavArticles(article_id, user_id) VALUES ($1, $2)",
-            article_id,
-            user_id
-        )
-        .execute(db)
-        .await
-        .map(|_| true),
+        Err(sqlx::error::Error::RowNotFound) => {
+            sqlx::query!(
+                "INSERT INTO FavArticles(article_id, user_id) VALUES ($1, $2)",
+                article_id,
+                user_id
+            )
+            .execute(db)
+            .await?;
+            if let Err(x) = crate::models::Notification::for_article_author(
+                article_id,
+                user_id,
+                crate::models::NotificationKind::Favorite,
+            )
+            .await
+            {
+                tracing::error!("problem while notifying a favorite: {x:?}");
+            }
+            Ok(true)
+        }
         Err(x) => Err(x),
     }
 }
 
+#[server(ReactAction, "/api")]
+#[tracing::instrument]
+pub async fn react_action(
+    kind: crate::models::Reaction,
+    article_id: Option<uuid::Uuid>,
+    comment_id: Option<uuid::Uuid>,
+) -> Result<bool, ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError(
+            "You need to be authenticated".into(),
+        ));
+    };
+    crate::auth::require_not_blocked(user_id, None, article_id, comment_id).await?;
+    let result = match (kind, article_id, comment_id) {
+        (crate::models::Reaction::Favorite, Some(article_id), None) => {
+            toggle_fav(article_id, user_id).await
+        }
+        (crate::models::Reaction::Favorite, _, _) => {
+            return Err(ServerFnError::ServerError(
+                "Only articles can be favorited".into(),
+            ));
+        }
+        (kind, Some(_), None) | (kind, None, Some(_)) => {
+            kind.toggle(user_id, article_id, comment_id).await
+        }
+        _ => {
+            return Err(ServerFnError::ServerError(
+                "A reaction needs either an article or a comment".into(),
+            ));
+        }
+    };
+    let reacted = result.map_err(|x| {
+        tracing::error!("problem while updating the database: {x:?}");
+        ServerFnError::ServerError("error while updating the reaction".into())
+    })?;
+    if let Some(article_id) = article_id {
+        publish_reactions(article_id).await;
+    }
+    if let Some(comment_id) = comment_id {
+        publish_comment_reactions(comment_id).await;
+    }
+    Ok(reacted)
+}
+
+/// What a set of reaction buttons reacts to.
+#[derive(Clone, Copy, Debug)]
+pub enum ReactionTarget {
+    Article(uuid::Uuid),
+    Comment(uuid::Uuid),
+}
+
+impl ReactionTarget {
+    fn field(self) -> &'static str {
+        match self {
+            ReactionTarget::Article(_) => "article_id",
+            ReactionTarget::Comment(_) => "comment_id",
+        }
+    }
+
+    fn id(self) -> uuid::Uuid {
+        match self {
+            ReactionTarget::Article(id) | ReactionTarget::Comment(id) => id,
+        }
+    }
+
+    fn accepts(self, kind: crate::models::Reaction) -> bool {
+        matches!(self, ReactionTarget::Article(_)) || kind.applies_to_comments()
+    }
+}
+
 #[component]
-pub fn ButtonFav(
+pub fn ButtonReactions(
     user_id: crate::auth::UserIdSignal,
     article: super::article_preview::ArticleSignal,
 ) -> impl IntoView {
-    let make_fav = create_server_action::<FavAction>();
-    let result_make_fav = make_fav.value();
-    let is_loading = make_fav.pending();
-    
-    let fav_count = move || {
-        if let Some(x) = result_make_fav.get() {
-            match x {
-                Ok(result) => {
-                    article.update(move |x| {
-                        x.fav = !x.fav;
-                        x.favorites_count =
-                            (x.favorites_count + if result { 1 } else { -1 }).max(0);
-                    });
-                }
-                Err(err) => {
-                    tracing::error!("problem while favoriting {err:?}");
-                }
-            }
-        }
-        article.with(|x| x.favorites_count)
-    };
+    view! {
+        <ReactionBar
+            user_id
+            target=ReactionTarget::Article(article.with_untracked(|x| x.id))
+            reactions=Signal::derive(move || article.with(crate::models::Article::reaction_counts))
+            on_toggle=Callback::new(move |(kind, reacted)| {
+                article.update(|x| x.apply_reaction(kind, reacted));
+            })
+        />
+    }
+}
+
+/// Latest reaction totals pushed for a comment of the article being read.
+#[derive(Clone, Copy)]
+pub struct CommentReactionTotals(pub RwSignal<Option<(uuid::Uuid, Vec<crate::models::ReactionCount>)>>);
+
+#[component]
+pub fn CommentReactions(
+    user_id: crate::auth::UserIdSignal,
+    comment: RwSignal<crate::models::Comment>,
+) -> impl IntoView {
+    if let Some(CommentReactionTotals(pushed)) = use_context() {
+        let id = comment.with_untracked(|x| x.id);
+        create_effect(move |_| {
+            pushed.with(|x| match x {
+                Some((comment_id, totals)) if *comment_id == id => comment.update(|x| {
+                    crate::models::ReactionCount::apply_totals(&mut x.reactions, totals)
+                }),
+                _ => {}
+            })
+        });
+    }
+
+    view! {
+        <ReactionBar
+            user_id
+            target=ReactionTarget::Comment(comment.with_untracked(|x| x.id))
+            reactions=Signal::derive(move || comment.with(|x| x.reactions.clone()))
+            on_toggle=Callback::new(move |(kind, reacted)| {
+                comment.update(|x| crate::models::ReactionCount::apply(&mut x.reactions, kind, reacted));
+            })
+        />
+    }
+}
+
+#[component]
+fn ReactionBar(
+    user_id: crate::auth::UserIdSignal,
+    target: ReactionTarget,
+    reactions: Signal<Vec<crate::models::ReactionCount>>,
+    on_toggle: Callback<(crate::models::Reaction, bool)>,
+) -> impl IntoView {
+    view! {
+        <div class="reactions inline pull-xs-right">
+            {crate::models::Reaction::ALL
+                .into_iter()
+                .filter(move |kind| target.accepts(*kind))
+                .map(|kind| view! { <ButtonReaction user_id target kind reactions on_toggle /> })
+                .collect_view()}
+        </div>
+    }
+}
+
+#[component]
+fn ButtonReaction(
+    user_id: crate::auth::UserIdSignal,
+    target: ReactionTarget,
+    kind: crate::models::Reaction,
+    reactions: Signal<Vec<crate::models::ReactionCount>>,
+    on_toggle: Callback<(crate::models::Reaction, bool)>,
+) -> impl IntoView {
+    let react = create_server_action::<ReactAction>();
+    let result_react = react.value();
+    let is_loading = react.pending();
+
+    create_effect(move |_| match result_react.get() {
+        Some(Ok(reacted)) => on_toggle.call((kind, reacted)),
+        Some(Err(err)) => tracing::error!("problem while reacting {err:?}"),
+        None => {}
+    });
+
+    let count = move || reactions.with(|x| crate::models::ReactionCount::find(x, kind).0);
+    let reacted = move || reactions.with(|x| crate::models::ReactionCount::find(x, kind).1);
 
     view! {
         <Show
             when=move || user_id.with(Option::is_some)
             fallback=move || view!{
-                <button class="btn btn-sm btn-outline-primary pull-xs-right">
-                    <i class="ion-heart"></i>
-                    <span class="counter">" ("{fav_count}")"</span>
+                <button class="btn btn-sm btn-outline-primary" title=kind.label()>
+                    <i class=kind.icon()></i>
+                    <span class="counter">" ("{count}")"</span>
                 </button>
             }
         >
-            <ActionForm action=make_fav class="inline pull-xs-right">
-                <input 
-                    type="hidden" 
-                    name="article_id" 
-                    value=move || article.with(|x| x.id.to_string()) 
+            <ActionForm action=react class="inline">
+                <input type="hidden" name="kind" value=kind.as_str() />
+                <input type="hidden" name=target.field() value=target.id().to_string() />
+                <button
+                    type="submit"
+                    class="btn btn-sm"
+                    class:btn-primary=reacted
+                    class:btn-outline-primary=move || !reacted()
+                    title=kind.label()
+                    disabled=is_loading
+                >
+                    <Show
+                        when=move || is_loading.get()
+                        fallback=move || view!{<i class=kind.icon()></i>" "{kind.label()}" "}
+                    >
+                        "Loading..."
+                    </Show>
+                    <span class="counter">"("{count}")"</span>
+                </button>
+            </ActionForm>
+        </Show>
+    }
+}
+
+#[server(BookmarkAction, "/api")]
+#[tracing::instrument]
+pub async fn bookmark_action(article_id: uuid::Uuid) -> Result<bool, ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError(
+            "You need to be authenticated".into(),
+        ));
+    };
+    crate::models::Article::toggle_bookmark(article_id, user_id)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while updating the database: {x:?}");
+            ServerFnError::ServerError("error while updating the reading list".into())
+        })
+}
+
+#[component]
+pub fn ButtonBookmark(
+    user_id: crate::auth::UserIdSignal,
+    article: super::article_preview::ArticleSignal,
+) -> impl IntoView {
+    let bookmark = create_server_action::<BookmarkAction>();
+    let result_bookmark = bookmark.value();
+    let is_loading = bookmark.pending();
+
+    create_effect(move |_| match result_bookmark.get() {
+        Some(Ok(bookmarked)) => article.update(|x| x.bookmarked = bookmarked),
+        Some(Err(err)) => tracing::error!("problem while bookmarking {err:?}"),
+        None => {}
+    });
+
+    view! {
+        <Show when=move || user_id.with(Option::is_some) fallback=|| ()>
+            <ActionForm action=bookmark class="inline pull-xs-right">
+                <input
+                    type="hidden"
+                    name="article_id"
+                    value=move || article.with(|x| x.id.to_string())
                 />
-                <button 
-                    type="submit" 
-                    class="btn btn-sm btn-outline-primary"
+                <button
+                    type="submit"
+                    class="btn btn-sm btn-outline-secondary"
                     disabled=is_loading
                 >
                     <Show
@@ -215,19 +608,18 @@ pub fn ButtonFav(
                         fallback=move || {
                             view! {
                                 <Show
-                                    when=move || article.with(|x| x.fav)
-                                    fallback=move || view!{<i class="ion-heart"></i>" Fav "}
+                                    when=move || article.with(|x| x.bookmarked)
+                                    fallback=|| view!{<i class="ion-bookmark"></i>" Save for later "}
                                 >
-                                    <i class="ion-heart-broken"></i>" Unfav "
+                                    <i class="ion-close-round"></i>" Remove from reading list "
                                 </Show>
                             }
                         }
                     >
                         "Loading..."
                     </Show>
-                    <span class="counter">"("{fav_count}")"</span>
                 </button>
             </ActionForm>
         </Show>
     }
-}
\ No newline at end of file
+}
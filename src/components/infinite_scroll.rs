@@ -0,0 +1,91 @@
+use leptos::*;
+
+/// Distance below the viewport at which the sentinel already counts as visible,
+/// so the next page starts loading before the reader reaches the end of the list.
+#[cfg(feature = "hydrate")]
+const ROOT_MARGIN: &str = "400px";
+
+/// Invisible marker placed after a list. Calls `on_visible` every time it
+/// scrolls into view while `active` is true, and again as long as it stays in
+/// view.
+#[component]
+pub fn ScrollSentinel(
+    on_visible: Callback<()>,
+    #[prop(into)] active: Signal<bool>,
+) -> impl IntoView {
+    let sentinel = create_node_ref::<html::Div>();
+
+    #[cfg(feature = "hydrate")]
+    {
+        use wasm_bindgen::{closure::Closure, JsCast};
+
+        let observer = store_value(None::<web_sys::IntersectionObserver>);
+        // An observer only reports changes, so when the sentinel is still in view
+        // after the list grew, observing it again is what tells so
+        let check_again = move || {
+            if let (Some(observer), Some(element)) = (observer.get_value(), sentinel.get_untracked()) {
+                observer.unobserve(&element);
+                observer.observe(&element);
+            }
+        };
+
+        sentinel.on_load(move |element| {
+            let callback = Closure::<dyn FnMut(js_sys::Array)>::new(move |entries: js_sys::Array| {
+                let visible = entries.iter().any(|entry| {
+                    entry
+                        .unchecked_into::<web_sys::IntersectionObserverEntry>()
+                        .is_intersecting()
+                });
+                if visible && active.get_untracked() {
+                    on_visible.call(());
+                    request_animation_frame(check_again);
+                }
+            });
+
+            let options = web_sys::IntersectionObserverInit::new();
+            options.set_root_margin(ROOT_MARGIN);
+
+            match web_sys::IntersectionObserver::new_with_options(
+                callback.as_ref().unchecked_ref(),
+                &options,
+            ) {
+                Ok(created) => {
+                    created.observe(&element);
+                    observer.set_value(Some(created.clone()));
+                    on_cleanup(move || {
+                        created.disconnect();
+                        drop(callback);
+                    });
+                }
+                Err(err) => tracing::error!("could not create the IntersectionObserver: {err:?}"),
+            }
+        });
+
+        // Loading may end with the sentinel still in view
+        create_effect(move |_| {
+            if active.get() {
+                check_again();
+            }
+        });
+    }
+
+    #[cfg(not(feature = "hydrate"))]
+    let _ = (on_visible, active, sentinel);
+
+    view! { <div class="scroll-sentinel" node_ref=sentinel></div> }
+}
+
+/// Rewrites the current history entry without notifying the router, so the
+/// address bar follows the reader without refetching what is already rendered.
+pub fn replace_url(url: &str) {
+    #[cfg(feature = "hydrate")]
+    if let Err(err) = window()
+        .history()
+        .and_then(|history| history.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(url)))
+    {
+        tracing::error!("could not replace the history entry: {err:?}");
+    }
+
+    #[cfg(not(feature = "hydrate"))]
+    let _ = url;
+}
@@ -1,6 +1,20 @@
 mod article_preview;
 mod buttons;
+mod infinite_scroll;
 mod navitems;
-pub(crate) use article_preview::{ArticleMeta, ArticlePreviewList, ArticleSignal};
-pub(crate) use buttons::{ButtonFollow};
+mod report;
+mod responsive_image;
+mod seo;
+#[cfg(feature = "hydrate")]
+mod upload;
+pub(crate) use article_preview::{ArticleMeta, ArticlePreview, ArticlePreviewList, ArticleSignal};
+pub(crate) use buttons::{
+    ButtonFollow, ButtonFollowOrganization, ButtonsBlockMute, CommentReactionTotals, CommentReactions,
+};
+pub(crate) use infinite_scroll::{replace_url, ScrollSentinel};
 pub(crate) use navitems::NavItems;
+pub(crate) use report::ReportButton;
+pub(crate) use responsive_image::ResponsiveImage;
+pub(crate) use seo::{provide_site_url, site_url, JsonLd, Seo, SITE_DESCRIPTION, SITE_NAME};
+#[cfg(feature = "hydrate")]
+pub(crate) use upload::upload_image;
@@ -31,6 +31,42 @@ pub(crate) fn NavItems(user_id: UserIdSignal) -> impl IntoView {
 
     let is_logged_in = move || auth_state.get().is_some();
 
+    // Refreshed on every navigation, so reading notifications clears the badge
+    let location = use_location();
+    let unread = create_resource(
+        move || (auth_state.get(), location.pathname.get()),
+        |(id, _)| async move {
+            if id.is_some() {
+                crate::routes::unread_notifications().await.unwrap_or_default()
+            } else {
+                0
+            }
+        },
+    );
+
+    let role = create_resource(
+        move || auth_state.get(),
+        |id| async move {
+            if id.is_some() {
+                crate::routes::my_role().await.unwrap_or_default()
+            } else {
+                crate::models::Role::User
+            }
+        },
+    );
+    let can = move |permission| role.get().is_some_and(|x| x.can(permission));
+
+    create_effect(move |_| {
+        if auth_state.get().is_some() {
+            #[cfg(feature = "hydrate")]
+            crate::realtime::subscribe(None, move |message| {
+                if matches!(message.event, crate::realtime::RealtimeEvent::NotificationsChanged) {
+                    unread.refetch();
+                }
+            });
+        }
+    });
+
     view! {
         <li class="nav-item">
             <A class="nav-link" href="/" exact=true>
@@ -67,6 +103,40 @@ pub(crate) fn NavItems(user_id: UserIdSignal) -> impl IntoView {
                         " New Article"
                     </A>
                 </li>
+                <li class="nav-item">
+                    <A class="nav-link" href="/notifications">
+                        <i class="ion-android-notifications"></i>
+                        <Transition fallback=|| ()>
+                            {move || unread.get().filter(|x| *x > 0).map(|x| view! {
+                                <span class="tag-pill tag-default notification-count">{x}</span>
+                            })}
+                        </Transition>
+                    </A>
+                </li>
+                <li class="nav-item">
+                    <A class="nav-link" href="/reading-list">
+                        <i class="ion-bookmark"></i>
+                        " Reading list"
+                    </A>
+                </li>
+                <Transition fallback=|| ()>
+                    {move || can(crate::models::Permission::ModerateContent).then(|| view! {
+                        <li class="nav-item">
+                            <A class="nav-link" href="/moderation">
+                                <i class="ion-flag"></i>
+                                " Moderation"
+                            </A>
+                        </li>
+                    })}
+                    {move || can(crate::models::Permission::Administer).then(|| view! {
+                        <li class="nav-item">
+                            <A class="nav-link" href="/admin">
+                                <i class="ion-settings"></i>
+                                " Admin"
+                            </A>
+                        </li>
+                    })}
+                </Transition>
                 <li class="nav-item">
                     <A class="nav-link" href="/settings">
                         <i class="ion-gear-a"></i>
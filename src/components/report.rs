@@ -0,0 +1,78 @@
+use leptos::*;
+use leptos_router::*;
+
+#[server(ReportAction, "/api")]
+#[tracing::instrument]
+pub async fn report(
+    target: crate::models::ReportTarget,
+    target_id: uuid::Uuid,
+    reason: crate::models::ReportReason,
+    details: String,
+) -> Result<(), ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError("You must be logged in".into()));
+    };
+
+    let hidden = crate::models::Report::file(user_id, target, target_id, reason, details.trim())
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while reporting the {} {target_id}: {x:?}", target.as_str());
+            ServerFnError::ServerError("Could not send the report, try again later".into())
+        })?;
+    if hidden {
+        tracing::info!("{} {target_id} was hidden after being reported", target.as_str());
+    }
+    Ok(())
+}
+
+/// Lets the logged user flag something for the moderators, with a reason.
+#[component]
+pub fn ReportButton(target: crate::models::ReportTarget, target_id: uuid::Uuid) -> impl IntoView {
+    let report = create_server_action::<ReportAction>();
+    let result = report.value();
+    let open = create_rw_signal(false);
+
+    create_effect(move |_| {
+        if let Some(Ok(())) = result.get() {
+            open.set(false);
+        }
+    });
+
+    view! {
+        <span class="report">
+            {move || match result.get() {
+                Some(Ok(())) => view! { <span class="text-muted">"Reported, thanks"</span> }.into_view(),
+                _ => view! {
+                    <button class="btn btn-sm btn-link" on:click=move |_| open.update(|x| *x = !*x)>
+                        <i class="ion-flag"></i>
+                        {move || if open.get() { " Cancel" } else { " Report" }}
+                    </button>
+                }.into_view(),
+            }}
+            <Show when=move || open.get() fallback=|| ()>
+                <ActionForm action=report class="report-form">
+                    <input type="hidden" name="target" value=target.as_str()/>
+                    <input type="hidden" name="target_id" value=target_id.to_string()/>
+                    <select name="reason" class="form-control form-control-sm">
+                        {crate::models::ReportReason::ALL
+                            .into_iter()
+                            .map(|x| view! { <option value=x.as_str()>{x.label()}</option> })
+                            .collect_view()}
+                    </select>
+                    <textarea
+                        name="details"
+                        class="form-control form-control-sm"
+                        rows="2"
+                        placeholder="Anything moderators should know?"
+                    ></textarea>
+                    <button type="submit" class="btn btn-sm btn-outline-danger" disabled=report.pending()>
+                        "Send report"
+                    </button>
+                    {move || result.get().and_then(Result::err).map(|x| view! {
+                        <span class="error-messages">{x.to_string()}</span>
+                    })}
+                </ActionForm>
+            </Show>
+        </span>
+    }
+}
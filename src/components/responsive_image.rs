@@ -0,0 +1,34 @@
+use leptos::*;
+
+use crate::models::{srcset, UploadKind, VARIANT_FORMATS};
+
+/// An image that lets the browser pick a smaller or more modern variant when `src`
+/// was uploaded here, and falls back to `src` as is otherwise.
+#[component]
+pub(crate) fn ResponsiveImage(
+    #[prop(into)] src: MaybeSignal<String>,
+    kind: UploadKind,
+    /// Displayed width, as in the `sizes` attribute.
+    sizes: &'static str,
+    #[prop(into, optional)] alt: MaybeSignal<String>,
+    #[prop(optional)] class: &'static str,
+) -> impl IntoView {
+    let fallback = src.clone();
+
+    view! {
+        <picture>
+            {move || {
+                let src = src.get();
+                VARIANT_FORMATS
+                    .into_iter()
+                    .filter_map(|(extension, content_type)| {
+                        srcset(&src, kind, extension).map(|srcset| view! {
+                            <source type=content_type srcset=srcset sizes=sizes/>
+                        })
+                    })
+                    .collect_view()
+            }}
+            <img src=move || fallback.get() alt=move || alt.get() class=class/>
+        </picture>
+    }
+}
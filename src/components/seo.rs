@@ -0,0 +1,88 @@
+use leptos::*;
+use leptos_meta::*;
+
+pub(crate) const SITE_NAME: &str = "thedeveloper";
+pub(crate) const SITE_DESCRIPTION: &str = "Empowering Developers, One Line of Code at a Time.";
+
+/// `SITE_URL` as the server read it, shared with the browser so both render the
+/// same tags.
+#[derive(Clone, Copy)]
+struct SiteUrl(Resource<(), String>);
+
+/// Provides the absolute URL of the site to every page. The server sends its value
+/// along with the page, the browser only falls back to its own origin without it.
+pub(crate) fn provide_site_url() {
+    #[cfg(feature = "ssr")]
+    let initial = Some(crate::site::url());
+    #[cfg(not(feature = "ssr"))]
+    let initial = None;
+    let url = create_resource_with_initial_value(
+        || (),
+        |_| async {
+            #[cfg(feature = "ssr")]
+            return crate::site::url();
+            #[cfg(not(feature = "ssr"))]
+            return window().location().origin().unwrap_or_default();
+        },
+        initial,
+    );
+    provide_context(SiteUrl(url));
+}
+
+/// Absolute URL of the site.
+pub(crate) fn site_url() -> String {
+    use_context::<SiteUrl>()
+        .and_then(|x| x.0.get())
+        .unwrap_or_default()
+}
+
+/// Description, canonical link, Open Graph and Twitter card tags of a page.
+/// `path` is relative to the site, `image` may be either.
+#[component]
+pub(crate) fn Seo(
+    #[prop(into)] title: String,
+    #[prop(into)] description: String,
+    #[prop(into)] path: String,
+    #[prop(default = None)] image: Option<String>,
+    #[prop(optional)] is_article: bool,
+) -> impl IntoView {
+    let site_url = site_url();
+    let url = format!("{site_url}{path}");
+    let image = image.map(|x| {
+        if x.starts_with('/') {
+            format!("{site_url}{x}")
+        } else {
+            x
+        }
+    });
+    let card = if image.is_some() {
+        "summary_large_image"
+    } else {
+        "summary"
+    };
+
+    view! {
+        <Meta name="description" content=description.clone()/>
+        <Link rel="canonical" href=url.clone()/>
+        <Meta property="og:site_name" content=SITE_NAME/>
+        <Meta property="og:type" content=if is_article { "article" } else { "website" }/>
+        <Meta property="og:title" content=title.clone()/>
+        <Meta property="og:description" content=description.clone()/>
+        <Meta property="og:url" content=url/>
+        <Meta name="twitter:card" content=card/>
+        <Meta name="twitter:title" content=title/>
+        <Meta name="twitter:description" content=description/>
+        {image.map(|x| view! {
+            <Meta property="og:image" content=x.clone()/>
+            <Meta name="twitter:image" content=x/>
+        })}
+    }
+}
+
+/// Embeds `data` as JSON-LD structured data.
+#[component]
+pub(crate) fn JsonLd(data: serde_json::Value) -> impl IntoView {
+    // `</script>` inside a string would end the element early
+    let json = data.to_string().replace("</", "<\\/");
+    view! { <script type="application/ld+json" inner_html=json></script> }
+}
@@ -0,0 +1,37 @@
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+/// Sends an image to the upload endpoint, returning where it is now served from
+/// or a message meant for the user.
+pub(crate) async fn upload_image(
+    file: web_sys::File,
+    kind: crate::models::UploadKind,
+) -> Result<String, String> {
+    let failed = |_| "Could not upload the image, try again later".to_string();
+
+    let form = web_sys::FormData::new().map_err(failed)?;
+    form.append_with_blob("file", &file).map_err(failed)?;
+    let init = web_sys::RequestInit::new();
+    init.set_method("POST");
+    init.set_body(&form);
+
+    let response: web_sys::Response = JsFuture::from(
+        leptos::window().fetch_with_str_and_init(&format!("/uploads/{}", kind.as_str()), &init),
+    )
+    .await
+    .map_err(failed)?
+    .unchecked_into();
+    let text = JsFuture::from(response.text().map_err(failed)?)
+        .await
+        .map_err(failed)?
+        .as_string()
+        .unwrap_or_default();
+
+    let body = serde_json::from_str::<serde_json::Value>(&text).unwrap_or_default();
+    let field = if response.ok() { "url" } else { "error" };
+    match (response.ok(), body[field].as_str()) {
+        (true, Some(url)) => Ok(url.to_string()),
+        (false, Some(error)) => Err(error.to_string()),
+        _ => Err(failed(wasm_bindgen::JsValue::NULL)),
+    }
+}
@@ -0,0 +1,175 @@
+use std::sync::Arc;
+
+use axum::{extract::Query, http::StatusCode, response::Html};
+use serde::Deserialize;
+
+use crate::mail::{Email, Mailer};
+use crate::models::{DigestArticle, DigestPreferences, DigestRecipient};
+
+/// How often the scheduler looks for digests that are due.
+const CHECK_INTERVAL_SECS: u64 = 3600;
+
+/// Sends the digests that are due every hour, for as long as the app runs.
+pub fn spawn_scheduler(mailer: Arc<dyn Mailer>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(CHECK_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            match send_due_digests(mailer.as_ref()).await {
+                Ok(0) => {}
+                Ok(sent) => tracing::info!("{sent} digests sent"),
+                Err(x) => tracing::error!("problem while sending digests: {x:?}"),
+            }
+        }
+    });
+}
+
+/// Sends one email to each user whose digest is due and has something new in it,
+/// returning how many were sent.
+#[tracing::instrument(skip(mailer))]
+pub async fn send_due_digests(mailer: &dyn Mailer) -> Result<usize, sqlx::Error> {
+    let mut sent = 0;
+    for recipient in DigestPreferences::claim_due().await? {
+        match send_digest(mailer, &recipient).await {
+            Ok(true) => sent += 1,
+            Ok(false) => {}
+            Err(x) => {
+                // Retried on the next run, as the digest is due again
+                tracing::error!("could not send the digest to {}: {x}", recipient.email);
+                DigestPreferences::release(&recipient).await?;
+            }
+        }
+    }
+    Ok(sent)
+}
+
+/// Whether there was anything new to send to `recipient`.
+async fn send_digest(mailer: &dyn Mailer, recipient: &DigestRecipient) -> Result<bool, String> {
+    let articles = DigestArticle::for_recipient(recipient)
+        .await
+        .map_err(|x| x.to_string())?;
+    if articles.is_empty() {
+        return Ok(false);
+    }
+    mailer.send(&render(recipient, &articles, &crate::site::url()))?;
+    Ok(true)
+}
+
+fn render(recipient: &DigestRecipient, articles: &[DigestArticle], site_url: &str) -> Email {
+    let unsubscribe_url = format!(
+        "{site_url}/unsubscribe?token={}",
+        crate::auth::generate_unsubscribe_token(recipient.user_id)
+    );
+    let mut body = format!("Hi {},\n\nHere is what's new since your last digest:\n\n", recipient.name);
+    for article in articles {
+        body.push_str(&format!(
+            "* {} by {}\n  {}\n  {site_url}/article/{}\n\n",
+            article.title, article.author, article.description, article.slug
+        ));
+    }
+    body.push_str(&format!(
+        "You can change how often you get this email in your settings: {site_url}/settings\nUnsubscribe: {unsubscribe_url}\n"
+    ));
+
+    Email {
+        to: recipient.email.clone(),
+        subject: format!(
+            "{} new article{} on thedeveloper",
+            articles.len(),
+            if articles.len() == 1 { "" } else { "s" }
+        ),
+        body,
+        unsubscribe_url: Some(unsubscribe_url),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UnsubscribeQuery {
+    token: String,
+}
+
+const INVALID_LINK: &str =
+    "<p>This unsubscribe link is invalid or has expired. You can still turn digests off in your settings.</p>";
+
+/// Target of the unsubscribe links when clicked, asking to confirm so that link
+/// scanners opening every URL of an email don't unsubscribe anyone.
+#[tracing::instrument(skip(query))]
+pub async fn unsubscribe_page(Query(query): Query<UnsubscribeQuery>) -> (StatusCode, Html<String>) {
+    if crate::auth::decode_unsubscribe_token(&query.token).is_none() {
+        return (StatusCode::BAD_REQUEST, Html(INVALID_LINK.to_string()));
+    }
+    (
+        StatusCode::OK,
+        Html(format!(
+            "<form method=\"post\" action=\"/unsubscribe?token={}\"><p>Stop getting digests by email?</p><button type=\"submit\">Unsubscribe</button></form>",
+            crate::feed::escape(&query.token)
+        )),
+    )
+}
+
+/// Unsubscribes, from the confirmation form or from the one-click `POST` of mail
+/// clients (RFC 8058), whose body only says `List-Unsubscribe=One-Click`.
+#[tracing::instrument(skip(query))]
+pub async fn unsubscribe_handler(Query(query): Query<UnsubscribeQuery>) -> (StatusCode, Html<&'static str>) {
+    let Some(user_id) = crate::auth::decode_unsubscribe_token(&query.token) else {
+        return (StatusCode::BAD_REQUEST, Html(INVALID_LINK));
+    };
+    match DigestPreferences::unsubscribe(user_id).await {
+        Ok(_) => (
+            StatusCode::OK,
+            Html("<p>You won't get any more digests. You can turn them back on in your settings.</p>"),
+        ),
+        Err(x) => {
+            tracing::error!("problem while unsubscribing {user_id}: {x:?}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Html("<p>Something went wrong, try again later.</p>"),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digests_link_every_article_and_the_unsubscribe_page() {
+        let recipient = DigestRecipient {
+            user_id: uuid::Uuid::new_v4(),
+            name: "Ada".to_string(),
+            email: "ada@example.com".to_string(),
+            tags: Vec::new(),
+            since: chrono::Utc::now(),
+            last_sent_at: None,
+        };
+        let article = |slug: &str| DigestArticle {
+            slug: slug.to_string(),
+            title: format!("About {slug}"),
+            description: "Worth a read".to_string(),
+            author: "Grace".to_string(),
+        };
+        let email = render(&recipient, &[article("first"), article("second")], "https://example.com");
+
+        assert_eq!(email.to, "ada@example.com");
+        assert_eq!(email.subject, "2 new articles on thedeveloper");
+        assert!(email.body.starts_with("Hi Ada,"));
+        assert!(email.body.contains("* About first by Grace\n  Worth a read\n  https://example.com/article/first\n"));
+        assert!(email.body.contains("https://example.com/article/second"));
+
+        let unsubscribe_url = email.unsubscribe_url.clone().unwrap();
+        assert!(email.body.contains(&format!("Unsubscribe: {unsubscribe_url}\n")));
+        let token = unsubscribe_url
+            .strip_prefix("https://example.com/unsubscribe?token=")
+            .unwrap();
+        assert_eq!(crate::auth::decode_unsubscribe_token(token), Some(recipient.user_id));
+        assert!(email
+            .to_rfc5322("from@example.com")
+            .contains(&format!("List-Unsubscribe: <{unsubscribe_url}>\r\n")));
+
+        assert_eq!(
+            render(&recipient, &[article("only")], "https://example.com").subject,
+            "1 new article on thedeveloper"
+        );
+    }
+}
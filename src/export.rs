@@ -0,0 +1,148 @@
+use std::io::Write;
+
+use axum::{
+    extract::Query,
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Response},
+};
+use serde::Deserialize;
+
+use crate::front_matter::FrontMatter;
+use crate::models::{DataExport, ExportStatus, ExportedArticle, PersonalData};
+
+/// Builds the archive of `user_id` in the background. The user finds the download
+/// link in their settings once it is ready.
+pub fn spawn_export(export_id: uuid::Uuid, user_id: uuid::Uuid) {
+    tokio::spawn(async move {
+        let status = match build(export_id, user_id).await {
+            Ok(()) => ExportStatus::Ready,
+            Err(x) => {
+                tracing::error!("problem while exporting the data of {user_id}: {x}");
+                ExportStatus::Failed
+            }
+        };
+        if let Err(x) = DataExport::finish(export_id, status).await {
+            tracing::error!("problem while finishing the export {export_id}: {x:?}");
+        }
+    });
+}
+
+/// How often exports are checked for expired download links.
+const CLEANUP_INTERVAL_SECS: u64 = 3600;
+
+/// Deletes the exports nobody can download anymore every hour, for as long as the
+/// app runs.
+pub fn spawn_cleanup_scheduler() {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(CLEANUP_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            match DataExport::delete_expired().await {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!("{deleted} exports deleted"),
+                Err(x) => tracing::error!("problem while deleting exports: {x:?}"),
+            }
+        }
+    });
+}
+
+#[tracing::instrument]
+async fn build(export_id: uuid::Uuid, user_id: uuid::Uuid) -> Result<(), String> {
+    let data = PersonalData::collect(user_id).await.map_err(|x| x.to_string())?;
+    let archive = archive(&data).map_err(|x| x.to_string())?;
+    crate::storage::get_store()
+        .put(&DataExport::key(export_id), archive, "application/zip")
+        .await
+        .map_err(|x| x.to_string())
+}
+
+/// A ZIP with everything in `data.json`, and each article as a Markdown file.
+pub fn archive(data: &PersonalData) -> zip::result::ZipResult<Vec<u8>> {
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip::write::FileOptions::default();
+
+    zip.start_file("data.json", options)?;
+    zip.write_all(&serde_json::to_vec_pretty(data).expect("personal data is always serializable"))?;
+    write_articles(&mut zip, "articles/", &data.articles)?;
+
+    Ok(zip.finish()?.into_inner())
+}
+
+/// Each article as `<dir><slug>.md`, in the format the importer reads.
+fn write_articles<W: Write + std::io::Seek>(
+    zip: &mut zip::ZipWriter<W>,
+    dir: &str,
+    articles: &[ExportedArticle],
+) -> zip::result::ZipResult<()> {
+    for article in articles {
+        zip.start_file(format!("{dir}{}.md", article.slug), zip::write::FileOptions::default())?;
+        zip.write_all(FrontMatter::from(article).render(&article.body).as_bytes())?;
+    }
+    Ok(())
+}
+
+/// `GET /export/articles`, a ZIP of the logged user's articles as Markdown files.
+#[tracing::instrument(skip(headers))]
+pub async fn articles_handler(headers: HeaderMap) -> Response {
+    let Some(user_id) = crate::auth::get_user_id_from_headers(&headers) else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+    let articles = match ExportedArticle::by_author(user_id).await {
+        Ok(x) => x,
+        Err(x) => {
+            tracing::error!("problem while fetching the articles of {user_id}: {x:?}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let archive = write_articles(&mut zip, "", &articles).and_then(|_| zip.finish());
+    match archive {
+        Ok(x) => (
+            [
+                (header::CONTENT_TYPE, "application/zip"),
+                (header::CONTENT_DISPOSITION, "attachment; filename=\"articles.zip\""),
+                (header::CACHE_CONTROL, "private, no-store"),
+            ],
+            x.into_inner(),
+        )
+            .into_response(),
+        Err(x) => {
+            tracing::error!("problem while zipping the articles of {user_id}: {x:?}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DownloadQuery {
+    token: String,
+}
+
+/// Target of the download links, which act without a session so they can be
+/// opened anywhere until they expire.
+#[tracing::instrument(skip(query))]
+pub async fn download_handler(Query(query): Query<DownloadQuery>) -> Response {
+    let Some(export_id) = crate::auth::decode_export_token(&query.token) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Html("<p>This download link is invalid or has expired. You can ask for a new export in your settings.</p>"),
+        )
+            .into_response();
+    };
+    match crate::storage::get_store().get(&DataExport::key(export_id)).await {
+        Ok(Some(bytes)) => (
+            [
+                (header::CONTENT_TYPE, "application/zip"),
+                (header::CONTENT_DISPOSITION, "attachment; filename=\"thedeveloper-export.zip\""),
+                (header::CACHE_CONTROL, "private, no-store"),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(x) => {
+            tracing::error!("problem while reading the export {export_id}: {x}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
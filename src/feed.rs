@@ -0,0 +1,393 @@
+use axum::{
+    extract::{Path, Query},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::models::{Article, FeedArticle};
+
+/// How many articles a feed carries.
+const FEED_SIZE: i64 = 20;
+const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FeedFormat {
+    Atom,
+    Rss,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct FeedQuery {
+    /// Only descriptions instead of the full article body.
+    summary: Option<bool>,
+}
+
+struct Feed {
+    title: String,
+    /// The page the feed mirrors, relative to the site.
+    page: String,
+    /// Where the feed itself is served, relative to the site.
+    path: String,
+    entries: Vec<FeedArticle>,
+}
+
+impl Feed {
+    /// Latest change of any entry, which is what clients compare against.
+    fn updated(&self) -> DateTime<Utc> {
+        self.entries
+            .iter()
+            .map(|x| x.updated.max(x.published))
+            .max()
+            .unwrap_or(DateTime::<Utc>::UNIX_EPOCH)
+    }
+
+    fn render(&self, format: FeedFormat, site_url: &str, summary: bool) -> String {
+        match format {
+            FeedFormat::Atom => self.render_atom(site_url, summary),
+            FeedFormat::Rss => self.render_rss(site_url, summary),
+        }
+    }
+
+    fn render_atom(&self, site_url: &str, summary: bool) -> String {
+        let mut xml = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+<title>{}</title>
+<id>{site_url}{}</id>
+<link rel="self" href="{site_url}{}"/>
+<link rel="alternate" type="text/html" href="{site_url}{}"/>
+<updated>{}</updated>
+"#,
+            escape(&self.title),
+            escape(&self.path),
+            escape(&self.path),
+            escape(&self.page),
+            self.updated().to_rfc3339(),
+        );
+        for entry in &self.entries {
+            let article = &entry.article;
+            let link = format!("{site_url}/article/{}", article.slug);
+            xml.push_str(&format!(
+                r#"<entry>
+<title>{}</title>
+<id>urn:uuid:{}</id>
+<link rel="alternate" type="text/html" href="{}"/>
+<published>{}</published>
+<updated>{}</updated>
+<author><name>{}</name><uri>{site_url}/profile/{}</uri></author>
+"#,
+                escape(&article.title),
+                article.id,
+                escape(&link),
+                entry.published.to_rfc3339(),
+                entry.updated.max(entry.published).to_rfc3339(),
+                escape(&article.author.name),
+                article.author.user_id,
+            ));
+            for tag in &article.tag_list {
+                xml.push_str(&format!("<category term=\"{}\"/>\n", escape(tag)));
+            }
+            xml.push_str(&format!("<summary>{}</summary>\n", escape(&article.description)));
+            if !summary {
+                xml.push_str(&format!(
+                    "<content type=\"html\">{}</content>\n",
+                    escape(article.body.as_deref().unwrap_or_default())
+                ));
+            }
+            xml.push_str("</entry>\n");
+        }
+        xml.push_str("</feed>\n");
+        xml
+    }
+
+    fn render_rss(&self, site_url: &str, summary: bool) -> String {
+        let mut xml = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+<channel>
+<title>{}</title>
+<link>{site_url}{}</link>
+<description>{}</description>
+<atom:link rel="self" type="application/rss+xml" href="{site_url}{}"/>
+<lastBuildDate>{}</lastBuildDate>
+"#,
+            escape(&self.title),
+            escape(&self.page),
+            escape(&self.title),
+            escape(&self.path),
+            self.updated().to_rfc2822(),
+        );
+        for entry in &self.entries {
+            let article = &entry.article;
+            let link = format!("{site_url}/article/{}", article.slug);
+            xml.push_str(&format!(
+                r#"<item>
+<title>{}</title>
+<link>{}</link>
+<guid isPermaLink="false">urn:uuid:{}</guid>
+<pubDate>{}</pubDate>
+<dc:creator xmlns:dc="http://purl.org/dc/elements/1.1/">{}</dc:creator>
+<description>{}</description>
+"#,
+                escape(&article.title),
+                escape(&link),
+                article.id,
+                entry.published.to_rfc2822(),
+                escape(&article.author.name),
+                escape(&article.description),
+            ));
+            for tag in &article.tag_list {
+                xml.push_str(&format!("<category>{}</category>\n", escape(tag)));
+            }
+            if !summary {
+                xml.push_str(&format!(
+                    "<content:encoded>{}</content:encoded>\n",
+                    escape(article.body.as_deref().unwrap_or_default())
+                ));
+            }
+            xml.push_str("</item>\n");
+        }
+        xml.push_str("</channel>\n</rss>\n");
+        xml
+    }
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Whether the client already has this version, going by `If-None-Match` first and
+/// `If-Modified-Since` only when no ETag was sent, as RFC 9110 asks.
+fn not_modified(headers: &HeaderMap, etag: &str, last_modified: DateTime<Utc>) -> bool {
+    if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH) {
+        return if_none_match
+            .to_str()
+            .map(|x| x.split(',').any(|x| x.trim() == etag || x.trim() == "*"))
+            .unwrap_or_default();
+    }
+    headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|x| x.to_str().ok())
+        .and_then(|x| DateTime::parse_from_rfc2822(x).ok())
+        // HTTP dates have no sub-second precision
+        .is_some_and(|since| last_modified.timestamp() <= since.timestamp())
+}
+
+fn respond(headers: &HeaderMap, feed: Feed, format: FeedFormat, query: &FeedQuery) -> Response {
+    let body = feed.render(format, &crate::site::url(), query.summary.unwrap_or_default());
+    let etag = format!("\"{:x}\"", Sha256::digest(body.as_bytes()));
+    let last_modified = feed.updated();
+
+    let mut response = if not_modified(headers, &etag, last_modified) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        body.into_response()
+    };
+    let response_headers = response.headers_mut();
+    response_headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(match format {
+            FeedFormat::Atom => "application/atom+xml; charset=utf-8",
+            FeedFormat::Rss => "application/rss+xml; charset=utf-8",
+        }),
+    );
+    if let Ok(x) = HeaderValue::from_str(&etag) {
+        response_headers.insert(header::ETAG, x);
+    }
+    if let Ok(x) = HeaderValue::from_str(&last_modified.format(HTTP_DATE_FORMAT).to_string()) {
+        response_headers.insert(header::LAST_MODIFIED, x);
+    }
+    response
+}
+
+/// Fills `feed` with the latest articles, optionally of one tag or one author.
+async fn fill(
+    headers: &HeaderMap,
+    query: &FeedQuery,
+    format: FeedFormat,
+    mut feed: Feed,
+    tag: Option<String>,
+    author_id: Option<uuid::Uuid>,
+) -> Response {
+    match Article::for_feed(tag, author_id, FEED_SIZE).await {
+        Ok(entries) => {
+            feed.entries = entries;
+            respond(headers, feed, format, query)
+        }
+        Err(x) => {
+            tracing::error!("problem while fetching feed articles: {x:?}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+fn empty(title: String, page: String, path: String) -> Feed {
+    Feed {
+        title,
+        page,
+        path,
+        entries: Vec::new(),
+    }
+}
+
+async fn global(headers: HeaderMap, query: FeedQuery, format: FeedFormat, path: &str) -> Response {
+    let feed = empty("thedeveloper".to_string(), "/".to_string(), path.to_string());
+    fill(&headers, &query, format, feed, None, None).await
+}
+
+async fn tag(headers: HeaderMap, query: FeedQuery, format: FeedFormat, tag: String, file: &str) -> Response {
+    let feed = empty(
+        format!("thedeveloper: #{tag}"),
+        format!("/?tag={tag}"),
+        format!("/tag/{tag}/{file}"),
+    );
+    fill(&headers, &query, format, feed, Some(tag), None).await
+}
+
+async fn author(
+    headers: HeaderMap,
+    query: FeedQuery,
+    format: FeedFormat,
+    user_id: uuid::Uuid,
+    file: &str,
+) -> Response {
+    let user = match crate::models::User::get_by_id(user_id).await {
+        Ok(x) => x,
+        Err(sqlx::Error::RowNotFound) => return StatusCode::NOT_FOUND.into_response(),
+        Err(x) => {
+            tracing::error!("problem while fetching the feed author: {x:?}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let feed = empty(
+        format!("thedeveloper: {}", user.name()),
+        format!("/profile/{user_id}"),
+        format!("/profile/{user_id}/{file}"),
+    );
+    fill(&headers, &query, format, feed, None, Some(user_id)).await
+}
+
+#[tracing::instrument(skip(headers))]
+pub async fn global_atom(headers: HeaderMap, Query(query): Query<FeedQuery>) -> Response {
+    global(headers, query, FeedFormat::Atom, "/feed.xml").await
+}
+
+#[tracing::instrument(skip(headers))]
+pub async fn global_rss(headers: HeaderMap, Query(query): Query<FeedQuery>) -> Response {
+    global(headers, query, FeedFormat::Rss, "/rss.xml").await
+}
+
+#[tracing::instrument(skip(headers))]
+pub async fn tag_atom(
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Query(query): Query<FeedQuery>,
+) -> Response {
+    tag(headers, query, FeedFormat::Atom, name, "feed.xml").await
+}
+
+#[tracing::instrument(skip(headers))]
+pub async fn tag_rss(
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Query(query): Query<FeedQuery>,
+) -> Response {
+    tag(headers, query, FeedFormat::Rss, name, "rss.xml").await
+}
+
+#[tracing::instrument(skip(headers))]
+pub async fn author_atom(
+    headers: HeaderMap,
+    Path(user_id): Path<uuid::Uuid>,
+    Query(query): Query<FeedQuery>,
+) -> Response {
+    author(headers, query, FeedFormat::Atom, user_id, "feed.xml").await
+}
+
+#[tracing::instrument(skip(headers))]
+pub async fn author_rss(
+    headers: HeaderMap,
+    Path(user_id): Path<uuid::Uuid>,
+    Query(query): Query<FeedQuery>,
+) -> Response {
+    author(headers, query, FeedFormat::Rss, user_id, "rss.xml").await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(updated: DateTime<Utc>) -> FeedArticle {
+        FeedArticle {
+            article: Article {
+                slug: "a-b".into(),
+                title: "Rust & <friends>".into(),
+                description: "All about \"it\"".into(),
+                body: Some("<p>Hello</p>".into()),
+                tag_list: vec!["rust".into()],
+                ..Default::default()
+            },
+            published: DateTime::<Utc>::UNIX_EPOCH,
+            updated,
+        }
+    }
+
+    fn feed(updated: DateTime<Utc>) -> Feed {
+        Feed {
+            title: "thedeveloper".into(),
+            page: "/".into(),
+            path: "/feed.xml".into(),
+            entries: vec![entry(updated)],
+        }
+    }
+
+    #[test]
+    fn content_is_escaped_and_optional() {
+        let feed = feed(DateTime::<Utc>::UNIX_EPOCH);
+        let atom = feed.render(FeedFormat::Atom, "http://localhost", false);
+        assert!(atom.contains("<title>Rust &amp; &lt;friends&gt;</title>"));
+        assert!(atom.contains("<content type=\"html\">&lt;p&gt;Hello&lt;/p&gt;</content>"));
+        assert!(atom.contains("href=\"http://localhost/article/a-b\""));
+
+        let rss = feed.render(FeedFormat::Rss, "http://localhost", true);
+        assert!(rss.contains("<description>All about &quot;it&quot;</description>"));
+        assert!(!rss.contains("content:encoded>"));
+    }
+
+    #[test]
+    fn conditional_requests() {
+        let updated = DateTime::parse_from_rfc3339("2024-05-01T10:00:00.500Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(feed(updated).updated(), updated);
+
+        let mut headers = HeaderMap::new();
+        assert!(!not_modified(&headers, "\"abc\"", updated));
+
+        headers.insert(
+            header::IF_MODIFIED_SINCE,
+            HeaderValue::from_static("Wed, 01 May 2024 10:00:00 GMT"),
+        );
+        assert!(not_modified(&headers, "\"abc\"", updated));
+
+        // A mismatching ETag wins over the date
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("\"old\""));
+        assert!(!not_modified(&headers, "\"abc\"", updated));
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("\"old\", \"abc\""));
+        assert!(not_modified(&headers, "\"abc\"", updated));
+    }
+}
@@ -0,0 +1,193 @@
+//! Articles as Markdown files with a YAML front matter, the format static site
+//! generators read and write. Values are written as JSON, which YAML reads as well,
+//! and read back with just enough YAML for what those generators produce.
+
+/// The metadata at the top of an article file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrontMatter {
+    pub title: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    /// Publication date, in RFC 3339.
+    pub date: Option<String>,
+    pub cover_image: Option<String>,
+}
+
+impl FrontMatter {
+    /// The whole file, front matter first and `body` after it.
+    pub fn render(&self, body: &str) -> String {
+        let quote = |x: &str| serde_json::to_string(x).expect("strings are always serializable");
+        let mut file = format!(
+            "---\ntitle: {}\ndescription: {}\ntags: {}\n",
+            quote(&self.title),
+            quote(&self.description),
+            serde_json::to_string(&self.tags).expect("strings are always serializable"),
+        );
+        if let Some(date) = &self.date {
+            file.push_str(&format!("date: {date}\n"));
+        }
+        if let Some(cover_image) = &self.cover_image {
+            file.push_str(&format!("cover_image: {}\n", quote(cover_image)));
+        }
+        file.push_str("---\n\n");
+        file.push_str(body);
+        if !body.ends_with('\n') {
+            file.push('\n');
+        }
+        file
+    }
+
+    /// Reads a file written by [`FrontMatter::render`] or by a static site generator,
+    /// returning its front matter and its body. Only the keys articles have are read,
+    /// along with the names other tools give them, and the rest is ignored.
+    pub fn parse(file: &str) -> Result<(Self, String), String> {
+        let file = file.strip_prefix('\u{feff}').unwrap_or(file);
+        let rest = file
+            .strip_prefix("---\n")
+            .or_else(|| file.strip_prefix("---\r\n"))
+            .ok_or("The file does not start with a front matter")?;
+
+        let mut lines = Vec::new();
+        let mut body_start = None;
+        let mut offset = 0;
+        for line in rest.split_inclusive('\n') {
+            offset += line.len();
+            if line.trim_end() == "---" {
+                body_start = Some(offset);
+                break;
+            }
+            lines.push(line.trim_end());
+        }
+        let body = &rest[body_start.ok_or("The front matter is never closed")?..];
+
+        let mut front_matter = Self::default();
+        let mut lines = lines.into_iter().peekable();
+        while let Some(line) = lines.next() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "title" => front_matter.title = scalar(value),
+                "description" | "summary" => front_matter.description = scalar(value),
+                "date" => front_matter.date = Some(scalar(value)).filter(|x| !x.is_empty()),
+                "cover_image" | "cover" | "image" => {
+                    front_matter.cover_image = Some(scalar(value)).filter(|x| !x.is_empty())
+                }
+                "tags" => {
+                    let tags = if value.is_empty() {
+                        // A block list, one `- tag` per line
+                        let mut tags = Vec::new();
+                        while let Some(item) = lines.peek().and_then(|x| x.trim_start().strip_prefix('-')) {
+                            tags.push(scalar(item));
+                            lines.next();
+                        }
+                        tags
+                    } else if value.starts_with('[') {
+                        serde_json::from_str::<Vec<String>>(value).unwrap_or_else(|_| {
+                            value
+                                .trim_start_matches('[')
+                                .trim_end_matches(']')
+                                .split(',')
+                                .map(scalar)
+                                .collect()
+                        })
+                    } else {
+                        scalar(value).split([',', ' ']).map(str::to_string).collect()
+                    };
+                    front_matter.tags = tags
+                        .into_iter()
+                        .map(|x| x.trim().to_string())
+                        .filter(|x| !x.is_empty())
+                        .collect();
+                }
+                _ => {}
+            }
+        }
+
+        if front_matter.title.trim().is_empty() {
+            return Err("The front matter has no title".into());
+        }
+        Ok((front_matter, body.trim_start_matches(['\r', '\n']).to_string()))
+    }
+}
+
+/// A YAML scalar without its quotes.
+fn scalar(value: &str) -> String {
+    let value = value.trim();
+    if value.starts_with('"') {
+        serde_json::from_str(value).unwrap_or_else(|_| value.trim_matches('"').to_string())
+    } else if let Some(x) = value.strip_prefix('\'').and_then(|x| x.strip_suffix('\'')) {
+        x.replace("''", "'")
+    } else {
+        value.to_string()
+    }
+}
+
+impl From<&crate::models::ExportedArticle> for FrontMatter {
+    fn from(article: &crate::models::ExportedArticle) -> Self {
+        Self {
+            title: article.title.clone(),
+            description: article.description.clone(),
+            tags: article.tags.clone(),
+            date: Some(article.created_at.clone()),
+            cover_image: article.cover_image.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_quoted() {
+        let front_matter = FrontMatter {
+            title: "Rust: \"the\" language".into(),
+            description: "Why".into(),
+            tags: vec!["rust".into(), "web".into()],
+            date: Some("2024-05-01T10:00:00+00:00".into()),
+            cover_image: None,
+        };
+        assert_eq!(
+            front_matter.render("# Hello"),
+            "---\ntitle: \"Rust: \\\"the\\\" language\"\ndescription: \"Why\"\ntags: [\"rust\",\"web\"]\ndate: 2024-05-01T10:00:00+00:00\n---\n\n# Hello\n"
+        );
+    }
+
+    #[test]
+    fn rendered_files_read_back() {
+        let front_matter = FrontMatter {
+            title: "Rust: \"the\" language".into(),
+            description: "Why, really".into(),
+            tags: vec!["rust".into(), "web".into()],
+            date: Some("2024-05-01T10:00:00+00:00".into()),
+            cover_image: Some("/uploads/cover.png".into()),
+        };
+        let file = front_matter.render("# Hello\n\nWorld");
+        assert_eq!(
+            FrontMatter::parse(&file),
+            Ok((front_matter, "# Hello\n\nWorld\n".to_string()))
+        );
+    }
+
+    #[test]
+    fn other_generators_are_understood() {
+        let file = "---\ntitle: 'It''s here'\nsummary: Plain text\ntags:\n  - rust\n  - \"web dev\"\nimage: https://example.com/a.png\nlayout: post\n---\nBody";
+        let (front_matter, body) = FrontMatter::parse(file).unwrap();
+        assert_eq!(front_matter.title, "It's here");
+        assert_eq!(front_matter.description, "Plain text");
+        assert_eq!(front_matter.tags, vec!["rust", "web dev"]);
+        assert_eq!(front_matter.cover_image.as_deref(), Some("https://example.com/a.png"));
+        assert_eq!(body, "Body");
+
+        assert_eq!(
+            FrontMatter::parse("tags: [a, b]\nBody").map(|_| ()),
+            Err("The file does not start with a front matter".into())
+        );
+        assert_eq!(
+            FrontMatter::parse("---\ntags: rust, web\n---\n").map(|_| ()),
+            Err("The front matter has no title".into())
+        );
+    }
+}
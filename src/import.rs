@@ -0,0 +1,357 @@
+use std::io::Read;
+
+use axum::{
+    extract::Multipart,
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Response},
+    Json,
+};
+use rand::Rng;
+use serde::Serialize;
+
+use crate::front_matter::FrontMatter;
+
+/// Largest ZIP the import route accepts, leaving room for the multipart framing.
+pub const MAX_IMPORT_BYTES: usize = 10 * 1024 * 1024 + 64 * 1024;
+/// Most Markdown files read from one ZIP.
+const MAX_IMPORT_FILES: usize = 500;
+/// Largest Markdown file read, once decompressed.
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+/// Most bytes decompressed from one ZIP, whatever its entries claim.
+const MAX_DECOMPRESSED_BYTES: u64 = 50 * 1024 * 1024;
+const TITLE_MIN_LENGTH: usize = 4;
+const BODY_MIN_LENGTH: usize = 10;
+
+/// An article read from a Markdown file, ready to be saved.
+#[derive(Debug)]
+struct ImportedArticle {
+    slug: String,
+    title: String,
+    description: String,
+    body: String,
+    tags: Vec<String>,
+    cover_image: Option<String>,
+    created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// What happened to each file of an import.
+#[derive(Debug, Default, Serialize)]
+struct ImportReport {
+    /// Slugs of the articles created or updated.
+    imported: Vec<String>,
+    /// File names, with the reason they were left out.
+    skipped: Vec<(String, String)>,
+}
+
+#[derive(Debug)]
+enum ImportError {
+    Unauthorized,
+    MissingFile,
+    NotAZip,
+    Internal,
+}
+
+impl IntoResponse for ImportError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            ImportError::Unauthorized => (StatusCode::UNAUTHORIZED, "You need to be logged in"),
+            ImportError::MissingFile => (StatusCode::BAD_REQUEST, "Missing the file field"),
+            ImportError::NotAZip => (StatusCode::UNSUPPORTED_MEDIA_TYPE, "Only ZIP files are accepted"),
+            ImportError::Internal => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Problem while importing the articles, try again later",
+            ),
+        };
+        (status, Json(serde_json::json!({ "error": message }))).into_response()
+    }
+}
+
+/// `POST /import/articles` with a ZIP of Markdown files in a multipart `file` field.
+/// Files whose name matches the slug of one of the user's articles update it, the
+/// others become new articles. Answers with what was imported, as a page for plain
+/// HTML forms.
+#[tracing::instrument(skip(headers, multipart))]
+pub async fn import_handler(headers: HeaderMap, multipart: Multipart) -> Response {
+    let html = headers
+        .get(header::ACCEPT)
+        .and_then(|x| x.to_str().ok())
+        .is_some_and(|x| x.contains("text/html"));
+    match import(&headers, multipart).await {
+        Ok(report) if html => Html(render(&report)).into_response(),
+        Ok(report) => Json(report).into_response(),
+        Err(x) => x.into_response(),
+    }
+}
+
+async fn import(headers: &HeaderMap, mut multipart: Multipart) -> Result<ImportReport, ImportError> {
+    let user_id = crate::auth::get_user_id_from_headers(headers).ok_or(ImportError::Unauthorized)?;
+
+    let mut bytes = None;
+    while let Some(field) = multipart.next_field().await.map_err(|x| {
+        tracing::error!("invalid multipart import: {x:?}");
+        ImportError::MissingFile
+    })? {
+        if field.name() == Some("file") {
+            // The body limit of the route already caps this
+            bytes = Some(field.bytes().await.map_err(|_| ImportError::MissingFile)?);
+            break;
+        }
+    }
+    let bytes = bytes.filter(|x| !x.is_empty()).ok_or(ImportError::MissingFile)?;
+
+    let files = tokio::task::spawn_blocking(move || read_zip(&bytes))
+        .await
+        .map_err(|x| {
+            tracing::error!("reading an import panicked: {x:?}");
+            ImportError::Internal
+        })?
+        .map_err(|x| {
+            tracing::info!("rejected an import that is not a ZIP: {x:?}");
+            ImportError::NotAZip
+        })?;
+
+    let mut report = ImportReport::default();
+    for (name, article) in files {
+        match article {
+            // Each article is saved on its own, so one failure keeps the others
+            Ok(article) => match save(user_id, &article).await {
+                Ok(slug) => report.imported.push(slug),
+                Err(x) => {
+                    tracing::error!("problem while importing {name} for {user_id}: {x:?}");
+                    report
+                        .skipped
+                        .push((name, "Problem while saving the article, try again later".to_string()));
+                }
+            },
+            Err(x) => report.skipped.push((name, x)),
+        }
+    }
+    tracing::info!("{user_id} imported {} articles", report.imported.len());
+    Ok(report)
+}
+
+/// The Markdown files of a ZIP, wherever they are in it, each read as an article
+/// or with the reason it cannot be one.
+fn read_zip(bytes: &[u8]) -> zip::result::ZipResult<Vec<(String, Result<ImportedArticle, String>)>> {
+    let mut zip = zip::ZipArchive::new(std::io::Cursor::new(bytes))?;
+    let mut files = Vec::new();
+    let mut budget = MAX_DECOMPRESSED_BYTES;
+    for index in 0..zip.len() {
+        let file = zip.by_index(index)?;
+        let name = file.name().to_string();
+        let is_markdown = name.ends_with(".md") || name.ends_with(".markdown");
+        // Skips folders, other files and the metadata macOS adds to archives
+        if file.is_dir() || !is_markdown || name.starts_with("__MACOSX/") {
+            continue;
+        }
+        if files.len() == MAX_IMPORT_FILES {
+            files.push((name, Err(format!("Only {MAX_IMPORT_FILES} files are imported at once"))));
+            break;
+        }
+        if file.size() > MAX_FILE_BYTES {
+            files.push((name, Err(too_large(MAX_FILE_BYTES))));
+            continue;
+        }
+
+        if budget == 0 {
+            files.push((name, Err(too_large(MAX_DECOMPRESSED_BYTES))));
+            continue;
+        }
+
+        // Sizes in the archive can lie, so what is read is counted too
+        let limit = MAX_FILE_BYTES.min(budget);
+        let mut content = Vec::new();
+        let read = file.take(limit + 1).read_to_end(&mut content);
+        budget = budget.saturating_sub(content.len() as u64);
+        let article = if read.is_err() {
+            Err("The file could not be decompressed".to_string())
+        } else if content.len() as u64 > limit {
+            Err(too_large(limit))
+        } else {
+            match String::from_utf8(content) {
+                Ok(content) => read_article(&name, &content),
+                Err(_) => Err("The file is not valid UTF-8".to_string()),
+            }
+        };
+        files.push((name, article));
+    }
+    Ok(files)
+}
+
+fn too_large(limit: u64) -> String {
+    if limit == MAX_FILE_BYTES {
+        format!("Files must be smaller than {} KiB", MAX_FILE_BYTES / 1024)
+    } else {
+        format!("Only {} MiB of files are imported at once", MAX_DECOMPRESSED_BYTES / 1024 / 1024)
+    }
+}
+
+fn read_article(name: &str, content: &str) -> Result<ImportedArticle, String> {
+    let (front_matter, body) = FrontMatter::parse(content)?;
+    let title = front_matter.title.trim().to_string();
+    if title.len() < TITLE_MIN_LENGTH {
+        return Err(format!("The title must be at least {TITLE_MIN_LENGTH} characters"));
+    }
+    if body.trim().len() < BODY_MIN_LENGTH {
+        return Err(format!("The body must be at least {BODY_MIN_LENGTH} characters"));
+    }
+
+    let stem = name
+        .rsplit('/')
+        .next()
+        .and_then(|x| x.rsplit_once('.'))
+        .map_or("", |(stem, _)| stem);
+    let slug = crate::models::Article::slug_of(stem)
+        .unwrap_or_else(|| crate::models::Article::slugify(&title));
+    let description = match front_matter.description.trim() {
+        "" => first_paragraph(&body),
+        x => x.to_string(),
+    };
+    // Tags are single words on this site
+    let tags = front_matter
+        .tags
+        .iter()
+        .map(|x| x.split_whitespace().collect::<Vec<_>>().join("-"))
+        .collect();
+    // Relative images of static sites would not resolve here
+    let cover_image = front_matter
+        .cover_image
+        .filter(|x| crate::storage::is_image_url(x));
+    // Dates to come would keep the article at the top of every listing
+    let created_at = front_matter
+        .date
+        .as_deref()
+        .and_then(parse_date)
+        .map(|x| x.min(chrono::Utc::now()));
+
+    Ok(ImportedArticle {
+        slug,
+        title,
+        description,
+        body,
+        tags,
+        cover_image,
+        created_at,
+    })
+}
+
+/// The first line of text of a body, when its front matter has no description.
+fn first_paragraph(body: &str) -> String {
+    body.lines()
+        .map(str::trim)
+        .find(|x| !x.is_empty() && !x.starts_with('#') && !x.starts_with("!["))
+        .unwrap_or_default()
+        .chars()
+        .take(160)
+        .collect()
+}
+
+/// Dates as static site generators write them, with or without a time.
+fn parse_date(date: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    use chrono::TimeZone;
+
+    chrono::DateTime::parse_from_rfc3339(date)
+        .or_else(|_| chrono::DateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S %z"))
+        .map(|x| x.with_timezone(&chrono::Utc))
+        .ok()
+        .or_else(|| {
+            let date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+            Some(chrono::Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?))
+        })
+}
+
+/// Updates the article of `user_id` with the same slug, or creates it. When the
+/// slug belongs to someone else's article, the new one gets a suffix.
+async fn save(user_id: uuid::Uuid, article: &ImportedArticle) -> Result<String, sqlx::Error> {
+    let mut transaction = crate::database::get_db().begin().await?;
+    let reading_time = crate::models::Article::reading_time_of(&article.body);
+
+    let mut slug = article.slug.clone();
+    loop {
+        let saved = sqlx::query!(
+            "
+            INSERT INTO Articles(slug, title, description, body, tags, author_id, cover_image, reading_time, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, COALESCE($9, NOW()))
+            ON CONFLICT (slug) DO UPDATE SET
+                title = $2, description = $3, body = $4, tags = $5, cover_image = $7,
+                reading_time = $8, updated_at = NOW()
+            WHERE Articles.author_id = $6
+            RETURNING slug",
+            slug,
+            article.title,
+            article.description,
+            article.body,
+            &article.tags,
+            user_id,
+            article.cover_image,
+            reading_time,
+            article.created_at,
+        )
+        .fetch_optional(transaction.as_mut())
+        .await?;
+        if saved.is_some() {
+            break;
+        }
+        let suffix: u32 = rand::thread_rng().gen_range(1000..10000);
+        slug = format!("{}-{suffix}", article.slug);
+    }
+    crate::models::CoAuthor::add_owner(transaction.as_mut(), &slug, user_id).await?;
+
+    transaction.commit().await?;
+    Ok(slug)
+}
+
+fn render(report: &ImportReport) -> String {
+    use crate::feed::escape;
+
+    let mut page = format!("<h1>{} articles imported</h1><ul>", report.imported.len());
+    for slug in &report.imported {
+        let slug = escape(slug);
+        page.push_str(&format!("<li><a href=\"/article/{slug}\">{slug}</a></li>"));
+    }
+    page.push_str("</ul>");
+    if !report.skipped.is_empty() {
+        page.push_str(&format!("<h2>{} files skipped</h2><ul>", report.skipped.len()));
+        for (name, reason) in &report.skipped {
+            page.push_str(&format!("<li>{}: {}</li>", escape(name), escape(reason)));
+        }
+        page.push_str("</ul>");
+    }
+    page.push_str("<p><a href=\"/settings\">Back to your settings</a></p>");
+    page
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn articles_are_read_from_files() {
+        let article = read_article(
+            "blog/posts/Hello_World.md",
+            "---\ntitle: Hello world\ntags: [rust, web dev]\ndate: 2024-05-01\ncover_image: ./cover.png\n---\n# Hi\n\nFirst words here.\n",
+        )
+        .unwrap();
+        assert_eq!(article.slug, "hello-world");
+        assert_eq!(article.description, "First words here.");
+        assert_eq!(article.tags, vec!["rust", "web-dev"]);
+        assert_eq!(article.cover_image, None);
+        assert_eq!(
+            article.created_at.map(|x| x.to_rfc3339()).as_deref(),
+            Some("2024-05-01T00:00:00+00:00")
+        );
+
+        let article = read_article("a.md", "---\ntitle: Later\ndate: 2999-01-01\n---\nLong enough body").unwrap();
+        assert!(article.created_at.unwrap() <= chrono::Utc::now());
+
+        assert!(read_article("a.md", "---\ntitle: Hey\n---\nLong enough body").is_err());
+        assert!(read_article("a.md", "---\ntitle: Long title\n---\nShort").is_err());
+    }
+
+    #[test]
+    fn titles_without_slug_characters_get_a_generic_slug() {
+        let article = read_article("!!!.md", "---\ntitle: Привет мир\n---\nLong enough body").unwrap();
+        assert_eq!(article.slug, "article");
+        assert_eq!(crate::models::Article::slugify("¿¡"), "article");
+    }
+}
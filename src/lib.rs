@@ -1,12 +1,33 @@
+#[cfg(feature = "ssr")]
+pub(crate) mod accounts;
 pub mod app;
 pub(crate) mod auth;
 pub(crate) mod components;
 #[cfg(feature = "ssr")]
 pub(crate) mod database;
-pub(crate) mod models;
+#[cfg(feature = "ssr")]
+pub(crate) mod digest;
+#[cfg(feature = "ssr")]
+pub(crate) mod export;
+#[cfg(feature = "ssr")]
+pub(crate) mod feed;
+#[cfg(feature = "ssr")]
+pub mod front_matter;
+#[cfg(feature = "ssr")]
+pub(crate) mod import;
+#[cfg(feature = "ssr")]
+pub(crate) mod mail;
+pub mod models;
+pub(crate) mod realtime;
 pub(crate) mod routes;
 #[cfg(feature = "ssr")]
 pub mod setup;
+#[cfg(feature = "ssr")]
+pub(crate) mod site;
+#[cfg(feature = "ssr")]
+pub(crate) mod sitemap;
+#[cfg(feature = "ssr")]
+pub(crate) mod storage;
 
 #[cfg(feature = "hydrate")]
 #[wasm_bindgen::prelude::wasm_bindgen]
@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+
+#[derive(Debug, Clone)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+    /// One-click unsubscribe link, sent as the `List-Unsubscribe` header.
+    pub unsubscribe_url: Option<String>,
+}
+
+impl Email {
+    /// The message in RFC 5322 format, plain text only.
+    pub fn to_rfc5322(&self, from: &str) -> String {
+        let mut message = format!(
+            "From: {from}\r\nTo: {}\r\nSubject: {}\r\nMIME-Version: 1.0\r\nContent-Type: text/plain; charset=utf-8\r\n",
+            self.to, self.subject
+        );
+        if let Some(url) = &self.unsubscribe_url {
+            message.push_str(&format!(
+                "List-Unsubscribe: <{url}>\r\nList-Unsubscribe-Post: List-Unsubscribe=One-Click\r\n"
+            ));
+        }
+        message.push_str("\r\n");
+        message.push_str(&self.body.replace('\n', "\r\n"));
+        message
+    }
+}
+
+/// Delivers emails. Sending is expected to be quick, so implementations that talk
+/// to a remote server should queue instead of blocking.
+pub trait Mailer: Send + Sync {
+    fn send(&self, email: &Email) -> Result<(), String>;
+}
+
+/// Writes every email as an `.eml` file, for local development and tests.
+pub struct FileMailer {
+    dir: PathBuf,
+    from: String,
+}
+
+impl FileMailer {
+    pub fn new(dir: impl Into<PathBuf>, from: impl Into<String>) -> Self {
+        Self {
+            dir: dir.into(),
+            from: from.into(),
+        }
+    }
+
+    /// Configured with `MAIL_DIR` and `MAIL_FROM`.
+    pub fn from_env() -> Self {
+        Self::new(
+            std::env::var("MAIL_DIR").unwrap_or_else(|_| "target/mail".to_string()),
+            std::env::var("MAIL_FROM").unwrap_or_else(|_| "thedeveloper <noreply@localhost>".to_string()),
+        )
+    }
+}
+
+impl Mailer for FileMailer {
+    fn send(&self, email: &Email) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir).map_err(|x| x.to_string())?;
+        let path = self.dir.join(format!(
+            "{}-{}.eml",
+            chrono::Utc::now().format("%Y%m%d%H%M%S"),
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::write(&path, email.to_rfc5322(&self.from)).map_err(|x| x.to_string())?;
+        tracing::info!("email to {} written to {}", email.to, path.display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_mailer_writes_one_file_per_email() {
+        let dir = std::env::temp_dir().join(format!("mail-{}", uuid::Uuid::new_v4()));
+        let mailer = FileMailer::new(&dir, "from@example.com");
+        let email = Email {
+            to: "to@example.com".into(),
+            subject: "Hello".into(),
+            body: "first line\nsecond line".into(),
+            unsubscribe_url: Some("http://localhost/unsubscribe?token=abc".into()),
+        };
+
+        mailer.send(&email).unwrap();
+
+        let files = std::fs::read_dir(&dir).unwrap().collect::<Vec<_>>();
+        assert_eq!(files.len(), 1);
+        let written = std::fs::read_to_string(files[0].as_ref().unwrap().path()).unwrap();
+        assert!(written.contains("To: to@example.com\r\n"));
+        assert!(written.contains("List-Unsubscribe: <http://localhost/unsubscribe?token=abc>\r\n"));
+        assert!(written.ends_with("first line\r\nsecond line"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,258 @@
+use serde::{Deserialize, Serialize};
+
+/// How many rows each list of the admin dashboard shows at once.
+pub const ADMIN_PAGE_SIZE: i64 = 50;
+
+/// A user as listed on the admin dashboard.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminUser {
+    pub id: uuid::Uuid,
+    pub username: String,
+    pub name: String,
+    pub email: String,
+    pub role: super::Role,
+    pub status: super::UserStatus,
+    pub articles: i64,
+    pub comments: i64,
+    pub created_at: String,
+}
+
+/// An article as listed on the admin dashboard, unpublished ones included.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminArticle {
+    pub id: uuid::Uuid,
+    pub slug: String,
+    pub title: String,
+    pub author_id: uuid::Uuid,
+    pub author_username: String,
+    pub unpublished: bool,
+    pub comments: i64,
+    pub created_at: String,
+}
+
+/// A comment as listed on the admin dashboard, with its body even when hidden.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminComment {
+    pub id: uuid::Uuid,
+    pub body: String,
+    pub article_slug: String,
+    pub article_title: String,
+    pub user_id: uuid::Uuid,
+    pub username: String,
+    pub hidden: bool,
+    pub created_at: String,
+}
+
+/// What happened on the site during one day.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DailyStats {
+    pub day: String,
+    pub signups: i64,
+    pub articles: i64,
+    pub comments: i64,
+}
+
+/// `%query%` for `ILIKE`, with the wildcards of `query` itself escaped.
+#[cfg(feature = "ssr")]
+fn like_pattern(query: &str) -> String {
+    let escaped = query
+        .trim()
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{escaped}%")
+}
+
+#[cfg(feature = "ssr")]
+impl AdminUser {
+    /// Users matching `query` by username, name or email. `filter` narrows them down
+    /// to a status other than active or to a role, and anything else lists everybody.
+    pub async fn search(query: &str, filter: &str, page: i64) -> Result<Vec<Self>, sqlx::Error> {
+        let users = sqlx::query!(
+            "
+            SELECT u.id, u.username, u.name, u.email, u.role, u.status, u.created_at,
+                (SELECT COUNT(*) FROM Articles WHERE author_id = u.id) AS \"articles!\",
+                (SELECT COUNT(*) FROM Comments WHERE user_id = u.id) AS \"comments!\"
+            FROM Users AS u
+            WHERE (u.username ILIKE $1 OR u.name ILIKE $1 OR u.email ILIKE $1)
+                AND CASE $2
+                    WHEN 'suspended' THEN u.status = 'suspended'
+                    WHEN 'deactivated' THEN u.status = 'deactivated'
+                    WHEN 'deleted' THEN u.status = 'deleted'
+                    WHEN 'user' THEN u.role = 'user'
+                    WHEN 'moderator' THEN u.role = 'moderator'
+                    WHEN 'admin' THEN u.role = 'admin'
+                    ELSE TRUE
+                END
+            ORDER BY u.created_at DESC
+            LIMIT $3 OFFSET $4",
+            like_pattern(query),
+            filter,
+            ADMIN_PAGE_SIZE,
+            page * ADMIN_PAGE_SIZE
+        )
+        .fetch_all(crate::database::get_db())
+        .await?;
+
+        users
+            .into_iter()
+            .map(|x| {
+                Ok(Self {
+                    id: x.id,
+                    username: x.username,
+                    name: x.name,
+                    email: x.email,
+                    role: x.role.parse().map_err(|x: String| sqlx::Error::Decode(x.into()))?,
+                    status: x.status.parse().map_err(|x: String| sqlx::Error::Decode(x.into()))?,
+                    articles: x.articles,
+                    comments: x.comments,
+                    created_at: x.created_at.format(super::DATE_FORMAT).to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// Suspends `user_id`, or lifts the suspension, on behalf of `admin_id`. Accounts
+    /// being deleted are left alone.
+    pub async fn set_suspended(
+        user_id: uuid::Uuid,
+        admin_id: uuid::Uuid,
+        suspended: bool,
+    ) -> Result<(), sqlx::Error> {
+        let mut transaction = crate::database::get_db().begin().await?;
+        let username = sqlx::query!(
+            "
+            UPDATE Users SET status = CASE WHEN $2 THEN 'suspended' ELSE 'active' END
+            WHERE id = $1 AND status IN ('active', 'suspended')
+            RETURNING username",
+            user_id,
+            suspended
+        )
+        .fetch_one(transaction.as_mut())
+        .await?
+        .username;
+        let action = if suspended {
+            super::ModerationAction::SuspendUser
+        } else {
+            super::ModerationAction::UnsuspendUser
+        };
+        super::AuditEntry::record(transaction.as_mut(), admin_id, action, user_id, &username).await?;
+        transaction.commit().await
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl AdminArticle {
+    /// Articles matching `query` by title, slug or author. `filter` set to
+    /// `unpublished` only lists the hidden ones.
+    pub async fn search(query: &str, filter: &str, page: i64) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query!(
+            "
+            SELECT a.id, a.slug, a.title, a.author_id, u.username, a.created_at,
+                a.hidden_at IS NOT NULL AS \"unpublished!\",
+                (SELECT COUNT(*) FROM Comments WHERE article_id = a.id) AS \"comments!\"
+            FROM Articles AS a
+                JOIN Users AS u ON u.id = a.author_id
+            WHERE (a.title ILIKE $1 OR a.slug ILIKE $1 OR u.username ILIKE $1)
+                AND ($2 <> 'unpublished' OR a.hidden_at IS NOT NULL)
+            ORDER BY a.created_at DESC
+            LIMIT $3 OFFSET $4",
+            like_pattern(query),
+            filter,
+            ADMIN_PAGE_SIZE,
+            page * ADMIN_PAGE_SIZE
+        )
+        .map(|x| Self {
+            id: x.id,
+            slug: x.slug,
+            title: x.title,
+            author_id: x.author_id,
+            author_username: x.username,
+            unpublished: x.unpublished,
+            comments: x.comments,
+            created_at: x.created_at.format(super::DATE_FORMAT).to_string(),
+        })
+        .fetch_all(crate::database::get_db())
+        .await
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl AdminComment {
+    /// Comments matching `query` by body or author, deleted ones left out. `filter`
+    /// set to `hidden` only lists the hidden ones.
+    pub async fn search(query: &str, filter: &str, page: i64) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query!(
+            "
+            SELECT c.id, c.body, c.user_id, c.created_at, a.slug, a.title, u.username,
+                c.hidden_at IS NOT NULL AS \"hidden!\"
+            FROM Comments AS c
+                JOIN Articles AS a ON a.id = c.article_id
+                JOIN Users AS u ON u.id = c.user_id
+            WHERE NOT c.deleted
+                AND (c.body ILIKE $1 OR u.username ILIKE $1)
+                AND ($2 <> 'hidden' OR c.hidden_at IS NOT NULL)
+            ORDER BY c.created_at DESC
+            LIMIT $3 OFFSET $4",
+            like_pattern(query),
+            filter,
+            ADMIN_PAGE_SIZE,
+            page * ADMIN_PAGE_SIZE
+        )
+        .map(|x| Self {
+            id: x.id,
+            body: x.body,
+            article_slug: x.slug,
+            article_title: x.title,
+            user_id: x.user_id,
+            username: x.username,
+            hidden: x.hidden,
+            created_at: x.created_at.format(super::DATE_FORMAT).to_string(),
+        })
+        .fetch_all(crate::database::get_db())
+        .await
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl DailyStats {
+    /// Signups, articles and comments of each of the last `days` days, today first.
+    pub async fn last_days(days: i32) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query!(
+            "
+            SELECT d.day AS \"day!\",
+                (SELECT COUNT(*) FROM Users
+                    WHERE created_at >= d.day AND created_at < d.day + interval '1 day') AS \"signups!\",
+                (SELECT COUNT(*) FROM Articles
+                    WHERE created_at >= d.day AND created_at < d.day + interval '1 day') AS \"articles!\",
+                (SELECT COUNT(*) FROM Comments
+                    WHERE created_at >= d.day AND created_at < d.day + interval '1 day') AS \"comments!\"
+            FROM generate_series(
+                date_trunc('day', NOW()) - ($1::int - 1) * interval '1 day',
+                date_trunc('day', NOW()),
+                interval '1 day'
+            ) AS d(day)
+            ORDER BY d.day DESC",
+            days
+        )
+        .map(|x| Self {
+            day: x.day.format("%d/%m/%Y").to_string(),
+            signups: x.signups,
+            articles: x.articles,
+            comments: x.comments,
+        })
+        .fetch_all(crate::database::get_db())
+        .await
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn like_pattern_escapes_wildcards() {
+        assert_eq!(like_pattern(" rust "), "%rust%");
+        assert_eq!(like_pattern("100%_done"), "%100\\%\\_done%");
+    }
+}
@@ -1,6 +1,15 @@
-use super::UserPreview;
+use super::{OrganizationPreview, Reaction, ReactionCount, UserPreview};
 use serde::{Deserialize, Serialize};
 
+/// An article with the exact timestamps syndication feeds need.
+#[cfg(feature = "ssr")]
+#[derive(Clone)]
+pub struct FeedArticle {
+    pub article: Article,
+    pub published: chrono::DateTime<chrono::Utc>,
+    pub updated: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Default)]
 pub struct Article {
     pub id: uuid::Uuid,
@@ -9,14 +18,121 @@ pub struct Article {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub body: Option<String>,
     pub description: String,
+    #[serde(default)]
+    pub cover_image: Option<String>,
+    /// Estimated minutes to read the body. The seeder leaves `0` for unknown, read
+    /// as `None` like an empty `cover_image`.
+    #[serde(default)]
+    pub reading_time: Option<i32>,
     pub created_at: String,
+    /// When it was published and last edited, in RFC 3339, for structured data.
+    /// Only filled in when a single article is fetched.
+    #[serde(default)]
+    pub published_at: Option<String>,
+    #[serde(default)]
+    pub modified_at: Option<String>,
     pub favorites_count: i64,
     pub tag_list: Vec<String>,
+    /// The owner, who wrote the article.
     pub author: UserPreview,
+    /// Taken down by a moderator, so only its authors and moderators can see it.
+    #[serde(default)]
+    pub hidden: bool,
+    /// Accepted co-authors, shown next to the owner in bylines.
+    #[serde(default)]
+    pub co_authors: Vec<UserPreview>,
+    /// The organization it was published on behalf of.
+    #[serde(default)]
+    pub organization: Option<OrganizationPreview>,
     pub fav: bool,
+    /// Whether the logged user saved it to their reading list.
+    pub bookmarked: bool,
+    /// Every reaction but favorites, which are `favorites_count` and `fav`.
+    #[serde(default)]
+    pub reactions: Vec<ReactionCount>,
 }
 
+/// Average adult reading speed, as used by most blogging platforms.
+const WORDS_PER_MINUTE: usize = 200;
+
 impl Article {
+    /// Minutes it takes to read `body`, never less than one.
+    pub fn reading_time_of(body: &str) -> i32 {
+        let words = body.split_whitespace().count();
+        words.div_ceil(WORDS_PER_MINUTE).max(1) as i32
+    }
+
+    /// Same slugs as the editor makes from titles, or a generic one when nothing of
+    /// the title is left.
+    pub fn slugify(text: &str) -> String {
+        Self::slug_of(text).unwrap_or_else(|| "article".to_string())
+    }
+
+    /// The slug of `text`, or `None` when it has no letter or digit to make one of.
+    pub fn slug_of(text: &str) -> Option<String> {
+        let slug = text
+            .chars()
+            .map(|c| {
+                let c = c.to_ascii_lowercase();
+                if c == ' ' || c == '_' { '-' } else { c }
+            })
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+            .collect::<String>();
+        slug.chars().any(|c| c.is_ascii_alphanumeric()).then_some(slug)
+    }
+
+    /// All reaction counts, favorites included.
+    pub fn reaction_counts(&self) -> Vec<ReactionCount> {
+        std::iter::once(ReactionCount {
+            kind: Reaction::Favorite,
+            count: self.favorites_count,
+            reacted: self.fav,
+        })
+        .chain(self.reactions.iter().cloned())
+        .collect()
+    }
+
+    /// Records the outcome of toggling a reaction on this article.
+    pub fn apply_reaction(&mut self, kind: Reaction, reacted: bool) {
+        if kind == Reaction::Favorite {
+            if self.fav != reacted {
+                self.fav = reacted;
+                self.favorites_count = (self.favorites_count + if reacted { 1 } else { -1 }).max(0);
+            }
+        } else {
+            ReactionCount::apply(&mut self.reactions, kind, reacted);
+        }
+    }
+
+    /// Takes reaction totals pushed by the server, keeping what the logged user reacted with.
+    pub fn apply_reaction_totals(&mut self, totals: &[ReactionCount]) {
+        (self.favorites_count, _) = ReactionCount::find(totals, Reaction::Favorite);
+        ReactionCount::apply_totals(&mut self.reactions, totals);
+    }
+
+    /// Whether `user_id`, the logged user, may edit it as its owner, an accepted
+    /// co-author or an editor of its organization.
+    pub fn is_editable_by(&self, user_id: uuid::Uuid) -> bool {
+        self.author.user_id == user_id
+            || self.co_authors.iter().any(|x| x.user_id == user_id)
+            || self.organization.as_ref().is_some_and(|x| x.can_edit)
+    }
+
+    #[cfg(feature = "ssr")]
+    async fn with_details(mut articles: Vec<Self>) -> Result<Vec<Self>, sqlx::Error> {
+        let ids = articles.iter().map(|x| x.id).collect::<Vec<_>>();
+        let mut reactions = ReactionCount::for_targets(&ids, false).await?;
+        let mut co_authors = super::CoAuthor::editors_of(&ids).await?;
+        let mut organizations =
+            super::Organization::for_articles(&ids, crate::auth::get_user_id()).await?;
+        for article in &mut articles {
+            article.reactions = reactions.remove(&article.id).unwrap_or_default();
+            article.co_authors = co_authors.remove(&article.id).unwrap_or_default();
+            article.organization = organizations.remove(&article.id);
+        }
+        Ok(articles)
+    }
+
     #[cfg(feature = "ssr")]
     pub async fn for_home_page(
         page: i64,
@@ -25,13 +141,15 @@ impl Article {
         my_feed: bool,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let user_id = crate::auth::get_user_id();
-        sqlx::query!(
+        let articles = sqlx::query!(
             "
             SELECT 
                 a.id,
                 a.slug,
                 a.title,
                 a.description,
+                a.cover_image,
+                a.reading_time,
                 a.created_at,
                 a.tags AS tag_list,
                 (SELECT COUNT(*) FROM FavArticles WHERE article_id = a.id) AS favorites_count,
@@ -40,13 +158,21 @@ impl Article {
                 u.username,
                 u.image,
                 EXISTS(SELECT 1 FROM FavArticles WHERE article_id = a.id AND user_id = $5) AS fav,
-                EXISTS(SELECT 1 FROM Follows WHERE follower_id = $5 AND influencer_id = u.id) AS following
+                EXISTS(SELECT 1 FROM Follows WHERE follower_id = $5 AND influencer_id = u.id) AS following,
+                EXISTS(SELECT 1 FROM Bookmarks WHERE article_id = a.id AND user_id = $5) AS bookmarked
             FROM Articles AS a
             JOIN Users AS u ON a.author_id = u.id
             WHERE
+                a.hidden_at IS NULL
+                AND u.status = 'active'
+                AND
                 ($3 = '' OR $3 = ANY(a.tags))
                 AND
-                (NOT $4 OR u.id IN (SELECT influencer_id FROM Follows WHERE follower_id = $5))
+                (NOT $4
+                    OR u.id IN (SELECT influencer_id FROM Follows WHERE follower_id = $5)
+                    OR a.organization_id IN (SELECT organization_id FROM OrganizationFollows WHERE follower_id = $5))
+                AND NOT EXISTS(SELECT 1 FROM Mutes WHERE muter_id = $5 AND muted_id = u.id)
+                AND NOT EXISTS(SELECT 1 FROM Blocks WHERE blocker_id = $5 AND blocked_id = u.id)
             ORDER BY a.created_at DESC
             LIMIT $1 OFFSET $2",
             amount,
@@ -61,8 +187,13 @@ impl Article {
             title: x.title,
             body: None, // no need
             fav: x.fav.unwrap_or_default(),
+            bookmarked: x.bookmarked.unwrap_or_default(),
             description: x.description,
+            cover_image: x.cover_image.filter(|x| !x.is_empty()),
+            reading_time: x.reading_time.filter(|x| *x > 0),
             created_at: x.created_at.format(super::DATE_FORMAT).to_string(),
+            published_at: None,
+            modified_at: None,
             favorites_count: x.favorites_count.unwrap_or_default(),
             author: UserPreview {
                 user_id: x.author_id,
@@ -72,9 +203,15 @@ impl Article {
                 following: x.following.unwrap_or_default(),
             },
             tag_list: x.tag_list.unwrap_or_default(),
+            reactions: Vec::new(),
+            co_authors: Vec::new(),
+            organization: None,
+            hidden: false,
         })
         .fetch_all(crate::database::get_db())
-        .await
+        .await?;
+
+        Self::with_details(articles).await
     }
 
     #[cfg(feature = "ssr")]
@@ -83,13 +220,15 @@ impl Article {
         favourites: bool,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let logged_user_id = crate::auth::get_user_id();
-        sqlx::query!(
+        let articles = sqlx::query!(
             "
             SELECT 
                 a.id,
                 a.slug,
                 a.title,
                 a.description,
+                a.cover_image,
+                a.reading_time,
                 a.created_at,
                 u.id as author_id,
                 u.username,
@@ -98,13 +237,20 @@ impl Article {
                 (SELECT COUNT(*) FROM FavArticles WHERE article_id = a.id) AS favorites_count,
                 EXISTS(SELECT 1 FROM FavArticles WHERE article_id = a.id AND user_id = $2) AS fav,
                 EXISTS(SELECT 1 FROM Follows WHERE follower_id = $2 AND influencer_id = a.author_id) AS following,
+                EXISTS(SELECT 1 FROM Bookmarks WHERE article_id = a.id AND user_id = $2) AS bookmarked,
+                a.hidden_at IS NOT NULL OR u.status <> 'active' AS hidden,
                 a.tags AS tag_list
             FROM Articles AS a
             JOIN Users AS u ON u.id = a.author_id
             WHERE
+                ((a.hidden_at IS NULL AND u.status = 'active') OR a.author_id = $2)
+                AND
                 CASE WHEN $3 THEN
                     EXISTS(SELECT fa.article_id FROM FavArticles AS fa WHERE fa.article_id = a.id AND fa.user_id = $1)
-                ELSE a.author_id = $1
+                ELSE a.author_id = $1 OR EXISTS(
+                    SELECT 1 FROM ArticleAuthors
+                    WHERE article_id = a.id AND user_id = $1 AND accepted_at IS NOT NULL
+                )
                 END",
             user_id,
             logged_user_id,
@@ -116,10 +262,147 @@ impl Article {
             title: x.title,
             body: None, // no need
             fav: x.fav.unwrap_or_default(),
+            bookmarked: x.bookmarked.unwrap_or_default(),
+            description: x.description,
+            cover_image: x.cover_image.filter(|x| !x.is_empty()),
+            reading_time: x.reading_time.filter(|x| *x > 0),
+            created_at: x.created_at.format(super::DATE_FORMAT).to_string(),
+            published_at: None,
+            modified_at: None,
+            favorites_count: x.favorites_count.unwrap_or_default(),
+            tag_list: x.tag_list.unwrap_or_default(),
+            reactions: Vec::new(),
+            co_authors: Vec::new(),
+            organization: None,
+            hidden: x.hidden.unwrap_or_default(),
+            author: UserPreview {
+                user_id: x.author_id,
+                name: x.name,
+                username: x.username,
+                image: x.image,
+                following: x.following.unwrap_or_default(),
+            },
+        })
+        .fetch_all(crate::database::get_db())
+        .await?;
+
+        Self::with_details(articles).await
+    }
+
+    /// Everything published on behalf of an organization, newest first.
+    #[cfg(feature = "ssr")]
+    pub async fn for_organization(organization_id: uuid::Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        let logged_user_id = crate::auth::get_user_id();
+        let articles = sqlx::query!(
+            "
+            SELECT
+                a.id,
+                a.slug,
+                a.title,
+                a.description,
+                a.cover_image,
+                a.reading_time,
+                a.created_at,
+                u.id as author_id,
+                u.username,
+                u.name,
+                u.image,
+                (SELECT COUNT(*) FROM FavArticles WHERE article_id = a.id) AS favorites_count,
+                EXISTS(SELECT 1 FROM FavArticles WHERE article_id = a.id AND user_id = $2) AS fav,
+                EXISTS(SELECT 1 FROM Follows WHERE follower_id = $2 AND influencer_id = a.author_id) AS following,
+                EXISTS(SELECT 1 FROM Bookmarks WHERE article_id = a.id AND user_id = $2) AS bookmarked,
+                a.tags AS tag_list
+            FROM Articles AS a
+            JOIN Users AS u ON u.id = a.author_id
+            WHERE a.organization_id = $1 AND a.hidden_at IS NULL AND u.status = 'active'
+            ORDER BY a.created_at DESC",
+            organization_id,
+            logged_user_id,
+        )
+        .map(|x| Self {
+            id: x.id,
+            slug: x.slug,
+            title: x.title,
+            body: None, // no need
+            fav: x.fav.unwrap_or_default(),
+            bookmarked: x.bookmarked.unwrap_or_default(),
+            description: x.description,
+            cover_image: x.cover_image.filter(|x| !x.is_empty()),
+            reading_time: x.reading_time.filter(|x| *x > 0),
+            created_at: x.created_at.format(super::DATE_FORMAT).to_string(),
+            published_at: None,
+            modified_at: None,
+            favorites_count: x.favorites_count.unwrap_or_default(),
+            tag_list: x.tag_list.unwrap_or_default(),
+            reactions: Vec::new(),
+            co_authors: Vec::new(),
+            organization: None,
+            hidden: false,
+            author: UserPreview {
+                user_id: x.author_id,
+                name: x.name,
+                username: x.username,
+                image: x.image,
+                following: x.following.unwrap_or_default(),
+            },
+        })
+        .fetch_all(crate::database::get_db())
+        .await?;
+
+        Self::with_details(articles).await
+    }
+
+    /// The private reading list of `user_id`, most recently saved first.
+    #[cfg(feature = "ssr")]
+    pub async fn for_reading_list(
+        user_id: uuid::Uuid,
+        archived: bool,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let articles = sqlx::query!(
+            "
+            SELECT
+                a.id,
+                a.slug,
+                a.title,
+                a.description,
+                a.cover_image,
+                a.reading_time,
+                a.created_at,
+                u.id as author_id,
+                u.username,
+                u.name,
+                u.image,
+                (SELECT COUNT(*) FROM FavArticles WHERE article_id = a.id) AS favorites_count,
+                EXISTS(SELECT 1 FROM FavArticles WHERE article_id = a.id AND user_id = $1) AS fav,
+                EXISTS(SELECT 1 FROM Follows WHERE follower_id = $1 AND influencer_id = a.author_id) AS following,
+                a.tags AS tag_list
+            FROM Bookmarks AS b
+            JOIN Articles AS a ON a.id = b.article_id
+            JOIN Users AS u ON u.id = a.author_id
+            WHERE b.user_id = $1 AND b.archived = $2 AND a.hidden_at IS NULL AND u.status = 'active'
+            ORDER BY b.created_at DESC",
+            user_id,
+            archived,
+        )
+        .map(|x| Self {
+            id: x.id,
+            slug: x.slug,
+            title: x.title,
+            body: None, // no need
+            fav: x.fav.unwrap_or_default(),
+            bookmarked: true,
             description: x.description,
+            cover_image: x.cover_image.filter(|x| !x.is_empty()),
+            reading_time: x.reading_time.filter(|x| *x > 0),
             created_at: x.created_at.format(super::DATE_FORMAT).to_string(),
+            published_at: None,
+            modified_at: None,
             favorites_count: x.favorites_count.unwrap_or_default(),
             tag_list: x.tag_list.unwrap_or_default(),
+            reactions: Vec::new(),
+            co_authors: Vec::new(),
+            organization: None,
+            hidden: false,
             author: UserPreview {
                 user_id: x.author_id,
                 name: x.name,
@@ -129,17 +412,71 @@ impl Article {
             },
         })
         .fetch_all(crate::database::get_db())
+        .await?;
+
+        Self::with_details(articles).await
+    }
+
+    /// Saves the article to the reading list of `user_id`, or removes it, returning
+    /// whether it is now saved.
+    #[cfg(feature = "ssr")]
+    pub async fn toggle_bookmark(
+        article_id: uuid::Uuid,
+        user_id: uuid::Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let mut transaction = crate::database::get_db().begin().await?;
+        let added = sqlx::query!(
+            "INSERT INTO Bookmarks(article_id, user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            article_id,
+            user_id
+        )
+        .execute(transaction.as_mut())
+        .await?
+        .rows_affected()
+            > 0;
+
+        if !added {
+            sqlx::query!(
+                "DELETE FROM Bookmarks WHERE article_id=$1 AND user_id=$2",
+                article_id,
+                user_id
+            )
+            .execute(transaction.as_mut())
+            .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(added)
+    }
+
+    #[cfg(feature = "ssr")]
+    pub async fn set_bookmark_archived(
+        article_id: uuid::Uuid,
+        user_id: uuid::Uuid,
+        archived: bool,
+    ) -> Result<sqlx::postgres::PgQueryResult, sqlx::Error> {
+        sqlx::query!(
+            "UPDATE Bookmarks SET archived=$3, updated_at=NOW() WHERE article_id=$1 AND user_id=$2",
+            article_id,
+            user_id,
+            archived
+        )
+        .execute(crate::database::get_db())
         .await
     }
 
+    /// Hidden articles, like those of authors who are not active anymore, are only
+    /// found by their authors, their co-authors and, with `moderator`, by whoever
+    /// may moderate them.
     #[cfg(feature = "ssr")]
-    pub async fn for_article(slug: String) -> Result<Self, sqlx::Error> {
+    pub async fn for_article(slug: String, moderator: bool) -> Result<Self, sqlx::Error> {
         let user_id = crate::auth::get_user_id();
 
-        sqlx::query!(
-            "
+        let article = sqlx::query!(
+            r#"
             SELECT
-                a.id, a.slug, a.title, a.description, a.body, a.created_at,
+                a.id, a.slug, a.title, a.description, a.body, a.cover_image, a.reading_time, a.created_at,
+                a.updated_at,
                 a.tags AS tag_list,
                 (SELECT COUNT(*) FROM FavArticles WHERE article_id = a.id) AS fav_count,
                 u.id AS author_id,
@@ -147,24 +484,41 @@ impl Article {
                 u.image,
                 u.name,
                 EXISTS(SELECT 1 FROM FavArticles WHERE article_id = a.id AND user_id = $2) AS fav,
-                EXISTS(SELECT 1 FROM Follows WHERE follower_id = $2 AND influencer_id = a.author_id) AS following
+                EXISTS(SELECT 1 FROM Follows WHERE follower_id = $2 AND influencer_id = a.author_id) AS following,
+                EXISTS(SELECT 1 FROM Bookmarks WHERE article_id = a.id AND user_id = $2) AS bookmarked,
+                a.hidden_at IS NOT NULL OR u.status <> 'active' AS "hidden!"
             FROM Articles a
             JOIN Users u ON a.author_id = u.id
-            WHERE a.slug = $1
-            ",
+            WHERE a.slug = $1 AND (
+                (a.hidden_at IS NULL AND u.status = 'active')
+                OR a.author_id = $2
+                OR $3
+                OR EXISTS(SELECT 1 FROM ArticleAuthors WHERE article_id = a.id AND user_id = $2 AND accepted_at IS NOT NULL)
+            )
+            "#,
             slug,
             user_id,
+            moderator,
         )
         .map(|x| Self {
             id: x.id,
             slug: x.slug,
             title: x.title,
             description: x.description,
+            cover_image: x.cover_image.filter(|x| !x.is_empty()),
+            reading_time: x.reading_time.filter(|x| *x > 0),
             body: Some(x.body),
             tag_list: x.tag_list.unwrap_or_default(),
+            reactions: Vec::new(),
+            co_authors: Vec::new(),
+            organization: None,
+            hidden: x.hidden,
             favorites_count: x.fav_count.unwrap_or_default(),
             created_at: x.created_at.format(super::DATE_FORMAT).to_string(),
+            published_at: Some(x.created_at.to_rfc3339()),
+            modified_at: Some(x.updated_at.to_rfc3339()),
             fav: x.fav.unwrap_or_default(),
+            bookmarked: x.bookmarked.unwrap_or_default(),
             author: UserPreview {
                 user_id: x.author_id,
                 name: x.name,
@@ -174,9 +528,13 @@ impl Article {
             },
         })
         .fetch_one(crate::database::get_db())
-        .await
+        .await?;
+
+        let mut articles = Self::with_details(vec![article]).await?;
+        Ok(articles.remove(0))
     }
 
+    /// Only the owner can delete an article, co-authors can leave it instead.
     #[cfg(feature = "ssr")]
     pub async fn delete(
         slug: String,
@@ -190,4 +548,163 @@ impl Article {
         .execute(crate::database::get_db())
         .await
     }
+
+    /// Deletes any article on behalf of a moderator, keeping track of it.
+    #[cfg(feature = "ssr")]
+    pub async fn delete_as_moderator(
+        slug: &str,
+        moderator_id: uuid::Uuid,
+    ) -> Result<(), sqlx::Error> {
+        let mut transaction = crate::database::get_db().begin().await?;
+        let article = sqlx::query!(
+            "DELETE FROM Articles WHERE slug=$1 RETURNING id, title",
+            slug
+        )
+        .fetch_one(transaction.as_mut())
+        .await?;
+        super::AuditEntry::record(
+            transaction.as_mut(),
+            moderator_id,
+            super::ModerationAction::DeleteArticle,
+            article.id,
+            &article.title,
+        )
+        .await?;
+        transaction.commit().await
+    }
+
+    /// Hides an article from everybody but its authors and moderators, or shows it again.
+    #[cfg(feature = "ssr")]
+    pub async fn set_hidden(
+        slug: &str,
+        moderator_id: uuid::Uuid,
+        hidden: bool,
+    ) -> Result<(), sqlx::Error> {
+        let mut transaction = crate::database::get_db().begin().await?;
+        let article = sqlx::query!(
+            "
+            UPDATE Articles SET hidden_at = CASE WHEN $2 THEN COALESCE(hidden_at, NOW()) END,
+                hidden_by_reports = FALSE
+            WHERE slug=$1
+            RETURNING id, title",
+            slug,
+            hidden
+        )
+        .fetch_one(transaction.as_mut())
+        .await?;
+        let action = if hidden {
+            super::ModerationAction::HideArticle
+        } else {
+            super::ModerationAction::UnhideArticle
+        };
+        super::AuditEntry::record(transaction.as_mut(), moderator_id, action, article.id, &article.title)
+            .await?;
+        transaction.commit().await
+    }
+
+    /// Latest articles for a syndication feed, optionally limited to one tag or one author.
+    #[cfg(feature = "ssr")]
+    pub async fn for_feed(
+        tag: Option<String>,
+        author_id: Option<uuid::Uuid>,
+        amount: i64,
+    ) -> Result<Vec<FeedArticle>, sqlx::Error> {
+        sqlx::query!(
+            "
+            SELECT
+                a.id, a.slug, a.title, a.description, a.body, a.cover_image, a.reading_time, a.created_at, a.updated_at,
+                a.tags AS tag_list,
+                (SELECT COUNT(*) FROM FavArticles WHERE article_id = a.id) AS favorites_count,
+                u.id AS author_id,
+                u.username,
+                u.image,
+                u.name
+            FROM Articles AS a
+            JOIN Users AS u ON a.author_id = u.id
+            WHERE a.hidden_at IS NULL AND u.status = 'active'
+                AND ($1::text IS NULL OR $1 = ANY(a.tags))
+                AND ($2::uuid IS NULL OR a.author_id = $2)
+            ORDER BY a.created_at DESC
+            LIMIT $3",
+            tag,
+            author_id,
+            amount,
+        )
+        .map(|x| FeedArticle {
+            published: x.created_at,
+            updated: x.updated_at,
+            article: Self {
+                id: x.id,
+                slug: x.slug,
+                title: x.title,
+                description: x.description,
+                cover_image: x.cover_image.filter(|x| !x.is_empty()),
+                reading_time: x.reading_time.filter(|x| *x > 0),
+                body: Some(x.body),
+                tag_list: x.tag_list.unwrap_or_default(),
+                reactions: Vec::new(),
+                co_authors: Vec::new(),
+                organization: None,
+                hidden: false,
+                favorites_count: x.favorites_count.unwrap_or_default(),
+                created_at: x.created_at.format(super::DATE_FORMAT).to_string(),
+                published_at: None,
+                modified_at: None,
+                fav: false,
+                bookmarked: false,
+                author: UserPreview {
+                    user_id: x.author_id,
+                    name: x.name,
+                    username: x.username,
+                    image: x.image,
+                    following: false,
+                },
+            },
+        })
+        .fetch_all(crate::database::get_db())
+        .await
+    }
+
+    /// Slug and last change of every article, for the sitemap.
+    #[cfg(feature = "ssr")]
+    pub async fn for_sitemap() -> Result<Vec<(String, chrono::DateTime<chrono::Utc>)>, sqlx::Error> {
+        sqlx::query!(
+            "
+            SELECT a.slug, a.updated_at FROM Articles AS a
+            JOIN Users AS u ON u.id = a.author_id
+            WHERE a.hidden_at IS NULL AND u.status = 'active'
+            ORDER BY a.created_at DESC"
+        )
+        .map(|x| (x.slug, x.updated_at))
+        .fetch_all(crate::database::get_db())
+        .await
+    }
+
+    /// Every author with a public article and the last change to those, for the sitemap.
+    #[cfg(feature = "ssr")]
+    pub async fn authors_for_sitemap(
+    ) -> Result<Vec<(uuid::Uuid, chrono::DateTime<chrono::Utc>)>, sqlx::Error> {
+        sqlx::query!(
+            "
+            SELECT a.author_id, MAX(a.updated_at) AS \"updated_at!\" FROM Articles AS a
+            JOIN Users AS u ON u.id = a.author_id
+            WHERE a.hidden_at IS NULL AND u.status = 'active'
+            GROUP BY a.author_id"
+        )
+        .map(|x| (x.author_id, x.updated_at))
+        .fetch_all(crate::database::get_db())
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reading_time_rounds_up() {
+        assert_eq!(Article::reading_time_of(""), 1);
+        assert_eq!(Article::reading_time_of(&"word ".repeat(200)), 1);
+        assert_eq!(Article::reading_time_of(&"word\n".repeat(201)), 2);
+    }
 }
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+
+/// Something a moderator or an admin did to content they do not own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationAction {
+    DeleteArticle,
+    HideArticle,
+    UnhideArticle,
+    DeleteComment,
+    HideComment,
+    UnhideComment,
+    SetRole,
+    SuspendUser,
+    UnsuspendUser,
+    ResolveReports,
+    DismissReports,
+}
+
+impl ModerationAction {
+    const ALL: [ModerationAction; 11] = [
+        ModerationAction::DeleteArticle,
+        ModerationAction::HideArticle,
+        ModerationAction::UnhideArticle,
+        ModerationAction::DeleteComment,
+        ModerationAction::HideComment,
+        ModerationAction::UnhideComment,
+        ModerationAction::SetRole,
+        ModerationAction::SuspendUser,
+        ModerationAction::UnsuspendUser,
+        ModerationAction::ResolveReports,
+        ModerationAction::DismissReports,
+    ];
+
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ModerationAction::DeleteArticle => "delete_article",
+            ModerationAction::HideArticle => "hide_article",
+            ModerationAction::UnhideArticle => "unhide_article",
+            ModerationAction::DeleteComment => "delete_comment",
+            ModerationAction::HideComment => "hide_comment",
+            ModerationAction::UnhideComment => "unhide_comment",
+            ModerationAction::SetRole => "set_role",
+            ModerationAction::SuspendUser => "suspend_user",
+            ModerationAction::UnsuspendUser => "unsuspend_user",
+            ModerationAction::ResolveReports => "resolve_reports",
+            ModerationAction::DismissReports => "dismiss_reports",
+        }
+    }
+
+    #[inline]
+    pub fn label(self) -> &'static str {
+        match self {
+            ModerationAction::DeleteArticle => "deleted the article",
+            ModerationAction::HideArticle => "hid the article",
+            ModerationAction::UnhideArticle => "restored the article",
+            ModerationAction::DeleteComment => "deleted the comment",
+            ModerationAction::HideComment => "hid the comment",
+            ModerationAction::UnhideComment => "restored the comment",
+            ModerationAction::SetRole => "changed the role of",
+            ModerationAction::SuspendUser => "suspended",
+            ModerationAction::UnsuspendUser => "lifted the suspension of",
+            ModerationAction::ResolveReports => "resolved the reports on",
+            ModerationAction::DismissReports => "dismissed the reports on",
+        }
+    }
+}
+
+impl std::str::FromStr for ModerationAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.as_str() == s)
+            .ok_or_else(|| format!("Unknown moderation action {s}"))
+    }
+}
+
+/// One line of the moderation audit trail.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub actor_id: Option<uuid::Uuid>,
+    pub actor_name: Option<String>,
+    pub action: ModerationAction,
+    pub target_id: uuid::Uuid,
+    /// What the target was, since it may be gone.
+    pub details: String,
+    pub created_at: String,
+}
+
+#[cfg(feature = "ssr")]
+impl AuditEntry {
+    /// Meant to run in the transaction of the action itself, so neither happens alone.
+    /// Actions taken automatically have no actor.
+    pub async fn record(
+        connection: &mut sqlx::PgConnection,
+        actor_id: impl Into<Option<uuid::Uuid>>,
+        action: ModerationAction,
+        target_id: uuid::Uuid,
+        details: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO ModerationLog(actor_id, action, target_id, details) VALUES ($1, $2, $3, $4)",
+            actor_id.into(),
+            action.as_str(),
+            target_id,
+            details
+        )
+        .execute(connection)
+        .await
+        .map(|_| ())
+    }
+
+    /// Most recent entries first.
+    pub async fn latest(limit: i64) -> Result<Vec<Self>, sqlx::Error> {
+        let entries = sqlx::query!(
+            "
+            SELECT l.actor_id, u.username AS \"actor_name?\", l.action, l.target_id, l.details, l.created_at
+            FROM ModerationLog AS l
+                LEFT JOIN Users AS u ON u.id = l.actor_id
+            ORDER BY l.created_at DESC
+            LIMIT $1",
+            limit
+        )
+        .fetch_all(crate::database::get_db())
+        .await?;
+
+        entries
+            .into_iter()
+            .map(|x| {
+                Ok(Self {
+                    actor_id: x.actor_id,
+                    actor_name: x.actor_name,
+                    action: x
+                        .action
+                        .parse()
+                        .map_err(|x: String| sqlx::Error::Decode(x.into()))?,
+                    target_id: x.target_id,
+                    details: x.details,
+                    created_at: x.created_at.format(super::DATE_FORMAT).to_string(),
+                })
+            })
+            .collect()
+    }
+}
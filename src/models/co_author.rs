@@ -0,0 +1,220 @@
+use super::UserPreview;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthorRole {
+    /// Wrote the article, and alone can delete it or invite others.
+    Owner,
+    /// Can edit the article once the invitation is accepted.
+    Editor,
+}
+
+impl AuthorRole {
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuthorRole::Owner => "owner",
+            AuthorRole::Editor => "editor",
+        }
+    }
+}
+
+impl std::str::FromStr for AuthorRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "owner" => Ok(AuthorRole::Owner),
+            "editor" => Ok(AuthorRole::Editor),
+            _ => Err(format!("Unknown author role {s}")),
+        }
+    }
+}
+
+/// One of the people who can edit an article. `Articles.author_id` stays the owner,
+/// so bylines and permissions hold even for articles without rows here.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CoAuthor {
+    pub user: UserPreview,
+    pub role: AuthorRole,
+    /// Invited but not accepted yet.
+    pub pending: bool,
+}
+
+#[cfg(feature = "ssr")]
+impl CoAuthor {
+    /// Everybody on the article, owner first, pending invitations included.
+    pub async fn for_article(slug: &str) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query!(
+            "
+            SELECT u.id, u.username, u.name, u.image, aa.role, aa.accepted_at IS NULL AS pending
+            FROM ArticleAuthors AS aa
+            JOIN Articles AS a ON a.id = aa.article_id
+            JOIN Users AS u ON u.id = aa.user_id
+            WHERE a.slug = $1
+            ORDER BY aa.role = 'owner' DESC, aa.created_at",
+            slug
+        )
+        .fetch_all(crate::database::get_db())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|x| {
+                Some(Self {
+                    user: UserPreview {
+                        user_id: x.id,
+                        username: x.username,
+                        name: x.name,
+                        image: x.image,
+                        following: false,
+                    },
+                    role: x.role.parse().ok()?,
+                    pending: x.pending.unwrap_or_default(),
+                })
+            })
+            .collect())
+    }
+
+    /// The accepted editors of each of `article_ids`, for bylines.
+    pub async fn editors_of(
+        article_ids: &[uuid::Uuid],
+    ) -> Result<std::collections::HashMap<uuid::Uuid, Vec<UserPreview>>, sqlx::Error> {
+        let rows = sqlx::query!(
+            "
+            SELECT aa.article_id, u.id, u.username, u.name, u.image
+            FROM ArticleAuthors AS aa
+            JOIN Users AS u ON u.id = aa.user_id
+            WHERE aa.article_id = ANY($1) AND aa.role = 'editor' AND aa.accepted_at IS NOT NULL
+            ORDER BY aa.accepted_at",
+            article_ids
+        )
+        .fetch_all(crate::database::get_db())
+        .await?;
+
+        let mut editors = std::collections::HashMap::<_, Vec<_>>::new();
+        for x in rows {
+            editors.entry(x.article_id).or_default().push(UserPreview {
+                user_id: x.id,
+                username: x.username,
+                name: x.name,
+                image: x.image,
+                following: false,
+            });
+        }
+        Ok(editors)
+    }
+
+    /// Whether `user_id` has an invitation to `slug` waiting for an answer.
+    pub async fn is_invited(slug: &str, user_id: uuid::Uuid) -> Result<bool, sqlx::Error> {
+        sqlx::query!(
+            "
+            SELECT EXISTS(
+                SELECT 1 FROM ArticleAuthors AS aa
+                JOIN Articles AS a ON a.id = aa.article_id
+                WHERE a.slug = $1 AND aa.user_id = $2 AND aa.accepted_at IS NULL
+            )",
+            slug,
+            user_id
+        )
+        .fetch_one(crate::database::get_db())
+        .await
+        .map(|x| x.exists.unwrap_or_default())
+    }
+
+    /// Invites `username` to edit the article `owner_id` owns. Returns the article and
+    /// the invited user, or `None` when there was nobody new to invite.
+    pub async fn invite(
+        slug: &str,
+        owner_id: uuid::Uuid,
+        username: &str,
+    ) -> Result<Option<(uuid::Uuid, uuid::Uuid)>, sqlx::Error> {
+        sqlx::query!(
+            "
+            INSERT INTO ArticleAuthors(article_id, user_id, role, invited_by)
+            SELECT a.id, u.id, 'editor', $2
+            FROM Articles AS a, Users AS u
+            WHERE a.slug = $1 AND a.author_id = $2 AND u.username = $3 AND u.id <> $2
+            ON CONFLICT (article_id, user_id) DO NOTHING
+            RETURNING article_id, user_id",
+            slug,
+            owner_id,
+            username
+        )
+        .fetch_optional(crate::database::get_db())
+        .await
+        .map(|x| x.map(|x| (x.article_id, x.user_id)))
+    }
+
+    /// Accepts or declines the pending invitation of `user_id` to `slug`.
+    pub async fn respond(
+        slug: &str,
+        user_id: uuid::Uuid,
+        accept: bool,
+    ) -> Result<sqlx::postgres::PgQueryResult, sqlx::Error> {
+        let db = crate::database::get_db();
+        if accept {
+            sqlx::query!(
+                "
+                UPDATE ArticleAuthors SET accepted_at = NOW()
+                WHERE user_id = $2 AND accepted_at IS NULL
+                    AND article_id = (SELECT id FROM Articles WHERE slug = $1)",
+                slug,
+                user_id
+            )
+            .execute(db)
+            .await
+        } else {
+            sqlx::query!(
+                "
+                DELETE FROM ArticleAuthors
+                WHERE user_id = $2 AND accepted_at IS NULL
+                    AND article_id = (SELECT id FROM Articles WHERE slug = $1)",
+                slug,
+                user_id
+            )
+            .execute(db)
+            .await
+        }
+    }
+
+    /// Takes `user_id` off the article. The owner can remove anybody else, while
+    /// editors can only leave.
+    pub async fn remove(
+        slug: &str,
+        actor_id: uuid::Uuid,
+        user_id: uuid::Uuid,
+    ) -> Result<sqlx::postgres::PgQueryResult, sqlx::Error> {
+        sqlx::query!(
+            "
+            DELETE FROM ArticleAuthors AS aa
+            USING Articles AS a
+            WHERE a.id = aa.article_id AND a.slug = $1 AND aa.user_id = $3 AND aa.role = 'editor'
+                AND ($2 = $3 OR a.author_id = $2)",
+            slug,
+            actor_id,
+            user_id
+        )
+        .execute(crate::database::get_db())
+        .await
+    }
+
+    /// Records the owner of a new article.
+    pub async fn add_owner(
+        connection: &mut sqlx::PgConnection,
+        slug: &str,
+        owner_id: uuid::Uuid,
+    ) -> Result<sqlx::postgres::PgQueryResult, sqlx::Error> {
+        sqlx::query!(
+            "
+            INSERT INTO ArticleAuthors(article_id, user_id, role, accepted_at)
+            SELECT id, $2, 'owner', NOW() FROM Articles WHERE slug = $1
+            ON CONFLICT (article_id, user_id) DO NOTHING",
+            slug,
+            owner_id
+        )
+        .execute(connection)
+        .await
+    }
+}
@@ -0,0 +1,328 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportStatus {
+    Pending,
+    Ready,
+    Failed,
+}
+
+impl ExportStatus {
+    pub const ALL: [ExportStatus; 3] = [ExportStatus::Pending, ExportStatus::Ready, ExportStatus::Failed];
+
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExportStatus::Pending => "pending",
+            ExportStatus::Ready => "ready",
+            ExportStatus::Failed => "failed",
+        }
+    }
+}
+
+impl std::str::FromStr for ExportStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.as_str() == s)
+            .ok_or_else(|| format!("Unknown export status {s}"))
+    }
+}
+
+/// The latest export a user asked for, with its download link once it is ready.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataExport {
+    pub id: uuid::Uuid,
+    pub status: ExportStatus,
+    pub requested_at: String,
+    pub download_url: Option<String>,
+}
+
+/// Everything a user put on the site, as written to `data.json` in their export.
+#[cfg(feature = "ssr")]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PersonalData {
+    pub profile: ExportedProfile,
+    pub articles: Vec<ExportedArticle>,
+    pub comments: Vec<ExportedComment>,
+    /// Usernames of the followed users.
+    pub following: Vec<String>,
+    /// Slugs of the favorited articles.
+    pub favorites: Vec<String>,
+    /// Slugs of the bookmarked articles.
+    pub bookmarks: Vec<String>,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExportedProfile {
+    pub username: String,
+    pub name: String,
+    pub email: String,
+    pub bio: Option<String>,
+    pub image: Option<String>,
+    pub created_at: String,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedArticle {
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+    pub body: String,
+    pub tags: Vec<String>,
+    pub cover_image: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedComment {
+    pub article_slug: String,
+    pub body: String,
+    pub created_at: String,
+    /// Earlier versions of the body, the oldest first.
+    pub revisions: Vec<String>,
+}
+
+#[cfg(feature = "ssr")]
+impl DataExport {
+    /// How long a pending export may run before another one can be asked for, in
+    /// case the server stopped in the middle of it.
+    const STALE_AFTER_MINUTES: i32 = 60;
+
+    /// Blob store key of the archive.
+    pub fn key(id: uuid::Uuid) -> String {
+        format!("export-{id}.zip")
+    }
+
+    /// Queues a new export for `user_id`, or returns `None` when one is already
+    /// being built.
+    pub async fn request(user_id: uuid::Uuid) -> Result<Option<uuid::Uuid>, sqlx::Error> {
+        let mut transaction = crate::database::get_db().begin().await?;
+        sqlx::query!(
+            "
+            UPDATE DataExports SET status = 'failed', finished_at = NOW()
+            WHERE user_id = $1 AND status = 'pending'
+                AND created_at <= NOW() - make_interval(mins => $2)",
+            user_id,
+            Self::STALE_AFTER_MINUTES,
+        )
+        .execute(transaction.as_mut())
+        .await?;
+        // The unique index on pending exports settles concurrent requests
+        let id = sqlx::query!(
+            "INSERT INTO DataExports(user_id) VALUES ($1) ON CONFLICT DO NOTHING RETURNING id",
+            user_id,
+        )
+        .fetch_optional(transaction.as_mut())
+        .await?
+        .map(|x| x.id);
+
+        transaction.commit().await?;
+        Ok(id)
+    }
+
+    pub async fn finish(id: uuid::Uuid, status: ExportStatus) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE DataExports SET status = $2, finished_at = NOW() WHERE id = $1",
+            id,
+            status.as_str()
+        )
+        .execute(crate::database::get_db())
+        .await
+        .map(|_| ())
+    }
+
+    /// Deletes the exports whose download link expired or that a newer export
+    /// replaced, archive included. Returns how many exports were deleted.
+    pub async fn delete_expired() -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let mut transaction = crate::database::get_db().begin().await?;
+        let ids = sqlx::query!(
+            "
+            DELETE FROM DataExports AS e
+            WHERE COALESCE(e.finished_at, e.created_at) <= NOW() - make_interval(days => $1)
+                OR (e.status <> 'pending' AND EXISTS(
+                    SELECT 1 FROM DataExports AS n
+                    WHERE n.user_id = e.user_id AND n.status = 'ready' AND n.created_at > e.created_at
+                ))
+            RETURNING e.id",
+            crate::auth::EXPORT_EXPIRATION_DAYS as i32,
+        )
+        .fetch_all(transaction.as_mut())
+        .await?
+        .into_iter()
+        .map(|x| x.id)
+        .collect::<Vec<_>>();
+
+        // Before committing, so the rows stay until their archives are gone
+        Self::delete_archives(&ids).await?;
+        transaction.commit().await?;
+        Ok(ids.len())
+    }
+
+    /// Deletes the archives of the exports `ids`, whether they were built or not.
+    pub async fn delete_archives(ids: &[uuid::Uuid]) -> Result<(), crate::storage::StorageError> {
+        let store = crate::storage::get_store();
+        for id in ids {
+            store.delete(&Self::key(*id)).await?;
+        }
+        Ok(())
+    }
+
+    /// The latest export of `user_id`, with a fresh download link when it is ready.
+    pub async fn latest(user_id: uuid::Uuid) -> Result<Option<Self>, sqlx::Error> {
+        let Some(x) = sqlx::query!(
+            "
+            SELECT id, status, created_at, finished_at FROM DataExports
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            LIMIT 1",
+            user_id
+        )
+        .fetch_optional(crate::database::get_db())
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let status: ExportStatus = x.status.parse().map_err(|x: String| sqlx::Error::Decode(x.into()))?;
+        let download_url = match (status, x.finished_at) {
+            (ExportStatus::Ready, Some(ready_at)) => Some(format!(
+                "/exports/download?token={}",
+                crate::auth::generate_export_token(x.id, ready_at)
+            )),
+            _ => None,
+        };
+        Ok(Some(Self {
+            id: x.id,
+            status,
+            requested_at: x.created_at.format(super::DATE_FORMAT).to_string(),
+            download_url,
+        }))
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl PersonalData {
+    pub async fn collect(user_id: uuid::Uuid) -> Result<Self, sqlx::Error> {
+        let db = crate::database::get_db();
+
+        let profile = sqlx::query!(
+            "SELECT username, name, email, bio, image, created_at FROM Users WHERE id = $1",
+            user_id
+        )
+        .map(|x| ExportedProfile {
+            username: x.username,
+            name: x.name,
+            email: x.email,
+            bio: x.bio,
+            image: x.image,
+            created_at: x.created_at.to_rfc3339(),
+        })
+        .fetch_one(db)
+        .await?;
+
+        let articles = ExportedArticle::by_author(user_id).await?;
+
+        let comments = sqlx::query!(
+            "
+            SELECT a.slug, c.body, c.created_at,
+                ARRAY(
+                    SELECT r.body FROM CommentRevisions AS r
+                    WHERE r.comment_id = c.id
+                    ORDER BY r.created_at
+                ) AS \"revisions!\"
+            FROM Comments AS c
+                JOIN Articles AS a ON a.id = c.article_id
+            WHERE c.user_id = $1 AND NOT c.deleted
+            ORDER BY c.created_at",
+            user_id
+        )
+        .map(|x| ExportedComment {
+            article_slug: x.slug,
+            body: x.body,
+            created_at: x.created_at.to_rfc3339(),
+            revisions: x.revisions,
+        })
+        .fetch_all(db)
+        .await?;
+
+        let following = sqlx::query!(
+            "
+            SELECT u.username FROM Follows AS f
+                JOIN Users AS u ON u.id = f.influencer_id
+            WHERE f.follower_id = $1
+            ORDER BY u.username",
+            user_id
+        )
+        .map(|x| x.username)
+        .fetch_all(db)
+        .await?;
+
+        let favorites = sqlx::query!(
+            "
+            SELECT a.slug FROM FavArticles AS f
+                JOIN Articles AS a ON a.id = f.article_id
+            WHERE f.user_id = $1
+            ORDER BY f.created_at",
+            user_id
+        )
+        .map(|x| x.slug)
+        .fetch_all(db)
+        .await?;
+
+        let bookmarks = sqlx::query!(
+            "
+            SELECT a.slug FROM Bookmarks AS b
+                JOIN Articles AS a ON a.id = b.article_id
+            WHERE b.user_id = $1
+            ORDER BY b.created_at",
+            user_id
+        )
+        .map(|x| x.slug)
+        .fetch_all(db)
+        .await?;
+
+        Ok(Self {
+            profile,
+            articles,
+            comments,
+            following,
+            favorites,
+            bookmarks,
+        })
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl ExportedArticle {
+    /// Articles written by `user_id`, the oldest first.
+    pub async fn by_author(user_id: uuid::Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query!(
+            "
+            SELECT slug, title, description, body, tags, cover_image, created_at, updated_at
+            FROM Articles
+            WHERE author_id = $1
+            ORDER BY created_at",
+            user_id
+        )
+        .map(|x| Self {
+            slug: x.slug,
+            title: x.title,
+            description: x.description,
+            body: x.body,
+            tags: x.tags.unwrap_or_default(),
+            cover_image: x.cover_image,
+            created_at: x.created_at.to_rfc3339(),
+            updated_at: x.updated_at.to_rfc3339(),
+        })
+        .fetch_all(crate::database::get_db())
+        .await
+    }
+}
@@ -0,0 +1,211 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestFrequency {
+    #[default]
+    Never,
+    Daily,
+    Weekly,
+}
+
+impl DigestFrequency {
+    pub const ALL: [DigestFrequency; 3] = [
+        DigestFrequency::Never,
+        DigestFrequency::Daily,
+        DigestFrequency::Weekly,
+    ];
+
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DigestFrequency::Never => "never",
+            DigestFrequency::Daily => "daily",
+            DigestFrequency::Weekly => "weekly",
+        }
+    }
+
+    #[inline]
+    pub fn label(self) -> &'static str {
+        match self {
+            DigestFrequency::Never => "Never",
+            DigestFrequency::Daily => "Daily",
+            DigestFrequency::Weekly => "Weekly",
+        }
+    }
+
+    /// How far back the first digest looks.
+    #[cfg(feature = "ssr")]
+    pub fn period(self) -> chrono::Duration {
+        match self {
+            DigestFrequency::Never | DigestFrequency::Daily => chrono::Duration::days(1),
+            DigestFrequency::Weekly => chrono::Duration::weeks(1),
+        }
+    }
+}
+
+impl std::str::FromStr for DigestFrequency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.as_str() == s)
+            .ok_or_else(|| format!("Unknown digest frequency {s}"))
+    }
+}
+
+/// What a user wants in their digest, besides articles from authors they follow.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DigestPreferences {
+    pub frequency: DigestFrequency,
+    pub tags: Vec<String>,
+}
+
+/// A user whose digest is due.
+#[cfg(feature = "ssr")]
+#[derive(Debug, Clone)]
+pub struct DigestRecipient {
+    pub user_id: uuid::Uuid,
+    pub name: String,
+    pub email: String,
+    pub tags: Vec<String>,
+    pub since: chrono::DateTime<chrono::Utc>,
+    /// When the previous digest was sent, to give the claim back if this one isn't.
+    pub last_sent_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestArticle {
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+    pub author: String,
+}
+
+#[cfg(feature = "ssr")]
+impl DigestPreferences {
+    pub async fn get(user_id: uuid::Uuid) -> Result<Self, sqlx::Error> {
+        sqlx::query!(
+            "SELECT frequency, tags FROM DigestPreferences WHERE user_id = $1",
+            user_id
+        )
+        .fetch_optional(crate::database::get_db())
+        .await
+        .map(|x| {
+            x.map(|x| Self {
+                frequency: x.frequency.parse().unwrap_or_default(),
+                tags: x.tags,
+            })
+            .unwrap_or_default()
+        })
+    }
+
+    pub async fn save(&self, user_id: uuid::Uuid) -> Result<sqlx::postgres::PgQueryResult, sqlx::Error> {
+        sqlx::query!(
+            "
+            INSERT INTO DigestPreferences(user_id, frequency, tags) VALUES ($1, $2, $3)
+            ON CONFLICT (user_id) DO UPDATE SET frequency = $2, tags = $3, updated_at = NOW()",
+            user_id,
+            self.frequency.as_str(),
+            &self.tags,
+        )
+        .execute(crate::database::get_db())
+        .await
+    }
+
+    pub async fn unsubscribe(user_id: uuid::Uuid) -> Result<sqlx::postgres::PgQueryResult, sqlx::Error> {
+        sqlx::query!(
+            "UPDATE DigestPreferences SET frequency = 'never', updated_at = NOW() WHERE user_id = $1",
+            user_id
+        )
+        .execute(crate::database::get_db())
+        .await
+    }
+
+    /// Users whose last digest is at least one period old, marked as sent right
+    /// away so that another server running the scheduler doesn't pick them too.
+    pub async fn claim_due() -> Result<Vec<DigestRecipient>, sqlx::Error> {
+        let now = chrono::Utc::now();
+        sqlx::query!(
+            "
+            WITH due AS (
+                SELECT p.user_id, p.last_sent_at
+                FROM DigestPreferences AS p
+                JOIN Users AS u ON u.id = p.user_id
+                WHERE p.frequency <> 'never' AND u.status = 'active'
+                    AND (p.last_sent_at IS NULL OR p.last_sent_at <= NOW() - CASE p.frequency
+                        WHEN 'daily' THEN INTERVAL '1 day'
+                        ELSE INTERVAL '7 days'
+                    END)
+                FOR UPDATE OF p SKIP LOCKED
+            )
+            UPDATE DigestPreferences AS p SET last_sent_at = NOW()
+            FROM due, Users AS u
+            WHERE p.user_id = due.user_id AND u.id = due.user_id
+            RETURNING p.user_id, p.frequency, p.tags, due.last_sent_at, u.name, u.email"
+        )
+        .fetch_all(crate::database::get_db())
+        .await
+        .map(|rows| {
+            rows.into_iter()
+                .map(|x| {
+                    let frequency: DigestFrequency = x.frequency.parse().unwrap_or_default();
+                    DigestRecipient {
+                        user_id: x.user_id,
+                        name: x.name,
+                        email: x.email,
+                        tags: x.tags,
+                        since: x.last_sent_at.unwrap_or(now - frequency.period()),
+                        last_sent_at: x.last_sent_at,
+                    }
+                })
+                .collect()
+        })
+    }
+
+    /// Makes the digest of `recipient` due again, after it could not be sent.
+    pub async fn release(recipient: &DigestRecipient) -> Result<sqlx::postgres::PgQueryResult, sqlx::Error> {
+        sqlx::query!(
+            "UPDATE DigestPreferences SET last_sent_at = $2 WHERE user_id = $1",
+            recipient.user_id,
+            recipient.last_sent_at,
+        )
+        .execute(crate::database::get_db())
+        .await
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl DigestArticle {
+    /// New articles from followed authors or followed tags since the last digest.
+    pub async fn for_recipient(recipient: &DigestRecipient) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query!(
+            "
+            SELECT a.slug, a.title, a.description, u.name AS author
+            FROM Articles AS a
+            JOIN Users AS u ON u.id = a.author_id
+            WHERE a.created_at > $2 AND a.hidden_at IS NULL AND u.status = 'active'
+                AND a.author_id <> $1
+                AND NOT EXISTS(SELECT 1 FROM Mutes WHERE muter_id = $1 AND muted_id = a.author_id)
+                AND NOT EXISTS(SELECT 1 FROM Blocks WHERE blocker_id = $1 AND blocked_id = a.author_id)
+                AND (
+                    a.author_id IN (SELECT influencer_id FROM Follows WHERE follower_id = $1)
+                    OR a.tags && $3
+                )
+            ORDER BY a.created_at DESC
+            LIMIT 20",
+            recipient.user_id,
+            recipient.since,
+            &recipient.tags,
+        )
+        .map(|x| Self {
+            slug: x.slug,
+            title: x.title,
+            description: x.description,
+            author: x.author,
+        })
+        .fetch_all(crate::database::get_db())
+        .await
+    }
+}
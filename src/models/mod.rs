@@ -1,11 +1,45 @@
 mod user;
-pub use user::{User, UserPreview};
+pub use user::{User, UserPreview, UserStatus};
+#[cfg(feature = "ssr")]
+pub use user::deletion_grace_days;
+mod organization;
+pub use organization::{OrgRole, Organization, OrganizationMember, OrganizationPreview};
 mod pagination;
 pub use pagination::Pagination;
+mod admin;
+pub use admin::{AdminArticle, AdminComment, AdminUser, DailyStats, ADMIN_PAGE_SIZE};
 mod article;
 pub use article::Article;
+#[cfg(feature = "ssr")]
+pub use article::FeedArticle;
+mod audit;
+pub use audit::{AuditEntry, ModerationAction};
+mod co_author;
+pub use co_author::{AuthorRole, CoAuthor};
 mod comment;
-pub use comment::Comment;
+pub use comment::{Comment, CommentRevision};
+mod data_export;
+pub use data_export::{DataExport, ExportStatus};
+#[cfg(feature = "ssr")]
+pub use data_export::{ExportedArticle, PersonalData};
+mod digest;
+#[cfg(feature = "ssr")]
+pub use digest::DigestRecipient;
+pub use digest::{DigestArticle, DigestFrequency, DigestPreferences};
+mod notification;
+pub use notification::{Notification, NotificationGroup, NotificationKind};
+mod reaction;
+pub use reaction::{Reaction, ReactionCount};
+mod relationship;
+pub use relationship::Relationship;
+mod report;
+pub use report::{Report, ReportDecision, ReportReason, ReportTarget, ReportedItem};
+mod role;
+pub use role::{Permission, Role};
+mod series;
+pub use series::{Series, SeriesPart};
+mod upload;
+pub use upload::{srcset, stored_keys, variant_key, Upload, UploadKind, VARIANT_FORMATS};
 
 #[cfg(feature = "ssr")]
-const DATE_FORMAT: &str = "%d/%m/%Y %H:%M";
+pub(crate) const DATE_FORMAT: &str = "%d/%m/%Y %H:%M";
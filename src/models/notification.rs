@@ -0,0 +1,347 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationKind {
+    Follow,
+    Favorite,
+    Comment,
+    Reply,
+    Invite,
+}
+
+impl NotificationKind {
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NotificationKind::Follow => "follow",
+            NotificationKind::Favorite => "favorite",
+            NotificationKind::Comment => "comment",
+            NotificationKind::Reply => "reply",
+            NotificationKind::Invite => "invite",
+        }
+    }
+
+    #[inline]
+    pub fn icon(self) -> &'static str {
+        match self {
+            NotificationKind::Follow => "ion-person-add",
+            NotificationKind::Favorite => "ion-heart",
+            NotificationKind::Comment | NotificationKind::Reply => "ion-chatbubble",
+            NotificationKind::Invite => "ion-person-stalker",
+        }
+    }
+}
+
+impl std::str::FromStr for NotificationKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "follow" => Ok(NotificationKind::Follow),
+            "favorite" => Ok(NotificationKind::Favorite),
+            "comment" => Ok(NotificationKind::Comment),
+            "reply" => Ok(NotificationKind::Reply),
+            "invite" => Ok(NotificationKind::Invite),
+            _ => Err(format!("Unknown notification kind {s}")),
+        }
+    }
+}
+
+/// Notifications of the same kind about the same article, shown as a single entry.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NotificationGroup {
+    pub kind: NotificationKind,
+    pub article_id: Option<uuid::Uuid>,
+    pub article_slug: Option<String>,
+    pub article_title: Option<String>,
+    pub actors: i64,
+    pub latest_actor_id: uuid::Uuid,
+    pub latest_actor_name: String,
+    pub latest_at: String,
+    pub unread: bool,
+}
+
+impl NotificationGroup {
+    pub fn message(&self) -> String {
+        let who = if self.actors > 1 {
+            format!("{} people", self.actors)
+        } else {
+            self.latest_actor_name.clone()
+        };
+        let title = self.article_title.as_deref().unwrap_or("your post");
+        match self.kind {
+            NotificationKind::Follow => format!("{who} followed you"),
+            NotificationKind::Favorite => format!("{who} favorited your post \"{title}\""),
+            NotificationKind::Comment => format!("{who} commented on your post \"{title}\""),
+            NotificationKind::Reply => format!("{who} replied to your comment on \"{title}\""),
+            NotificationKind::Invite => format!("{who} invited you to co-author \"{title}\""),
+        }
+    }
+
+    pub fn href(&self) -> String {
+        match (&self.kind, &self.article_slug) {
+            (NotificationKind::Follow, _) | (_, None) => {
+                format!("/profile/{}", self.latest_actor_id)
+            }
+            (_, Some(slug)) => format!("/article/{slug}"),
+        }
+    }
+}
+
+pub struct Notification;
+
+#[cfg(feature = "ssr")]
+impl Notification {
+    /// Lets the open tabs of each recipient refresh their unread count.
+    async fn published(recipients: Vec<uuid::Uuid>) {
+        for user_id in recipients {
+            crate::realtime::publish(
+                crate::realtime::Topic::User(user_id),
+                crate::realtime::RealtimeEvent::NotificationsChanged,
+            )
+            .await;
+        }
+    }
+
+    /// Lets `recipient_id` know about something `actor_id` did. Acting on your own
+    /// content is not worth a notification, and neither is doing the same thing
+    /// again before the last one was read.
+    pub async fn for_user(
+        recipient_id: uuid::Uuid,
+        actor_id: uuid::Uuid,
+        kind: NotificationKind,
+    ) -> Result<(), sqlx::Error> {
+        let recipients = sqlx::query!(
+            "
+            INSERT INTO Notifications(user_id, actor_id, kind)
+            SELECT $1::uuid, $2::uuid, $3
+            WHERE $1::uuid <> $2::uuid AND NOT EXISTS(
+                SELECT 1 FROM Notifications
+                WHERE user_id = $1 AND actor_id = $2 AND kind = $3
+                    AND article_id IS NULL AND read_at IS NULL
+            )
+            RETURNING user_id",
+            recipient_id,
+            actor_id,
+            kind.as_str(),
+        )
+        .fetch_all(crate::database::get_db())
+        .await?;
+
+        Self::published(recipients.into_iter().map(|x| x.user_id).collect()).await;
+        Ok(())
+    }
+
+    /// An invitation of `recipient_id` to co-author `article_id`.
+    pub async fn for_invitee(
+        recipient_id: uuid::Uuid,
+        actor_id: uuid::Uuid,
+        article_id: uuid::Uuid,
+    ) -> Result<(), sqlx::Error> {
+        let recipients = sqlx::query!(
+            "
+            INSERT INTO Notifications(user_id, actor_id, kind, article_id)
+            SELECT $1::uuid, $2::uuid, $3, $4::uuid
+            WHERE $1::uuid <> $2::uuid AND NOT EXISTS(
+                SELECT 1 FROM Notifications
+                WHERE user_id = $1 AND actor_id = $2 AND kind = $3
+                    AND article_id = $4 AND read_at IS NULL
+            )
+            RETURNING user_id",
+            recipient_id,
+            actor_id,
+            NotificationKind::Invite.as_str(),
+            article_id,
+        )
+        .fetch_all(crate::database::get_db())
+        .await?;
+
+        Self::published(recipients.into_iter().map(|x| x.user_id).collect()).await;
+        Ok(())
+    }
+
+    pub async fn for_article_author(
+        article_id: uuid::Uuid,
+        actor_id: uuid::Uuid,
+        kind: NotificationKind,
+    ) -> Result<(), sqlx::Error> {
+        let recipients = sqlx::query!(
+            "
+            INSERT INTO Notifications(user_id, actor_id, kind, article_id)
+            SELECT a.author_id, $2, $3, a.id FROM Articles AS a
+            WHERE a.id = $1 AND a.author_id <> $2 AND NOT EXISTS(
+                SELECT 1 FROM Notifications AS n
+                WHERE n.user_id = a.author_id AND n.actor_id = $2 AND n.kind = $3
+                    AND n.article_id = a.id AND n.read_at IS NULL
+            )
+            RETURNING user_id",
+            article_id,
+            actor_id,
+            kind.as_str(),
+        )
+        .fetch_all(crate::database::get_db())
+        .await?;
+
+        Self::published(recipients.into_iter().map(|x| x.user_id).collect()).await;
+        Ok(())
+    }
+
+    pub async fn for_comment_author(
+        comment_id: uuid::Uuid,
+        actor_id: uuid::Uuid,
+        kind: NotificationKind,
+    ) -> Result<(), sqlx::Error> {
+        let recipients = sqlx::query!(
+            "
+            INSERT INTO Notifications(user_id, actor_id, kind, article_id)
+            SELECT c.user_id, $2, $3, c.article_id FROM Comments AS c
+            WHERE c.id = $1 AND c.user_id <> $2 AND NOT EXISTS(
+                SELECT 1 FROM Notifications AS n
+                WHERE n.user_id = c.user_id AND n.actor_id = $2 AND n.kind = $3
+                    AND n.article_id = c.article_id AND n.read_at IS NULL
+            )
+            RETURNING user_id",
+            comment_id,
+            actor_id,
+            kind.as_str(),
+        )
+        .fetch_all(crate::database::get_db())
+        .await?;
+
+        Self::published(recipients.into_iter().map(|x| x.user_id).collect()).await;
+        Ok(())
+    }
+
+    /// Unread groups, as listed by `grouped`.
+    pub async fn unread_count(user_id: uuid::Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query!(
+            "
+            SELECT COUNT(DISTINCT (kind, article_id)) FROM Notifications
+            WHERE user_id = $1 AND read_at IS NULL",
+            user_id
+        )
+        .fetch_one(crate::database::get_db())
+        .await
+        .map(|x| x.count.unwrap_or_default())
+    }
+
+    pub async fn grouped(user_id: uuid::Uuid) -> Result<Vec<NotificationGroup>, sqlx::Error> {
+        sqlx::query!(
+            r#"
+            SELECT
+                n.kind,
+                n.article_id,
+                a.slug AS "article_slug?",
+                a.title AS "article_title?",
+                COUNT(DISTINCT n.actor_id) AS actors,
+                (ARRAY_AGG(n.actor_id ORDER BY n.created_at DESC))[1] AS latest_actor_id,
+                (ARRAY_AGG(u.name ORDER BY n.created_at DESC))[1] AS latest_actor_name,
+                MAX(n.created_at) AS latest_at,
+                BOOL_OR(n.read_at IS NULL) AS unread
+            FROM Notifications AS n
+            JOIN Users AS u ON u.id = n.actor_id
+            LEFT JOIN Articles AS a ON a.id = n.article_id
+            WHERE n.user_id = $1
+            GROUP BY n.kind, n.article_id, a.slug, a.title
+            ORDER BY latest_at DESC
+            LIMIT 50"#,
+            user_id
+        )
+        .fetch_all(crate::database::get_db())
+        .await
+        .map(|rows| {
+            rows.into_iter()
+                .filter_map(|x| {
+                    Some(NotificationGroup {
+                        kind: x.kind.parse().ok()?,
+                        article_id: x.article_id,
+                        article_slug: x.article_slug,
+                        article_title: x.article_title,
+                        actors: x.actors.unwrap_or_default(),
+                        latest_actor_id: x.latest_actor_id?,
+                        latest_actor_name: x.latest_actor_name.unwrap_or_default(),
+                        latest_at: x
+                            .latest_at
+                            .map(|x| x.format(super::DATE_FORMAT).to_string())
+                            .unwrap_or_default(),
+                        unread: x.unread.unwrap_or_default(),
+                    })
+                })
+                .collect()
+        })
+    }
+
+    pub async fn mark_read(
+        user_id: uuid::Uuid,
+        kind: NotificationKind,
+        article_id: Option<uuid::Uuid>,
+    ) -> Result<sqlx::postgres::PgQueryResult, sqlx::Error> {
+        sqlx::query!(
+            "
+            UPDATE Notifications SET read_at = NOW()
+            WHERE user_id = $1 AND kind = $2 AND article_id IS NOT DISTINCT FROM $3 AND read_at IS NULL",
+            user_id,
+            kind.as_str(),
+            article_id,
+        )
+        .execute(crate::database::get_db())
+        .await
+    }
+
+    pub async fn mark_all_read(
+        user_id: uuid::Uuid,
+    ) -> Result<sqlx::postgres::PgQueryResult, sqlx::Error> {
+        sqlx::query!(
+            "UPDATE Notifications SET read_at = NOW() WHERE user_id = $1 AND read_at IS NULL",
+            user_id
+        )
+        .execute(crate::database::get_db())
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(kind: NotificationKind, actors: i64) -> NotificationGroup {
+        NotificationGroup {
+            kind,
+            article_id: Some(uuid::Uuid::nil()),
+            article_slug: Some("hello-world".into()),
+            article_title: Some("Hello world".into()),
+            actors,
+            latest_actor_id: uuid::Uuid::nil(),
+            latest_actor_name: "Alice".into(),
+            latest_at: String::new(),
+            unread: true,
+        }
+    }
+
+    #[test]
+    fn messages_aggregate_actors() {
+        assert_eq!(
+            group(NotificationKind::Favorite, 1).message(),
+            "Alice favorited your post \"Hello world\""
+        );
+        assert_eq!(
+            group(NotificationKind::Favorite, 5).message(),
+            "5 people favorited your post \"Hello world\""
+        );
+        assert_eq!(group(NotificationKind::Follow, 2).message(), "2 people followed you");
+        assert_eq!(
+            group(NotificationKind::Invite, 1).message(),
+            "Alice invited you to co-author \"Hello world\""
+        );
+    }
+
+    #[test]
+    fn links_point_to_the_article_or_the_actor() {
+        assert_eq!(group(NotificationKind::Comment, 1).href(), "/article/hello-world");
+        assert_eq!(
+            group(NotificationKind::Follow, 1).href(),
+            format!("/profile/{}", uuid::Uuid::nil())
+        );
+    }
+}
@@ -0,0 +1,372 @@
+use super::UserPreview;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrgRole {
+    Owner,
+    Editor,
+    Writer,
+}
+
+impl OrgRole {
+    pub const ALL: [OrgRole; 3] = [OrgRole::Owner, OrgRole::Editor, OrgRole::Writer];
+
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OrgRole::Owner => "owner",
+            OrgRole::Editor => "editor",
+            OrgRole::Writer => "writer",
+        }
+    }
+
+    #[inline]
+    pub fn label(self) -> &'static str {
+        match self {
+            OrgRole::Owner => "Owner",
+            OrgRole::Editor => "Editor",
+            OrgRole::Writer => "Writer",
+        }
+    }
+}
+
+impl std::str::FromStr for OrgRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.as_str() == s)
+            .ok_or_else(|| format!("Unknown organization role {s}"))
+    }
+}
+
+/// What articles show of the organization they were published on behalf of.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct OrganizationPreview {
+    pub id: uuid::Uuid,
+    pub slug: String,
+    pub name: String,
+    /// Whether the logged user edits every article of the organization.
+    #[serde(default)]
+    pub can_edit: bool,
+}
+
+/// A shared brand several users publish under.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Organization {
+    pub id: uuid::Uuid,
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+    pub image: Option<String>,
+    /// Whether the logged user follows it.
+    pub following: bool,
+    /// The role of the logged user, when they are a member.
+    pub role: Option<OrgRole>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct OrganizationMember {
+    pub user: UserPreview,
+    pub role: OrgRole,
+}
+
+const SLUG_MIN_LENGTH: usize = 3;
+const SLUG_MAX_LENGTH: usize = 40;
+
+impl Organization {
+    /// Slugs end up in URLs, so only lowercase letters, digits and inner dashes are allowed.
+    pub fn validate_slug(slug: &str) -> Result<(), String> {
+        if !(SLUG_MIN_LENGTH..=SLUG_MAX_LENGTH).contains(&slug.len()) {
+            return Err(format!(
+                "The handle must be between {SLUG_MIN_LENGTH} and {SLUG_MAX_LENGTH} characters"
+            ));
+        }
+        if !slug.bytes().all(|x| matches!(x, b'a'..=b'z' | b'0'..=b'9' | b'-'))
+            || slug.starts_with('-')
+            || slug.ends_with('-')
+        {
+            return Err("The handle can only have lowercase letters, digits and dashes".into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl Organization {
+    pub async fn by_slug(slug: &str) -> Result<Self, sqlx::Error> {
+        let user_id = crate::auth::get_user_id();
+        let organization = sqlx::query!(
+            r#"
+            SELECT
+                o.id, o.slug, o.name, o.description, o.image,
+                EXISTS(SELECT 1 FROM OrganizationFollows WHERE organization_id = o.id AND follower_id = $2) AS "following!",
+                (SELECT role FROM OrganizationMembers WHERE organization_id = o.id AND user_id = $2) AS role
+            FROM Organizations AS o
+            WHERE o.slug = $1"#,
+            slug,
+            user_id,
+        )
+        .fetch_one(crate::database::get_db())
+        .await?;
+
+        Ok(Self {
+            id: organization.id,
+            slug: organization.slug,
+            name: organization.name,
+            description: organization.description,
+            image: organization.image,
+            following: organization.following,
+            role: organization.role.and_then(|x| x.parse().ok()),
+        })
+    }
+
+    /// Every organization `user_id` belongs to, to publish on behalf of.
+    pub async fn for_member(user_id: uuid::Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query!(
+            "
+            SELECT o.id, o.slug, o.name, o.description, o.image, m.role
+            FROM Organizations AS o
+            JOIN OrganizationMembers AS m ON m.organization_id = o.id
+            WHERE m.user_id = $1
+            ORDER BY o.name",
+            user_id
+        )
+        .map(|x| Self {
+            id: x.id,
+            slug: x.slug,
+            name: x.name,
+            description: x.description,
+            image: x.image,
+            following: false,
+            role: x.role.parse().ok(),
+        })
+        .fetch_all(crate::database::get_db())
+        .await
+    }
+
+    /// The organization each of `article_ids` was published on behalf of.
+    pub async fn for_articles(
+        article_ids: &[uuid::Uuid],
+        user_id: Option<uuid::Uuid>,
+    ) -> Result<std::collections::HashMap<uuid::Uuid, OrganizationPreview>, sqlx::Error> {
+        sqlx::query!(
+            r#"
+            SELECT a.id AS article_id, o.id, o.slug, o.name,
+                EXISTS(
+                    SELECT 1 FROM OrganizationMembers
+                    WHERE organization_id = o.id AND user_id = $2 AND role IN ('owner', 'editor')
+                ) AS "can_edit!"
+            FROM Articles AS a
+            JOIN Organizations AS o ON o.id = a.organization_id
+            WHERE a.id = ANY($1)"#,
+            article_ids,
+            user_id,
+        )
+        .map(|x| {
+            (
+                x.article_id,
+                OrganizationPreview {
+                    id: x.id,
+                    slug: x.slug,
+                    name: x.name,
+                    can_edit: x.can_edit,
+                },
+            )
+        })
+        .fetch_all(crate::database::get_db())
+        .await
+        .map(|x| x.into_iter().collect())
+    }
+
+    pub async fn members(organization_id: uuid::Uuid) -> Result<Vec<OrganizationMember>, sqlx::Error> {
+        let rows = sqlx::query!(
+            "
+            SELECT u.id, u.username, u.name, u.image, m.role
+            FROM OrganizationMembers AS m
+            JOIN Users AS u ON u.id = m.user_id
+            WHERE m.organization_id = $1
+            ORDER BY m.created_at",
+            organization_id
+        )
+        .fetch_all(crate::database::get_db())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|x| {
+                Some(OrganizationMember {
+                    user: UserPreview {
+                        user_id: x.id,
+                        username: x.username,
+                        name: x.name,
+                        image: x.image,
+                        following: false,
+                    },
+                    role: x.role.parse().ok()?,
+                })
+            })
+            .collect())
+    }
+
+    /// Creates an organization owned by `user_id`, returning its slug.
+    pub async fn create(
+        user_id: uuid::Uuid,
+        slug: &str,
+        name: &str,
+        description: &str,
+    ) -> Result<String, sqlx::Error> {
+        let mut transaction = crate::database::get_db().begin().await?;
+        let organization = sqlx::query!(
+            "INSERT INTO Organizations(slug, name, description) VALUES ($1, $2, $3) RETURNING id, slug",
+            slug,
+            name,
+            description
+        )
+        .fetch_one(transaction.as_mut())
+        .await?;
+        sqlx::query!(
+            "INSERT INTO OrganizationMembers(organization_id, user_id, role) VALUES ($1, $2, $3)",
+            organization.id,
+            user_id,
+            OrgRole::Owner.as_str()
+        )
+        .execute(transaction.as_mut())
+        .await?;
+        transaction.commit().await?;
+        Ok(organization.slug)
+    }
+
+    /// Adds `username` to the organization, or changes their role, when `owner_id`
+    /// owns it. Owners cannot change their own role so there is always one left.
+    pub async fn set_member(
+        organization_id: uuid::Uuid,
+        owner_id: uuid::Uuid,
+        username: &str,
+        role: OrgRole,
+    ) -> Result<sqlx::postgres::PgQueryResult, sqlx::Error> {
+        sqlx::query!(
+            "
+            INSERT INTO OrganizationMembers(organization_id, user_id, role)
+            SELECT $1, u.id, $4 FROM Users AS u
+            WHERE u.username = $3 AND u.id <> $2 AND EXISTS(
+                SELECT 1 FROM OrganizationMembers
+                WHERE organization_id = $1 AND user_id = $2 AND role = 'owner'
+            )
+            ON CONFLICT (organization_id, user_id) DO UPDATE SET role = EXCLUDED.role",
+            organization_id,
+            owner_id,
+            username,
+            role.as_str(),
+        )
+        .execute(crate::database::get_db())
+        .await
+    }
+
+    /// Takes `user_id` out of the organization. Owners remove others, and anybody
+    /// but an owner can leave.
+    pub async fn remove_member(
+        organization_id: uuid::Uuid,
+        actor_id: uuid::Uuid,
+        user_id: uuid::Uuid,
+    ) -> Result<sqlx::postgres::PgQueryResult, sqlx::Error> {
+        sqlx::query!(
+            "
+            DELETE FROM OrganizationMembers
+            WHERE organization_id = $1 AND user_id = $3 AND (
+                ($2 = $3 AND role <> 'owner')
+                OR ($2 <> $3 AND EXISTS(
+                    SELECT 1 FROM OrganizationMembers
+                    WHERE organization_id = $1 AND user_id = $2 AND role = 'owner'
+                ))
+            )",
+            organization_id,
+            actor_id,
+            user_id,
+        )
+        .execute(crate::database::get_db())
+        .await
+    }
+
+    /// Publishes `slug` on behalf of `organization_id`, or as its authors alone with
+    /// `None`. Only the owner of the article, or an owner or editor of its current
+    /// organization, can change it, and only to an organization they are a member
+    /// of. For anybody else editing the article, it stays where it is.
+    pub async fn assign(
+        connection: &mut sqlx::PgConnection,
+        slug: &str,
+        user_id: uuid::Uuid,
+        organization_id: Option<uuid::Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "
+            UPDATE Articles SET organization_id = $3
+            WHERE slug = $1
+                AND organization_id IS DISTINCT FROM $3
+                AND (
+                    author_id = $2
+                    OR EXISTS(
+                        SELECT 1 FROM OrganizationMembers AS m
+                        WHERE m.organization_id = Articles.organization_id AND m.user_id = $2
+                            AND m.role IN ('owner', 'editor')
+                    )
+                )
+                AND (
+                    $3::uuid IS NULL
+                    OR EXISTS(SELECT 1 FROM OrganizationMembers WHERE organization_id = $3 AND user_id = $2)
+                )",
+            slug,
+            user_id,
+            organization_id,
+        )
+        .execute(connection)
+        .await?;
+        Ok(())
+    }
+
+    /// Follows the organization, or stops following it, returning whether it is now followed.
+    pub async fn toggle_follow(
+        organization_id: uuid::Uuid,
+        user_id: uuid::Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let mut transaction = crate::database::get_db().begin().await?;
+        let added = sqlx::query!(
+            "INSERT INTO OrganizationFollows(organization_id, follower_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            organization_id,
+            user_id
+        )
+        .execute(transaction.as_mut())
+        .await?
+        .rows_affected()
+            > 0;
+
+        if !added {
+            sqlx::query!(
+                "DELETE FROM OrganizationFollows WHERE organization_id=$1 AND follower_id=$2",
+                organization_id,
+                user_id
+            )
+            .execute(transaction.as_mut())
+            .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(added)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugs_are_url_safe() {
+        assert!(Organization::validate_slug("rust-team").is_ok());
+        assert!(Organization::validate_slug("ab").is_err());
+        assert!(Organization::validate_slug("Rust Team").is_err());
+        assert!(Organization::validate_slug("-rust").is_err());
+        assert!(Organization::validate_slug(&"a".repeat(41)).is_err());
+    }
+}
@@ -7,6 +7,7 @@ pub struct Pagination {
     my_feed: Option<bool>,
     page: Option<u32>,
     amount: Option<u32>,
+    infinite: Option<bool>,
 }
 
 impl Pagination {
@@ -30,6 +31,11 @@ impl Pagination {
         self.amount.unwrap_or(10)
     }
 
+    #[inline]
+    pub fn get_infinite(&self) -> bool {
+        self.infinite.unwrap_or_default()
+    }
+
     #[inline]
     pub fn set_tag<T: ToString + ?Sized>(mut self, tag: &T) -> Self {
         self.tag = Some(tag.to_string());
@@ -48,6 +54,18 @@ impl Pagination {
         self
     }
 
+    #[inline]
+    pub fn set_infinite(mut self, infinite: bool) -> Self {
+        self.infinite = Some(infinite);
+        self
+    }
+
+    #[inline]
+    pub fn set_page(mut self, page: u32) -> Self {
+        self.page = Some(page);
+        self
+    }
+
     #[inline]
     pub fn reset_page(mut self) -> Self {
         self.page = Some(0);
@@ -74,6 +92,7 @@ impl Default for Pagination {
             my_feed: Some(false),
             page: Some(0),
             amount: Some(10),
+            infinite: None,
         }
     }
 }
@@ -94,6 +113,9 @@ impl ToString for Pagination {
         if self.get_amount() != 10 {
             params.push(format!("amount={}", self.get_amount()));
         }
+        if self.get_infinite() {
+            params.push("infinite=true".to_string());
+        }
 
         if params.is_empty() {
             "/".to_string()
@@ -0,0 +1,266 @@
+use serde::{Deserialize, Serialize};
+
+/// The fixed set of reactions. Favorites keep living in `FavArticles`, so the
+/// profile's favourites tab and `favorites_count` mean what they always did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Reaction {
+    Favorite,
+    Like,
+    Insightful,
+    Celebrate,
+}
+
+impl Reaction {
+    pub const ALL: [Reaction; 4] = [
+        Reaction::Favorite,
+        Reaction::Like,
+        Reaction::Insightful,
+        Reaction::Celebrate,
+    ];
+
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Reaction::Favorite => "favorite",
+            Reaction::Like => "like",
+            Reaction::Insightful => "insightful",
+            Reaction::Celebrate => "celebrate",
+        }
+    }
+
+    #[inline]
+    pub fn label(self) -> &'static str {
+        match self {
+            Reaction::Favorite => "Fav",
+            Reaction::Like => "Like",
+            Reaction::Insightful => "Insightful",
+            Reaction::Celebrate => "Celebrate",
+        }
+    }
+
+    #[inline]
+    pub fn icon(self) -> &'static str {
+        match self {
+            Reaction::Favorite => "ion-heart",
+            Reaction::Like => "ion-thumbsup",
+            Reaction::Insightful => "ion-lightbulb",
+            Reaction::Celebrate => "ion-trophy",
+        }
+    }
+
+    /// Comments can't be favorited, only reacted to.
+    #[inline]
+    pub fn applies_to_comments(self) -> bool {
+        self != Reaction::Favorite
+    }
+}
+
+impl std::str::FromStr for Reaction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.as_str() == s)
+            .ok_or_else(|| format!("Unknown reaction {s}"))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ReactionCount {
+    pub kind: Reaction,
+    pub count: i64,
+    pub reacted: bool,
+}
+
+impl ReactionCount {
+    /// Count and whether the logged user reacted, for one kind of reaction.
+    pub fn find(counts: &[Self], kind: Reaction) -> (i64, bool) {
+        counts
+            .iter()
+            .find(|x| x.kind == kind)
+            .map_or((0, false), |x| (x.count, x.reacted))
+    }
+
+    /// Records the outcome of a toggle. Applying the same outcome twice is a no-op.
+    pub fn apply(counts: &mut Vec<Self>, kind: Reaction, reacted: bool) {
+        match counts.iter_mut().find(|x| x.kind == kind) {
+            Some(x) if x.reacted != reacted => {
+                x.reacted = reacted;
+                x.count = (x.count + if reacted { 1 } else { -1 }).max(0);
+            }
+            Some(_) => {}
+            None if reacted => counts.push(Self {
+                kind,
+                count: 1,
+                reacted,
+            }),
+            None => {}
+        }
+    }
+
+    /// Takes the counts of pushed `totals`, keeping whether the logged user reacted.
+    /// Favorites are left out, articles count them on their own.
+    pub fn apply_totals(counts: &mut Vec<Self>, totals: &[Self]) {
+        for kind in Reaction::ALL.into_iter().filter(|x| *x != Reaction::Favorite) {
+            let (count, _) = Self::find(totals, kind);
+            if let Some(x) = counts.iter_mut().find(|x| x.kind == kind) {
+                x.count = count;
+            } else if count > 0 {
+                counts.push(Self {
+                    kind,
+                    count,
+                    reacted: false,
+                });
+            }
+        }
+    }
+
+    /// Reaction counts of several articles, or of several comments, keyed by their id.
+    #[cfg(feature = "ssr")]
+    pub async fn for_targets(
+        ids: &[uuid::Uuid],
+        on_comments: bool,
+    ) -> Result<std::collections::HashMap<uuid::Uuid, Vec<Self>>, sqlx::Error> {
+        let user_id = crate::auth::get_user_id();
+        let rows = sqlx::query!(
+            "
+            SELECT
+                COALESCE(article_id, comment_id) AS target_id,
+                kind,
+                COUNT(*) AS count,
+                BOOL_OR(user_id = $2) AS reacted
+            FROM Reactions
+            WHERE CASE WHEN $3 THEN comment_id = ANY($1) ELSE article_id = ANY($1) END
+            GROUP BY target_id, kind",
+            ids,
+            user_id,
+            on_comments,
+        )
+        .fetch_all(crate::database::get_db())
+        .await?;
+
+        let mut result = std::collections::HashMap::<uuid::Uuid, Vec<Self>>::new();
+        for row in rows {
+            let (Some(target_id), Ok(kind)) = (row.target_id, row.kind.parse()) else {
+                continue;
+            };
+            result.entry(target_id).or_default().push(Self {
+                kind,
+                count: row.count.unwrap_or_default(),
+                reacted: row.reacted.unwrap_or_default(),
+            });
+        }
+        Ok(result)
+    }
+
+    /// Totals of every reaction on an article, favorites included, regardless of who reacted.
+    #[cfg(feature = "ssr")]
+    pub async fn totals_for_article(article_id: uuid::Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        let favorites = sqlx::query!(
+            "SELECT COUNT(*) FROM FavArticles WHERE article_id = $1",
+            article_id
+        )
+        .fetch_one(crate::database::get_db())
+        .await?
+        .count
+        .unwrap_or_default();
+        let reactions = sqlx::query!(
+            "SELECT kind, COUNT(*) AS count FROM Reactions WHERE article_id = $1 GROUP BY kind",
+            article_id
+        )
+        .fetch_all(crate::database::get_db())
+        .await?;
+
+        Ok(std::iter::once(Self {
+            kind: Reaction::Favorite,
+            count: favorites,
+            reacted: false,
+        })
+        .chain(reactions.into_iter().filter_map(|x| {
+            Some(Self {
+                kind: x.kind.parse().ok()?,
+                count: x.count.unwrap_or_default(),
+                reacted: false,
+            })
+        }))
+        .collect())
+    }
+
+    /// Totals of every reaction on a comment regardless of who reacted, with the
+    /// article the comment is on.
+    #[cfg(feature = "ssr")]
+    pub async fn totals_for_comment(
+        comment_id: uuid::Uuid,
+    ) -> Result<(uuid::Uuid, Vec<Self>), sqlx::Error> {
+        let db = crate::database::get_db();
+        let article_id = sqlx::query!("SELECT article_id FROM Comments WHERE id = $1", comment_id)
+            .fetch_one(db)
+            .await?
+            .article_id;
+        let totals = sqlx::query!(
+            "SELECT kind, COUNT(*) AS count FROM Reactions WHERE comment_id = $1 GROUP BY kind",
+            comment_id
+        )
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .filter_map(|x| {
+            Some(Self {
+                kind: x.kind.parse().ok()?,
+                count: x.count.unwrap_or_default(),
+                reacted: false,
+            })
+        })
+        .collect();
+        Ok((article_id, totals))
+    }
+}
+
+impl Reaction {
+    /// Adds or removes the reaction of `user_id` on an article or a comment,
+    /// returning whether it is now set. Favorites go through `FavArticles` instead.
+    #[cfg(feature = "ssr")]
+    pub async fn toggle(
+        self,
+        user_id: uuid::Uuid,
+        article_id: Option<uuid::Uuid>,
+        comment_id: Option<uuid::Uuid>,
+    ) -> Result<bool, sqlx::Error> {
+        let mut transaction = crate::database::get_db().begin().await?;
+        // Inserting first lets the unique indexes settle concurrent toggles
+        let added = sqlx::query!(
+            "
+            INSERT INTO Reactions(user_id, article_id, comment_id, kind) VALUES ($1, $2, $3, $4)
+            ON CONFLICT DO NOTHING",
+            user_id,
+            article_id,
+            comment_id,
+            self.as_str(),
+        )
+        .execute(transaction.as_mut())
+        .await?
+        .rows_affected()
+            > 0;
+
+        if !added {
+            sqlx::query!(
+                "
+                DELETE FROM Reactions
+                WHERE user_id=$1 AND kind=$4
+                    AND article_id IS NOT DISTINCT FROM $2
+                    AND comment_id IS NOT DISTINCT FROM $3",
+                user_id,
+                article_id,
+                comment_id,
+                self.as_str(),
+            )
+            .execute(transaction.as_mut())
+            .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(added)
+    }
+}
@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+/// What the logged user did about another user, besides following them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Relationship {
+    /// They cannot comment on, follow or react to the content of the logged user.
+    pub blocked: bool,
+    /// Their articles and comments are left out of what the logged user reads.
+    pub muted: bool,
+}
+
+#[cfg(feature = "ssr")]
+impl Relationship {
+    pub async fn between(user_id: uuid::Uuid, other_id: uuid::Uuid) -> Result<Self, sqlx::Error> {
+        sqlx::query!(
+            "
+            SELECT
+                EXISTS(SELECT 1 FROM Blocks WHERE blocker_id = $1 AND blocked_id = $2) AS \"blocked!\",
+                EXISTS(SELECT 1 FROM Mutes WHERE muter_id = $1 AND muted_id = $2) AS \"muted!\"",
+            user_id,
+            other_id
+        )
+        .map(|x| Self {
+            blocked: x.blocked,
+            muted: x.muted,
+        })
+        .fetch_one(crate::database::get_db())
+        .await
+    }
+
+    /// Blocks or unblocks `other_id`, returning whether they are blocked now. Blocking
+    /// also stops them from following `user_id`.
+    pub async fn toggle_block(user_id: uuid::Uuid, other_id: uuid::Uuid) -> Result<bool, sqlx::Error> {
+        let mut transaction = crate::database::get_db().begin().await?;
+        let unblocked = sqlx::query!(
+            "DELETE FROM Blocks WHERE blocker_id = $1 AND blocked_id = $2",
+            user_id,
+            other_id
+        )
+        .execute(transaction.as_mut())
+        .await?
+        .rows_affected()
+            > 0;
+
+        if !unblocked {
+            sqlx::query!(
+                "INSERT INTO Blocks(blocker_id, blocked_id) VALUES ($1, $2)",
+                user_id,
+                other_id
+            )
+            .execute(transaction.as_mut())
+            .await?;
+            sqlx::query!(
+                "DELETE FROM Follows WHERE follower_id = $2 AND influencer_id = $1",
+                user_id,
+                other_id
+            )
+            .execute(transaction.as_mut())
+            .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(!unblocked)
+    }
+
+    /// Mutes or unmutes `other_id`, returning whether they are muted now.
+    pub async fn toggle_mute(user_id: uuid::Uuid, other_id: uuid::Uuid) -> Result<bool, sqlx::Error> {
+        let unmuted = sqlx::query!(
+            "DELETE FROM Mutes WHERE muter_id = $1 AND muted_id = $2",
+            user_id,
+            other_id
+        )
+        .execute(crate::database::get_db())
+        .await?
+        .rows_affected()
+            > 0;
+
+        if !unmuted {
+            sqlx::query!(
+                "INSERT INTO Mutes(muter_id, muted_id) VALUES ($1, $2)",
+                user_id,
+                other_id
+            )
+            .execute(crate::database::get_db())
+            .await?;
+        }
+        Ok(!unmuted)
+    }
+
+    /// Whether `actor_id` was blocked by `owner_id`, or by the author of the article
+    /// or comment, whichever of them are given.
+    pub async fn is_blocked(
+        actor_id: uuid::Uuid,
+        owner_id: Option<uuid::Uuid>,
+        article_id: Option<uuid::Uuid>,
+        comment_id: Option<uuid::Uuid>,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query!(
+            "
+            SELECT EXISTS(
+                SELECT 1 FROM Blocks
+                WHERE blocked_id = $1 AND blocker_id IN (
+                    SELECT $2::uuid
+                    UNION SELECT author_id FROM Articles WHERE id = $3
+                    UNION SELECT user_id FROM Comments WHERE id = $4
+                )
+            ) AS \"blocked!\"",
+            actor_id,
+            owner_id,
+            article_id,
+            comment_id
+        )
+        .fetch_one(crate::database::get_db())
+        .await
+        .map(|x| x.blocked)
+    }
+}
@@ -0,0 +1,400 @@
+use serde::{Deserialize, Serialize};
+
+/// What a report is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportTarget {
+    Article,
+    Comment,
+    User,
+}
+
+impl ReportTarget {
+    pub const ALL: [ReportTarget; 3] = [
+        ReportTarget::Article,
+        ReportTarget::Comment,
+        ReportTarget::User,
+    ];
+
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReportTarget::Article => "article",
+            ReportTarget::Comment => "comment",
+            ReportTarget::User => "user",
+        }
+    }
+
+    #[inline]
+    pub fn label(self) -> &'static str {
+        match self {
+            ReportTarget::Article => "Article",
+            ReportTarget::Comment => "Comment",
+            ReportTarget::User => "Profile",
+        }
+    }
+}
+
+impl std::str::FromStr for ReportTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.as_str() == s)
+            .ok_or_else(|| format!("Unknown report target {s}"))
+    }
+}
+
+/// Why something was reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportReason {
+    Spam,
+    Abuse,
+    Harassment,
+    Other,
+}
+
+impl ReportReason {
+    pub const ALL: [ReportReason; 4] = [
+        ReportReason::Spam,
+        ReportReason::Abuse,
+        ReportReason::Harassment,
+        ReportReason::Other,
+    ];
+
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReportReason::Spam => "spam",
+            ReportReason::Abuse => "abuse",
+            ReportReason::Harassment => "harassment",
+            ReportReason::Other => "other",
+        }
+    }
+
+    #[inline]
+    pub fn label(self) -> &'static str {
+        match self {
+            ReportReason::Spam => "Spam",
+            ReportReason::Abuse => "Abusive or offensive",
+            ReportReason::Harassment => "Harassment",
+            ReportReason::Other => "Something else",
+        }
+    }
+}
+
+impl std::str::FromStr for ReportReason {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.as_str() == s)
+            .ok_or_else(|| format!("Unknown report reason {s}"))
+    }
+}
+
+/// How a moderator closes the open reports on a target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportDecision {
+    /// The reports were right, and whatever they needed was already done.
+    Resolve,
+    /// The reports were wrong. Content hidden because of them is restored.
+    Dismiss,
+    /// The reports were right: hide the content, or suspend the user, then resolve.
+    TakeAction,
+}
+
+impl ReportDecision {
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReportDecision::Resolve => "resolve",
+            ReportDecision::Dismiss => "dismiss",
+            ReportDecision::TakeAction => "take_action",
+        }
+    }
+}
+
+/// Something with open reports, waiting in the moderation queue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportedItem {
+    pub target: ReportTarget,
+    pub target_id: uuid::Uuid,
+    /// Title, beginning of the body or username, depending on the target.
+    pub summary: Option<String>,
+    pub href: Option<String>,
+    /// Hidden content, or a suspended user.
+    pub hidden: bool,
+    pub reports: i64,
+    pub reasons: Vec<ReportReason>,
+    pub details: Vec<String>,
+    pub first_reported_at: String,
+}
+
+pub struct Report;
+
+/// Open reports it takes to hide an article or a comment until a moderator looks at it.
+#[cfg(feature = "ssr")]
+fn report_threshold() -> i64 {
+    std::env::var("REPORT_THRESHOLD")
+        .ok()
+        .and_then(|x| x.parse().ok())
+        .unwrap_or(3)
+}
+
+#[cfg(feature = "ssr")]
+impl Report {
+    /// Files a report from `reporter_id`, once per target while it is open. Returns
+    /// whether the target got hidden because it reached the report threshold.
+    pub async fn file(
+        reporter_id: uuid::Uuid,
+        target: ReportTarget,
+        target_id: uuid::Uuid,
+        reason: ReportReason,
+        details: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let mut transaction = crate::database::get_db().begin().await?;
+        // Also makes sure the target exists
+        let (_, hidden, _) = Self::set_hidden(transaction.as_mut(), target, target_id, None, false).await?;
+
+        sqlx::query!(
+            "
+            INSERT INTO Reports(reporter_id, target_kind, target_id, reason, details)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (reporter_id, target_kind, target_id) WHERE status = 'open' DO NOTHING",
+            reporter_id,
+            target.as_str(),
+            target_id,
+            reason.as_str(),
+            details
+        )
+        .execute(transaction.as_mut())
+        .await?;
+
+        let reports = sqlx::query!(
+            "
+            SELECT COUNT(*) AS \"count!\" FROM Reports
+            WHERE target_kind = $1 AND target_id = $2 AND status = 'open'",
+            target.as_str(),
+            target_id
+        )
+        .fetch_one(transaction.as_mut())
+        .await?
+        .count;
+
+        let hide = !hidden && target != ReportTarget::User && reports >= report_threshold();
+        if hide {
+            let (summary, ..) =
+                Self::set_hidden(transaction.as_mut(), target, target_id, Some(true), true).await?;
+            let action = if target == ReportTarget::Article {
+                super::ModerationAction::HideArticle
+            } else {
+                super::ModerationAction::HideComment
+            };
+            super::AuditEntry::record(
+                transaction.as_mut(),
+                None,
+                action,
+                target_id,
+                &format!("{summary} (reported {reports} times)"),
+            )
+            .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(hide)
+    }
+
+    /// Summary and hidden state of a target, and whether the reports hid it, hiding
+    /// or restoring it first when `hidden` is set. For users, hidden means suspended.
+    async fn set_hidden(
+        connection: &mut sqlx::PgConnection,
+        target: ReportTarget,
+        target_id: uuid::Uuid,
+        hidden: Option<bool>,
+        by_reports: bool,
+    ) -> Result<(String, bool, bool), sqlx::Error> {
+        match target {
+            ReportTarget::Article => sqlx::query!(
+                "
+                UPDATE Articles SET hidden_at = CASE
+                        WHEN $2::bool IS NULL THEN hidden_at
+                        WHEN $2 THEN COALESCE(hidden_at, NOW())
+                    END,
+                    hidden_by_reports = CASE WHEN $2::bool IS NULL THEN hidden_by_reports ELSE $2 AND $3 END
+                WHERE id = $1
+                RETURNING title, hidden_at IS NOT NULL AS \"hidden!\", hidden_by_reports",
+                target_id,
+                hidden,
+                by_reports
+            )
+            .fetch_one(connection)
+            .await
+            .map(|x| (x.title, x.hidden, x.hidden_by_reports)),
+            ReportTarget::Comment => sqlx::query!(
+                "
+                UPDATE Comments SET hidden_at = CASE
+                        WHEN $2::bool IS NULL THEN hidden_at
+                        WHEN $2 THEN COALESCE(hidden_at, NOW())
+                    END,
+                    hidden_by_reports = CASE WHEN $2::bool IS NULL THEN hidden_by_reports ELSE $2 AND $3 END
+                WHERE id = $1
+                RETURNING body, hidden_at IS NOT NULL AS \"hidden!\", hidden_by_reports",
+                target_id,
+                hidden,
+                by_reports
+            )
+            .fetch_one(connection)
+            .await
+            .map(|x| (x.body, x.hidden, x.hidden_by_reports)),
+            ReportTarget::User => sqlx::query!(
+                "
+                UPDATE Users SET status = CASE
+                    WHEN $2::bool IS NULL OR status NOT IN ('active', 'suspended') THEN status
+                    WHEN $2 THEN 'suspended'
+                    ELSE 'active'
+                END
+                WHERE id = $1
+                RETURNING username, status = 'suspended' AS \"hidden!\"",
+                target_id,
+                hidden
+            )
+            .fetch_one(connection)
+            .await
+            .map(|x| (x.username, x.hidden, false)),
+        }
+    }
+
+    /// Targets with open reports, the most reported first.
+    pub async fn queue() -> Result<Vec<ReportedItem>, sqlx::Error> {
+        let items = sqlx::query!(
+            "
+            SELECT r.target_kind, r.target_id,
+                COUNT(*) AS \"reports!\",
+                MIN(r.created_at) AS \"first_reported_at!\",
+                ARRAY_AGG(DISTINCT r.reason) AS \"reasons!\",
+                ARRAY_REMOVE(ARRAY_AGG(r.details), '') AS \"details!\",
+                COALESCE(a.title, c.body, u.username) AS summary,
+                COALESCE(a.slug, ca.slug) AS slug,
+                COALESCE(a.hidden_at, c.hidden_at) IS NOT NULL OR COALESCE(u.status = 'suspended', FALSE) AS \"hidden!\"
+            FROM Reports AS r
+                LEFT JOIN Articles AS a ON r.target_kind = 'article' AND a.id = r.target_id
+                LEFT JOIN Comments AS c ON r.target_kind = 'comment' AND c.id = r.target_id
+                LEFT JOIN Articles AS ca ON ca.id = c.article_id
+                LEFT JOIN Users AS u ON r.target_kind = 'user' AND u.id = r.target_id
+            WHERE r.status = 'open'
+            GROUP BY r.target_kind, r.target_id, a.title, a.slug, a.hidden_at,
+                c.body, c.hidden_at, ca.slug, u.username, u.status
+            ORDER BY COUNT(*) DESC, MIN(r.created_at)",
+        )
+        .fetch_all(crate::database::get_db())
+        .await?;
+
+        items
+            .into_iter()
+            .map(|x| {
+                let target: ReportTarget = x
+                    .target_kind
+                    .parse()
+                    .map_err(|x: String| sqlx::Error::Decode(x.into()))?;
+                let href = match target {
+                    ReportTarget::User => Some(format!("/profile/{}", x.target_id)),
+                    _ => x.slug.map(|slug| format!("/article/{slug}")),
+                };
+                Ok(ReportedItem {
+                    target,
+                    target_id: x.target_id,
+                    summary: x.summary,
+                    href,
+                    hidden: x.hidden,
+                    reports: x.reports,
+                    reasons: x
+                        .reasons
+                        .iter()
+                        .map(|x| x.parse().map_err(|x: String| sqlx::Error::Decode(x.into())))
+                        .collect::<Result<_, _>>()?,
+                    details: x.details,
+                    first_reported_at: x.first_reported_at.format(super::DATE_FORMAT).to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// Closes every open report on a target on behalf of `moderator_id`. Returns how
+    /// many reports were closed.
+    pub async fn decide(
+        target: ReportTarget,
+        target_id: uuid::Uuid,
+        moderator_id: uuid::Uuid,
+        decision: ReportDecision,
+    ) -> Result<u64, sqlx::Error> {
+        use super::ModerationAction;
+
+        let mut transaction = crate::database::get_db().begin().await?;
+        let (summary, hidden, by_reports) =
+            Self::set_hidden(transaction.as_mut(), target, target_id, None, false).await?;
+
+        // Dismissing only restores what the reports hid, not what a moderator did
+        let change = match decision {
+            ReportDecision::TakeAction if !hidden => Some(true),
+            ReportDecision::Dismiss if by_reports => Some(false),
+            _ => None,
+        };
+        if decision == ReportDecision::TakeAction && by_reports {
+            // Now a moderator's decision
+            Self::set_hidden(transaction.as_mut(), target, target_id, Some(true), false).await?;
+        }
+        if let Some(hide) = change {
+            Self::set_hidden(transaction.as_mut(), target, target_id, Some(hide), false).await?;
+            let action = match (target, hide) {
+                (ReportTarget::Article, true) => ModerationAction::HideArticle,
+                (ReportTarget::Article, false) => ModerationAction::UnhideArticle,
+                (ReportTarget::Comment, true) => ModerationAction::HideComment,
+                (ReportTarget::Comment, false) => ModerationAction::UnhideComment,
+                (ReportTarget::User, true) => ModerationAction::SuspendUser,
+                (ReportTarget::User, false) => ModerationAction::UnsuspendUser,
+            };
+            super::AuditEntry::record(transaction.as_mut(), moderator_id, action, target_id, &summary)
+                .await?;
+        }
+
+        let status = if decision == ReportDecision::Dismiss {
+            "dismissed"
+        } else {
+            "resolved"
+        };
+        let closed = sqlx::query!(
+            "
+            UPDATE Reports SET status = $3, resolved_by = $4, resolved_at = NOW()
+            WHERE target_kind = $1 AND target_id = $2 AND status = 'open'",
+            target.as_str(),
+            target_id,
+            status,
+            moderator_id
+        )
+        .execute(transaction.as_mut())
+        .await?
+        .rows_affected();
+
+        let action = if decision == ReportDecision::Dismiss {
+            ModerationAction::DismissReports
+        } else {
+            ModerationAction::ResolveReports
+        };
+        super::AuditEntry::record(
+            transaction.as_mut(),
+            moderator_id,
+            action,
+            target_id,
+            &format!("{} {summary} ({closed} reports)", target.as_str()),
+        )
+        .await?;
+
+        transaction.commit().await?;
+        Ok(closed)
+    }
+}
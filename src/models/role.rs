@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+
+/// What a user may do on the site, besides managing their own content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    #[default]
+    User,
+    Moderator,
+    Admin,
+}
+
+/// Actions that need more than owning the content acted on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    /// Delete or hide any article or comment.
+    ModerateContent,
+    /// Give or take roles.
+    ManageRoles,
+    /// Browse everything from the admin dashboard and suspend users.
+    Administer,
+}
+
+impl Role {
+    pub const ALL: [Role; 3] = [Role::User, Role::Moderator, Role::Admin];
+
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Moderator => "moderator",
+            Role::Admin => "admin",
+        }
+    }
+
+    #[inline]
+    pub fn label(self) -> &'static str {
+        match self {
+            Role::User => "User",
+            Role::Moderator => "Moderator",
+            Role::Admin => "Admin",
+        }
+    }
+
+    pub fn can(self, permission: Permission) -> bool {
+        match permission {
+            Permission::ModerateContent => matches!(self, Role::Moderator | Role::Admin),
+            Permission::ManageRoles | Permission::Administer => self == Role::Admin,
+        }
+    }
+}
+
+impl std::str::FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.as_str() == s)
+            .ok_or_else(|| format!("Unknown role {s}"))
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl Role {
+    pub async fn of(user_id: uuid::Uuid) -> Result<Self, sqlx::Error> {
+        let role = sqlx::query!("SELECT role FROM Users WHERE id = $1", user_id)
+            .fetch_one(crate::database::get_db())
+            .await?
+            .role;
+        role.parse().map_err(|x: String| sqlx::Error::Decode(x.into()))
+    }
+
+    /// Gives `user_id` a role on behalf of `admin_id`, keeping track of it.
+    pub async fn set(
+        user_id: uuid::Uuid,
+        role: Role,
+        admin_id: uuid::Uuid,
+    ) -> Result<(), sqlx::Error> {
+        let mut transaction = crate::database::get_db().begin().await?;
+        let previous = sqlx::query!("SELECT role FROM Users WHERE id = $1 FOR UPDATE", user_id)
+            .fetch_one(transaction.as_mut())
+            .await?
+            .role;
+        sqlx::query!("UPDATE Users SET role = $2 WHERE id = $1", user_id, role.as_str())
+            .execute(transaction.as_mut())
+            .await?;
+        super::AuditEntry::record(
+            transaction.as_mut(),
+            admin_id,
+            super::ModerationAction::SetRole,
+            user_id,
+            &format!("{previous} → {}", role.as_str()),
+        )
+        .await?;
+        transaction.commit().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permissions_grow_with_roles() {
+        assert!(!Role::User.can(Permission::ModerateContent));
+        assert!(Role::Moderator.can(Permission::ModerateContent));
+        assert!(!Role::Moderator.can(Permission::ManageRoles));
+        assert!(Role::Admin.can(Permission::ModerateContent));
+        assert!(Role::Admin.can(Permission::ManageRoles));
+        assert!(!Role::Moderator.can(Permission::Administer));
+        assert!(Role::Admin.can(Permission::Administer));
+    }
+}
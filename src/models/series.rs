@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SeriesPart {
+    pub slug: String,
+    pub title: String,
+    pub position: i32,
+}
+
+/// Articles of one author meant to be read in order.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct Series {
+    pub id: uuid::Uuid,
+    pub title: String,
+    /// In reading order. Empty when only the series itself was asked for.
+    pub parts: Vec<SeriesPart>,
+}
+
+impl Series {
+    /// The parts before and after `slug`.
+    pub fn neighbours(&self, slug: &str) -> (Option<&SeriesPart>, Option<&SeriesPart>) {
+        let Some(index) = self.parts.iter().position(|x| x.slug == slug) else {
+            return (None, None);
+        };
+        (
+            index.checked_sub(1).and_then(|x| self.parts.get(x)),
+            self.parts.get(index + 1),
+        )
+    }
+
+    /// Position of `slug` within the series, for display.
+    pub fn position_of(&self, slug: &str) -> Option<i32> {
+        self.parts.iter().find(|x| x.slug == slug).map(|x| x.position)
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl Series {
+    /// The series `slug` belongs to, with its public parts. Hidden parts are only
+    /// listed to `viewer` when the series is theirs.
+    pub async fn for_article(
+        slug: &str,
+        viewer: Option<uuid::Uuid>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT s.id, s.title AS series_title, a.slug, a.title, a.series_position AS "position!"
+            FROM Series AS s
+            JOIN Articles AS a ON a.series_id = s.id
+            JOIN Users AS u ON u.id = a.author_id
+            WHERE s.id = (SELECT series_id FROM Articles WHERE slug = $1)
+                AND ((a.hidden_at IS NULL AND u.status = 'active') OR s.user_id = $2)
+            ORDER BY a.series_position, a.created_at"#,
+            slug,
+            viewer,
+        )
+        .fetch_all(crate::database::get_db())
+        .await?;
+
+        let Some(first) = rows.first() else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            id: first.id,
+            title: first.series_title.clone(),
+            parts: rows
+                .into_iter()
+                .map(|x| SeriesPart {
+                    slug: x.slug,
+                    title: x.title,
+                    position: x.position,
+                })
+                .collect(),
+        }))
+    }
+
+    /// Every series of `user_id`, without their parts.
+    pub async fn for_user(user_id: uuid::Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query!(
+            "SELECT id, title FROM Series WHERE user_id = $1 ORDER BY created_at DESC",
+            user_id
+        )
+        .map(|x| Self {
+            id: x.id,
+            title: x.title,
+            parts: Vec::new(),
+        })
+        .fetch_all(crate::database::get_db())
+        .await
+    }
+
+    /// Every series of whoever owns `slug`, so co-authors pick among the owner's.
+    pub async fn for_owner_of(slug: &str) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query!(
+            "
+            SELECT s.id, s.title FROM Series AS s
+            JOIN Articles AS a ON a.author_id = s.user_id
+            WHERE a.slug = $1
+            ORDER BY s.created_at DESC",
+            slug
+        )
+        .map(|x| Self {
+            id: x.id,
+            title: x.title,
+            parts: Vec::new(),
+        })
+        .fetch_all(crate::database::get_db())
+        .await
+    }
+
+    pub async fn create(
+        connection: &mut sqlx::PgConnection,
+        user_id: uuid::Uuid,
+        title: &str,
+    ) -> Result<uuid::Uuid, sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO Series(user_id, title) VALUES ($1, $2) RETURNING id",
+            user_id,
+            title
+        )
+        .fetch_one(connection)
+        .await
+        .map(|x| x.id)
+    }
+
+    /// Moves an article into a series of its author, or out of any with `None`.
+    /// Without a `position` it keeps its place, or goes last when it is new there.
+    pub async fn place(
+        connection: &mut sqlx::PgConnection,
+        slug: &str,
+        author_id: uuid::Uuid,
+        series_id: Option<uuid::Uuid>,
+        position: Option<i32>,
+    ) -> Result<(), sqlx::Error> {
+        let rows = sqlx::query!(
+            "
+            UPDATE Articles SET
+                series_id = $3,
+                series_position = CASE WHEN $3::uuid IS NULL THEN NULL ELSE COALESCE(
+                    $4,
+                    CASE WHEN series_id = $3 THEN series_position END,
+                    (SELECT COALESCE(MAX(series_position), 0) + 1 FROM Articles WHERE series_id = $3)
+                ) END
+            WHERE slug = $1 AND author_id = $2
+                AND ($3::uuid IS NULL OR EXISTS(SELECT 1 FROM Series WHERE id = $3 AND user_id = $2))",
+            slug,
+            author_id,
+            series_id,
+            position,
+        )
+        .execute(connection)
+        .await?
+        .rows_affected();
+
+        if rows != 1 {
+            return Err(sqlx::Error::RowNotFound);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part(slug: &str, position: i32) -> SeriesPart {
+        SeriesPart {
+            slug: slug.into(),
+            title: slug.to_uppercase(),
+            position,
+        }
+    }
+
+    #[test]
+    fn neighbours_follow_the_reading_order() {
+        let series = Series {
+            parts: vec![part("one", 1), part("two", 2), part("three", 3)],
+            ..Default::default()
+        };
+        fn slugs<'a>((previous, next): (Option<&'a SeriesPart>, Option<&'a SeriesPart>)) -> (Option<&'a str>, Option<&'a str>) {
+            (previous.map(|x| x.slug.as_str()), next.map(|x| x.slug.as_str()))
+        }
+
+        assert_eq!(slugs(series.neighbours("one")), (None, Some("two")));
+        assert_eq!(slugs(series.neighbours("two")), (Some("one"), Some("three")));
+        assert_eq!(slugs(series.neighbours("three")), (Some("two"), None));
+        assert_eq!(slugs(series.neighbours("elsewhere")), (None, None));
+    }
+}
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+
+/// What an image is uploaded for, which decides how large it may be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UploadKind {
+    Avatar,
+    Cover,
+    Inline,
+}
+
+impl UploadKind {
+    pub const ALL: [UploadKind; 3] = [UploadKind::Avatar, UploadKind::Cover, UploadKind::Inline];
+
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UploadKind::Avatar => "avatar",
+            UploadKind::Cover => "cover",
+            UploadKind::Inline => "inline",
+        }
+    }
+
+    #[inline]
+    pub fn max_bytes(self) -> usize {
+        match self {
+            UploadKind::Avatar => 1024 * 1024,
+            UploadKind::Cover | UploadKind::Inline => 5 * 1024 * 1024,
+        }
+    }
+
+    /// Widths the responsive variants are generated at. Inline images are shown
+    /// as written in the article, so they get none.
+    #[inline]
+    pub fn variant_widths(self) -> &'static [u32] {
+        match self {
+            UploadKind::Avatar => &[64, 128, 256],
+            UploadKind::Cover => &[480, 960, 1600],
+            UploadKind::Inline => &[],
+        }
+    }
+}
+
+/// Formats of the responsive variants as extension and content type, best first.
+pub const VARIANT_FORMATS: [(&str, &str); 2] = [("avif", "image/avif"), ("webp", "image/webp")];
+
+/// Name of one variant of the stored image `key`.
+pub fn variant_key(key: &str, width: u32, extension: &str) -> String {
+    let hash = key.split_once('.').map_or(key, |(hash, _)| hash);
+    format!("{hash}-{width}w.{extension}")
+}
+
+/// Names of everything that may be stored for an upload of `key`: the image and
+/// its variants for every kind, as they are also rendered when first requested.
+pub fn stored_keys(key: &str) -> Vec<String> {
+    let widths = UploadKind::ALL.iter().flat_map(|x| x.variant_widths());
+    let variants = widths.flat_map(|width| {
+        VARIANT_FORMATS
+            .iter()
+            .map(move |(extension, _)| variant_key(key, *width, extension))
+    });
+    std::iter::once(key.to_string()).chain(variants).collect()
+}
+
+/// `srcset` of the variants of an uploaded image in one format. Linked images and
+/// GIFs, which would lose their animation, have none.
+pub fn srcset(url: &str, kind: UploadKind, extension: &str) -> Option<String> {
+    let key = url.strip_prefix("/uploads/")?;
+    if key.ends_with(".gif") || kind.variant_widths().is_empty() {
+        return None;
+    }
+    Some(
+        kind.variant_widths()
+            .iter()
+            .map(|width| format!("/uploads/{} {width}w", variant_key(key, *width, extension)))
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+}
+
+impl std::str::FromStr for UploadKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.as_str() == s)
+            .ok_or_else(|| format!("Unknown upload kind {s}"))
+    }
+}
+
+pub struct Upload;
+
+#[cfg(feature = "ssr")]
+impl Upload {
+    /// Remembers who uploaded a blob, so it can be found again for that user.
+    pub async fn record(
+        key: &str,
+        user_id: uuid::Uuid,
+        kind: UploadKind,
+        content_type: &str,
+        size: i64,
+    ) -> Result<sqlx::postgres::PgQueryResult, sqlx::Error> {
+        sqlx::query!(
+            "
+            INSERT INTO Uploads(key, user_id, kind, content_type, size) VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (user_id, key, kind) DO NOTHING",
+            key,
+            user_id,
+            kind.as_str(),
+            content_type,
+            size,
+        )
+        .execute(crate::database::get_db())
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn srcset_lists_every_width() {
+        assert_eq!(
+            srcset("/uploads/abc.png", UploadKind::Avatar, "webp").as_deref(),
+            Some("/uploads/abc-64w.webp 64w, /uploads/abc-128w.webp 128w, /uploads/abc-256w.webp 256w")
+        );
+        assert_eq!(srcset("https://example.com/a.png", UploadKind::Avatar, "webp"), None);
+        assert_eq!(srcset("/uploads/abc.gif", UploadKind::Cover, "webp"), None);
+        assert_eq!(srcset("/uploads/abc.png", UploadKind::Inline, "webp"), None);
+    }
+}
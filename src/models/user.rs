@@ -13,6 +13,70 @@ pub struct UserPreview {
     pub following: bool,
 }
 
+/// Where an account stands. Accounts are never removed from the database, deleting
+/// one scrubs it and leaves its comments behind, anonymized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UserStatus {
+    #[default]
+    Active,
+    /// Locked out by an admin or after reports, their articles are hidden until reinstated.
+    Suspended,
+    /// Waiting for deletion, logging in again during the grace period reactivates it.
+    Deactivated,
+    Deleted,
+}
+
+impl UserStatus {
+    pub const ALL: [UserStatus; 4] = [
+        UserStatus::Active,
+        UserStatus::Suspended,
+        UserStatus::Deactivated,
+        UserStatus::Deleted,
+    ];
+
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UserStatus::Active => "active",
+            UserStatus::Suspended => "suspended",
+            UserStatus::Deactivated => "deactivated",
+            UserStatus::Deleted => "deleted",
+        }
+    }
+
+    #[inline]
+    pub fn label(self) -> &'static str {
+        match self {
+            UserStatus::Active => "Active",
+            UserStatus::Suspended => "Suspended",
+            UserStatus::Deactivated => "Pending deletion",
+            UserStatus::Deleted => "Deleted",
+        }
+    }
+}
+
+impl std::str::FromStr for UserStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.as_str() == s)
+            .ok_or_else(|| format!("Unknown user status {s}"))
+    }
+}
+
+/// Days a deactivated account waits before being deleted, configured with
+/// `ACCOUNT_DELETION_GRACE_DAYS`.
+#[cfg(feature = "ssr")]
+pub fn deletion_grace_days() -> i64 {
+    std::env::var("ACCOUNT_DELETION_GRACE_DAYS")
+        .ok()
+        .and_then(|x| x.parse().ok())
+        .unwrap_or(30)
+}
+
 #[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct User {
     id: Option<Uuid>, // Added ID field to store after insertion
@@ -135,11 +199,11 @@ impl User {
         Ok(self)
     }
 
-    #[warn(dead_code)]
+    #[cfg(feature = "ssr")]
     pub fn set_image(&mut self, image: String) -> Result<&mut Self, String> {
         if image.is_empty() {
             self.image = None;
-        } else if !image.starts_with("http") {
+        } else if !crate::storage::is_image_url(&image) {
             return Err("Invalid image!".into());
         } else {
             self.image = Some(image);
@@ -147,7 +211,7 @@ impl User {
         Ok(self)
     }
 
-    fn hash_email(email: &str) -> String {
+    pub fn hash_email(email: &str) -> String {
         let mut hasher = Sha256::new();
         hasher.update(email);
         format!("{:x}", hasher.finalize()) // Returns the hash as a hex string
@@ -155,9 +219,15 @@ impl User {
 
     #[warn(dead_code)]
     fn generate_username(name: &str) -> String {
+        Self::generate_username_with(name, &mut rand::thread_rng())
+    }
+
+    /// A username made from `name`, with the random part drawn from `rng` so seeded
+    /// generators always give the same ones.
+    pub fn generate_username_with<R: Rng + ?Sized>(name: &str, rng: &mut R) -> String {
         let words: Vec<&str> = name.split_whitespace().collect();
         let random_string: String = std::iter::repeat(())
-            .map(|()| rand::thread_rng().sample(Alphanumeric))
+            .map(|()| rng.sample(Alphanumeric))
             .take(7)
             .map(char::from)
             .collect();
@@ -205,6 +275,155 @@ impl User {
         .await
     }
 
+    #[cfg(feature = "ssr")]
+    pub async fn status(user_id: uuid::Uuid) -> Result<UserStatus, sqlx::Error> {
+        sqlx::query!("SELECT status FROM Users WHERE id = $1", user_id)
+            .fetch_one(crate::database::get_db())
+            .await?
+            .status
+            .parse()
+            .map_err(|x: String| sqlx::Error::Decode(x.into()))
+    }
+
+    /// Starts the grace period before `user_id` gets deleted, as long as `password`
+    /// is theirs. Returns when the account will be deleted, or `None` when the
+    /// password is wrong.
+    #[cfg(feature = "ssr")]
+    pub async fn deactivate(
+        user_id: uuid::Uuid,
+        password: &str,
+    ) -> Result<Option<chrono::DateTime<chrono::Utc>>, sqlx::Error> {
+        sqlx::query!(
+            "
+            UPDATE Users SET status = 'deactivated', delete_after = NOW() + make_interval(days => $3)
+            WHERE id = $1 AND status = 'active' AND password = crypt($2, password)
+            RETURNING delete_after AS \"delete_after!\"",
+            user_id,
+            password,
+            deletion_grace_days() as i32,
+        )
+        .fetch_optional(crate::database::get_db())
+        .await
+        .map(|x| x.map(|x| x.delete_after))
+    }
+
+    /// Cancels the deletion of `user_id`, returning whether it was pending.
+    #[cfg(feature = "ssr")]
+    pub async fn reactivate(user_id: uuid::Uuid) -> Result<bool, sqlx::Error> {
+        sqlx::query!(
+            "UPDATE Users SET status = 'active', delete_after = NULL WHERE id = $1 AND status = 'deactivated'",
+            user_id
+        )
+        .execute(crate::database::get_db())
+        .await
+        .map(|x| x.rows_affected() > 0)
+    }
+
+    /// Deletes the accounts whose grace period is over. Their personal data and
+    /// everything only meaningful to them goes away, their articles are unpublished
+    /// and their comments stay, signed by a scrubbed account. Returns how many
+    /// accounts were deleted.
+    #[cfg(feature = "ssr")]
+    pub async fn delete_expired() -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let mut transaction = crate::database::get_db().begin().await?;
+        let ids = sqlx::query!(
+            "
+            UPDATE Users SET
+                status = 'deleted',
+                delete_after = NULL,
+                name = 'Deleted user',
+                username = 'deleted_' || replace(id::text, '-', ''),
+                email = id::text || '@deleted.invalid',
+                email_hash = id::text,
+                password = '',
+                bio = NULL,
+                image = NULL,
+                role = 'user'
+            WHERE status = 'deactivated' AND delete_after <= NOW()
+            RETURNING id"
+        )
+        .fetch_all(transaction.as_mut())
+        .await?
+        .into_iter()
+        .map(|x| x.id)
+        .collect::<Vec<_>>();
+
+        if !ids.is_empty() {
+            sqlx::query!(
+                "
+                UPDATE Articles SET hidden_at = COALESCE(hidden_at, NOW()), hidden_by_reports = FALSE
+                WHERE author_id = ANY($1)",
+                &ids
+            )
+            .execute(transaction.as_mut())
+            .await?;
+            sqlx::query!("DELETE FROM Follows WHERE follower_id = ANY($1) OR influencer_id = ANY($1)", &ids)
+                .execute(transaction.as_mut())
+                .await?;
+            sqlx::query!("DELETE FROM OrganizationFollows WHERE follower_id = ANY($1)", &ids)
+                .execute(transaction.as_mut())
+                .await?;
+            sqlx::query!("DELETE FROM FavArticles WHERE user_id = ANY($1)", &ids)
+                .execute(transaction.as_mut())
+                .await?;
+            sqlx::query!("DELETE FROM Bookmarks WHERE user_id = ANY($1)", &ids)
+                .execute(transaction.as_mut())
+                .await?;
+            sqlx::query!("DELETE FROM Reactions WHERE user_id = ANY($1)", &ids)
+                .execute(transaction.as_mut())
+                .await?;
+            sqlx::query!("DELETE FROM Reports WHERE reporter_id = ANY($1)", &ids)
+                .execute(transaction.as_mut())
+                .await?;
+            sqlx::query!("DELETE FROM Notifications WHERE user_id = ANY($1) OR actor_id = ANY($1)", &ids)
+                .execute(transaction.as_mut())
+                .await?;
+            sqlx::query!("DELETE FROM DigestPreferences WHERE user_id = ANY($1)", &ids)
+                .execute(transaction.as_mut())
+                .await?;
+            sqlx::query!("DELETE FROM Blocks WHERE blocker_id = ANY($1) OR blocked_id = ANY($1)", &ids)
+                .execute(transaction.as_mut())
+                .await?;
+            sqlx::query!("DELETE FROM Mutes WHERE muter_id = ANY($1) OR muted_id = ANY($1)", &ids)
+                .execute(transaction.as_mut())
+                .await?;
+            let exports = sqlx::query!("DELETE FROM DataExports WHERE user_id = ANY($1) RETURNING id", &ids)
+                .fetch_all(transaction.as_mut())
+                .await?
+                .into_iter()
+                .map(|x| x.id)
+                .collect::<Vec<_>>();
+            sqlx::query!("DELETE FROM OrganizationMembers WHERE user_id = ANY($1)", &ids)
+                .execute(transaction.as_mut())
+                .await?;
+            sqlx::query!("DELETE FROM ArticleAuthors WHERE user_id = ANY($1)", &ids)
+                .execute(transaction.as_mut())
+                .await?;
+            // Blobs are shared by everyone who uploaded the same content
+            let uploads = sqlx::query!(
+                "
+                WITH deleted AS (DELETE FROM Uploads WHERE user_id = ANY($1) RETURNING key)
+                SELECT DISTINCT key FROM deleted
+                WHERE NOT EXISTS(SELECT 1 FROM Uploads AS u WHERE u.key = deleted.key AND u.user_id <> ALL($1))",
+                &ids
+            )
+            .fetch_all(transaction.as_mut())
+            .await?;
+
+            // Before committing, so a failure leaves the accounts to be deleted again
+            crate::models::DataExport::delete_archives(&exports).await?;
+            let store = crate::storage::get_store();
+            for upload in uploads {
+                for key in super::stored_keys(&upload.key) {
+                    store.delete(&key).await?;
+                }
+            }
+        }
+
+        transaction.commit().await?;
+        Ok(ids.len())
+    }
+
     #[cfg(feature = "ssr")]
     pub async fn insert(&mut self) -> Result<Uuid, sqlx::Error> {
         let pool = crate::database::get_db();
@@ -263,6 +482,16 @@ impl User {
         .await
     }
 
+    #[cfg(feature = "ssr")]
+    pub async fn set_avatar(
+        user_id: uuid::Uuid,
+        image: &str,
+    ) -> Result<sqlx::postgres::PgQueryResult, sqlx::Error> {
+        sqlx::query!("UPDATE Users SET image=$2 WHERE id=$1", user_id, image)
+            .execute(crate::database::get_db())
+            .await
+    }
+
     #[cfg(feature = "ssr")]
     #[allow(dead_code)]
     pub async fn update_password(
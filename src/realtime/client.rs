@@ -0,0 +1,36 @@
+use leptos::on_cleanup;
+use wasm_bindgen::{closure::Closure, JsCast};
+
+use super::RealtimeMessage;
+
+/// Listens to the events of `article` and of the logged user until the calling
+/// scope is cleaned up. The browser reconnects on its own if the stream drops.
+pub fn subscribe(article: Option<uuid::Uuid>, on_message: impl Fn(RealtimeMessage) + 'static) {
+    let url = match article {
+        Some(id) => format!("/events?article={id}"),
+        None => "/events".to_string(),
+    };
+    let source = match web_sys::EventSource::new(&url) {
+        Ok(x) => x,
+        Err(err) => {
+            tracing::error!("could not open the realtime stream: {err:?}");
+            return;
+        }
+    };
+
+    let callback = Closure::<dyn FnMut(web_sys::MessageEvent)>::new(move |event: web_sys::MessageEvent| {
+        let Some(data) = event.data().as_string() else {
+            return;
+        };
+        match serde_json::from_str::<RealtimeMessage>(&data) {
+            Ok(message) => on_message(message),
+            Err(err) => tracing::error!("invalid realtime event: {err:?}"),
+        }
+    });
+    source.set_onmessage(Some(callback.as_ref().unchecked_ref()));
+
+    on_cleanup(move || {
+        source.close();
+        drop(callback);
+    });
+}
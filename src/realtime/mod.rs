@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[cfg(feature = "hydrate")]
+mod client;
+#[cfg(feature = "ssr")]
+mod server;
+
+#[cfg(feature = "hydrate")]
+pub use client::subscribe;
+#[cfg(feature = "ssr")]
+pub use server::{init, publish, sse_handler};
+
+/// Who gets to see an event: everyone reading an article, or a single user.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub enum Topic {
+    Article(Uuid),
+    User(Uuid),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[allow(clippy::enum_variant_names)]
+pub enum RealtimeEvent {
+    CommentsChanged,
+    /// Totals of every reaction on the article, favorites included, or on one of its
+    /// comments when `comment_id` is set. `reacted` is always false.
+    ReactionsChanged {
+        totals: Vec<crate::models::ReactionCount>,
+        #[serde(default)]
+        comment_id: Option<Uuid>,
+    },
+    NotificationsChanged,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RealtimeMessage {
+    pub topic: Topic,
+    pub event: RealtimeEvent,
+}
+
+impl RealtimeMessage {
+    /// Whether a subscriber following `article`, logged in as `user_id`, should get it.
+    pub fn is_for(&self, article: Option<Uuid>, user_id: Option<Uuid>) -> bool {
+        match self.topic {
+            Topic::Article(id) => article == Some(id),
+            Topic::User(id) => user_id == Some(id),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use axum::{
+    extract::Query,
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::Stream;
+use serde::Deserialize;
+use std::sync::OnceLock;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use super::{RealtimeEvent, RealtimeMessage, Topic};
+
+/// Postgres channel shared by every instance of the app.
+const CHANNEL: &str = "realtime";
+/// Messages kept for subscribers that fall behind before they start missing some.
+const BUFFER: usize = 256;
+
+static SENDER: OnceLock<broadcast::Sender<RealtimeMessage>> = OnceLock::new();
+
+fn sender() -> &'static broadcast::Sender<RealtimeMessage> {
+    SENDER.get_or_init(|| broadcast::channel(BUFFER).0)
+}
+
+/// Starts forwarding what any instance publishes to the subscribers of this one.
+#[tracing::instrument]
+pub async fn init() -> Result<(), sqlx::Error> {
+    let mut listener = sqlx::postgres::PgListener::connect_with(crate::database::get_db()).await?;
+    listener.listen(CHANNEL).await?;
+
+    tokio::spawn(async move {
+        loop {
+            match listener.recv().await {
+                Ok(notification) => {
+                    match serde_json::from_str::<RealtimeMessage>(notification.payload()) {
+                        // Nobody listening is fine
+                        Ok(message) => drop(sender().send(message)),
+                        Err(x) => tracing::error!("invalid realtime payload: {x:?}"),
+                    }
+                }
+                Err(x) => {
+                    // The listener reconnects on the next call
+                    tracing::error!("problem while listening for realtime events: {x:?}");
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Sends an event to every instance. Failing to do so only costs a live update,
+/// so it is logged instead of returned.
+#[tracing::instrument]
+pub async fn publish(topic: Topic, event: RealtimeEvent) {
+    let payload = match serde_json::to_string(&RealtimeMessage { topic, event }) {
+        Ok(x) => x,
+        Err(x) => {
+            tracing::error!("could not serialize a realtime event: {x:?}");
+            return;
+        }
+    };
+    if let Err(x) = sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(CHANNEL)
+        .bind(payload)
+        .execute(crate::database::get_db())
+        .await
+    {
+        tracing::error!("could not publish a realtime event: {x:?}");
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Subscription {
+    article: Option<Uuid>,
+}
+
+/// Server-sent events for one article and for the logged user.
+#[tracing::instrument(skip(headers))]
+pub async fn sse_handler(
+    headers: HeaderMap,
+    Query(subscription): Query<Subscription>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let user_id = crate::auth::get_user_id_from_headers(&headers);
+    let article = subscription.article;
+
+    let stream = futures::stream::unfold(sender().subscribe(), move |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(message) if message.is_for(article, user_id) => {
+                    return Some((Event::default().json_data(&message).map_err(axum::Error::new), receiver));
+                }
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("realtime subscriber skipped {skipped} events");
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
@@ -0,0 +1,592 @@
+use leptos::*;
+use leptos_meta::*;
+use leptos_router::*;
+
+/// How many days the statistics go back.
+const STATS_DAYS: i32 = 30;
+
+/// Role of the logged user, to show the links to the pages it opens.
+#[server(MyRoleAction, "/api", "GetJson")]
+#[tracing::instrument]
+pub async fn my_role() -> Result<crate::models::Role, ServerFnError> {
+    Ok(crate::auth::current_role().await.unwrap_or_default())
+}
+
+#[server(AdminStatsAction, "/api", "GetJson")]
+#[tracing::instrument]
+pub async fn admin_stats() -> Result<Vec<crate::models::DailyStats>, ServerFnError> {
+    crate::auth::require_permission(crate::models::Permission::Administer).await?;
+
+    crate::models::DailyStats::last_days(STATS_DAYS)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while computing the statistics: {x:?}");
+            ServerFnError::ServerError("Could not compute the statistics, try again later".into())
+        })
+}
+
+#[server(AdminUsersAction, "/api", "GetJson")]
+#[tracing::instrument]
+pub async fn admin_users(
+    query: String,
+    filter: String,
+    page: u32,
+) -> Result<Vec<crate::models::AdminUser>, ServerFnError> {
+    crate::auth::require_permission(crate::models::Permission::Administer).await?;
+
+    crate::models::AdminUser::search(&query, &filter, page.into())
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while searching users: {x:?}");
+            ServerFnError::ServerError("Could not retrieve users, try again later".into())
+        })
+}
+
+#[server(AdminArticlesAction, "/api", "GetJson")]
+#[tracing::instrument]
+pub async fn admin_articles(
+    query: String,
+    filter: String,
+    page: u32,
+) -> Result<Vec<crate::models::AdminArticle>, ServerFnError> {
+    crate::auth::require_permission(crate::models::Permission::Administer).await?;
+
+    crate::models::AdminArticle::search(&query, &filter, page.into())
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while searching articles: {x:?}");
+            ServerFnError::ServerError("Could not retrieve articles, try again later".into())
+        })
+}
+
+#[server(AdminCommentsAction, "/api", "GetJson")]
+#[tracing::instrument]
+pub async fn admin_comments(
+    query: String,
+    filter: String,
+    page: u32,
+) -> Result<Vec<crate::models::AdminComment>, ServerFnError> {
+    crate::auth::require_permission(crate::models::Permission::Administer).await?;
+
+    crate::models::AdminComment::search(&query, &filter, page.into())
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while searching comments: {x:?}");
+            ServerFnError::ServerError("Could not retrieve comments, try again later".into())
+        })
+}
+
+#[server(AdminLogAction, "/api", "GetJson")]
+#[tracing::instrument]
+pub async fn admin_log() -> Result<Vec<crate::models::AuditEntry>, ServerFnError> {
+    crate::auth::require_permission(crate::models::Permission::Administer).await?;
+
+    crate::models::AuditEntry::latest(crate::models::ADMIN_PAGE_SIZE)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while reading the moderation log: {x:?}");
+            ServerFnError::ServerError("Could not retrieve the moderation log, try again later".into())
+        })
+}
+
+#[server(SuspendUserAction, "/api")]
+#[tracing::instrument]
+pub async fn suspend_user(user_id: uuid::Uuid, suspended: bool) -> Result<(), ServerFnError> {
+    let admin_id = crate::auth::require_permission(crate::models::Permission::Administer).await?;
+    if admin_id == user_id {
+        return Err(ServerFnError::ServerError("You cannot suspend yourself".into()));
+    }
+
+    crate::models::AdminUser::set_suspended(user_id, admin_id, suspended)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while suspending {user_id}: {x:?}");
+            ServerFnError::ServerError("Could not update the user, try again later".into())
+        })
+}
+
+#[server(UnpublishArticleAction, "/api")]
+#[tracing::instrument]
+pub async fn unpublish_article(slug: String, unpublished: bool) -> Result<(), ServerFnError> {
+    let admin_id = crate::auth::require_permission(crate::models::Permission::Administer).await?;
+
+    crate::models::Article::set_hidden(&slug, admin_id, unpublished)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while unpublishing {slug}: {x:?}");
+            ServerFnError::ServerError("Could not update the article, try again later".into())
+        })
+}
+
+#[server(AdminDeleteCommentAction, "/api")]
+#[tracing::instrument]
+pub async fn admin_delete_comment(id: uuid::Uuid) -> Result<(), ServerFnError> {
+    let admin_id = crate::auth::require_permission(crate::models::Permission::Administer).await?;
+
+    let article_id = crate::models::Comment::delete(id, admin_id, true)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while deleting the comment {id}: {x:?}");
+            ServerFnError::ServerError("Could not delete the comment, try again later".into())
+        })?;
+    if let Some(article_id) = article_id {
+        crate::routes::publish_comments(article_id).await;
+    }
+    Ok(())
+}
+
+/// Which list of the dashboard is shown, taken from `?tab=`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AdminTab {
+    Stats,
+    Users,
+    Articles,
+    Comments,
+    Log,
+}
+
+impl AdminTab {
+    const ALL: [AdminTab; 5] = [
+        AdminTab::Stats,
+        AdminTab::Users,
+        AdminTab::Articles,
+        AdminTab::Comments,
+        AdminTab::Log,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            AdminTab::Stats => "stats",
+            AdminTab::Users => "users",
+            AdminTab::Articles => "articles",
+            AdminTab::Comments => "comments",
+            AdminTab::Log => "log",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            AdminTab::Stats => "Statistics",
+            AdminTab::Users => "Users",
+            AdminTab::Articles => "Articles",
+            AdminTab::Comments => "Comments",
+            AdminTab::Log => "Moderation log",
+        }
+    }
+
+    /// Values the filter select offers on this tab, the first one meaning no filter.
+    fn filters(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            AdminTab::Users => &[
+                ("", "Everybody"),
+                ("suspended", "Suspended"),
+                ("deactivated", "Pending deletion"),
+                ("deleted", "Deleted"),
+                ("user", "Users"),
+                ("moderator", "Moderators"),
+                ("admin", "Admins"),
+            ],
+            AdminTab::Articles => &[("", "All articles"), ("unpublished", "Unpublished")],
+            AdminTab::Comments => &[("", "All comments"), ("hidden", "Hidden")],
+            AdminTab::Stats | AdminTab::Log => &[],
+        }
+    }
+
+    fn from_query(tab: Option<&String>) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|x| tab.is_some_and(|tab| tab == x.as_str()))
+            .unwrap_or(AdminTab::Stats)
+    }
+}
+
+/// Search and paging state of the dashboard, kept in the query string.
+#[derive(Debug, Clone, PartialEq)]
+struct AdminQuery {
+    tab: AdminTab,
+    query: String,
+    filter: String,
+    page: u32,
+}
+
+#[tracing::instrument]
+#[component]
+pub fn Admin() -> impl IntoView {
+    let query = use_query_map();
+    let state = create_memo(move |_| {
+        query.with(|x| AdminQuery {
+            tab: AdminTab::from_query(x.get("tab")),
+            query: x.get("q").cloned().unwrap_or_default(),
+            filter: x.get("filter").cloned().unwrap_or_default(),
+            page: x.get("page").and_then(|x| x.parse().ok()).unwrap_or_default(),
+        })
+    });
+
+    view! {
+        <Title text="Admin"/>
+        <div class="admin-page">
+            <div class="container page">
+                <div class="articles-toggle">
+                    <ul class="nav nav-pills outline-active">
+                        {AdminTab::ALL
+                            .into_iter()
+                            .map(|tab| view! {
+                                <li class="nav-item">
+                                    <a
+                                        class="nav-link"
+                                        class:active=move || state.with(|x| x.tab == tab)
+                                        href=format!("/admin?tab={}", tab.as_str())
+                                    >
+                                        {tab.label()}
+                                    </a>
+                                </li>
+                            })
+                            .collect_view()}
+                    </ul>
+                </div>
+                <AdminSearch state/>
+                {move || match state.with(|x| x.tab) {
+                    AdminTab::Stats => view! { <AdminStats/> }.into_view(),
+                    AdminTab::Users => view! { <AdminUsers state/> }.into_view(),
+                    AdminTab::Articles => view! { <AdminArticles state/> }.into_view(),
+                    AdminTab::Comments => view! { <AdminComments state/> }.into_view(),
+                    AdminTab::Log => view! { <AdminLog/> }.into_view(),
+                }}
+            </div>
+        </div>
+    }
+}
+
+/// Search box and filter of the lists, submitted as a plain GET to keep links shareable.
+#[component]
+fn AdminSearch(state: Memo<AdminQuery>) -> impl IntoView {
+    move || {
+        let AdminQuery { tab, query, filter, .. } = state.get();
+        (!tab.filters().is_empty()).then(|| view! {
+            <Form method="GET" action="/admin" class="admin-search">
+                <input type="hidden" name="tab" value=tab.as_str()/>
+                <input
+                    type="search"
+                    name="q"
+                    class="form-control"
+                    placeholder="Search"
+                    value=query
+                />
+                <select name="filter" class="form-control">
+                    {tab
+                        .filters()
+                        .iter()
+                        .map(|(value, label)| view! {
+                            <option value=*value selected=filter == *value>{*label}</option>
+                        })
+                        .collect_view()}
+                </select>
+                <button type="submit" class="btn btn-outline-primary">"Search"</button>
+            </Form>
+        })
+    }
+}
+
+/// Previous and next links, the next one only while the page is full.
+#[component]
+fn AdminPager(state: Memo<AdminQuery>, rows: usize) -> impl IntoView {
+    let page = state.with_untracked(|x| x.page);
+    let full = rows as i64 >= crate::models::ADMIN_PAGE_SIZE;
+    let query = use_query_map().get_untracked();
+    let href = move |page: u32| {
+        let mut query = query.clone();
+        query.insert("page".into(), page.to_string());
+        format!("/admin{}", query.to_query_string())
+    };
+
+    view! {
+        <div class="admin-pager">
+            {(page > 0).then(|| view! {
+                <A href=href(page - 1) class="btn btn-sm btn-outline-secondary">"Previous"</A>
+            })}
+            {full.then(|| view! {
+                <A href=href(page + 1) class="btn btn-sm btn-outline-secondary">"Next"</A>
+            })}
+        </div>
+    }
+}
+
+#[component]
+fn AdminError() -> impl IntoView {
+    view! {
+        <p class="error-messages text-xs-center">
+            "Only admins can see this page, or something went wrong. Try again later."
+        </p>
+    }
+}
+
+#[component]
+fn AdminStats() -> impl IntoView {
+    let stats = create_resource(|| (), |_| admin_stats());
+
+    view! {
+        <Suspense fallback=|| view! { <p>"Loading statistics..."</p> }>
+            <ErrorBoundary fallback=|_| view! { <AdminError/> }>
+                {move || stats.get().map(|x| x.map(|days| {
+                    let total = days.iter().fold(crate::models::DailyStats::default(), |mut total, x| {
+                        total.signups += x.signups;
+                        total.articles += x.articles;
+                        total.comments += x.comments;
+                        total
+                    });
+                    view! {
+                        <table class="table admin-table">
+                            <thead>
+                                <tr>
+                                    <th>"Day"</th>
+                                    <th>"Signups"</th>
+                                    <th>"Articles"</th>
+                                    <th>"Comments"</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {days
+                                    .into_iter()
+                                    .map(|x| view! {
+                                        <tr>
+                                            <td>{x.day}</td>
+                                            <td>{x.signups}</td>
+                                            <td>{x.articles}</td>
+                                            <td>{x.comments}</td>
+                                        </tr>
+                                    })
+                                    .collect_view()}
+                            </tbody>
+                            <tfoot>
+                                <tr>
+                                    <th>{format!("Last {STATS_DAYS} days")}</th>
+                                    <th>{total.signups}</th>
+                                    <th>{total.articles}</th>
+                                    <th>{total.comments}</th>
+                                </tr>
+                            </tfoot>
+                        </table>
+                    }
+                }))}
+            </ErrorBoundary>
+        </Suspense>
+    }
+}
+
+#[component]
+fn AdminUsers(state: Memo<AdminQuery>) -> impl IntoView {
+    let suspend = create_server_action::<SuspendUserAction>();
+    let users = create_resource(
+        move || (state.get(), suspend.version().get()),
+        |(state, _)| admin_users(state.query, state.filter, state.page),
+    );
+
+    view! {
+        {move || suspend.value().get().and_then(Result::err).map(|x| view! {
+            <p class="error-messages">{x.to_string()}</p>
+        })}
+        <Suspense fallback=|| view! { <p>"Loading users..."</p> }>
+            <ErrorBoundary fallback=|_| view! { <AdminError/> }>
+                {move || users.get().map(|x| x.map(|users| {
+                    let rows = users.len();
+                    view! {
+                        <table class="table admin-table">
+                            <thead>
+                                <tr>
+                                    <th>"User"</th>
+                                    <th>"Email"</th>
+                                    <th>"Role"</th>
+                                    <th>"Status"</th>
+                                    <th>"Articles"</th>
+                                    <th>"Comments"</th>
+                                    <th>"Joined"</th>
+                                    <th></th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {users
+                                    .into_iter()
+                                    .map(|x| {
+                                        use crate::models::UserStatus;
+                                        let suspended = x.status == UserStatus::Suspended;
+                                        view! {
+                                            <tr class:text-muted=x.status != UserStatus::Active>
+                                                <td>
+                                                    <A href=format!("/profile/{}", x.id)>{x.username}</A>
+                                                    <div class="text-muted">{x.name}</div>
+                                                </td>
+                                                <td>{x.email}</td>
+                                                <td>{x.role.label()}</td>
+                                                <td>{x.status.label()}</td>
+                                                <td>{x.articles}</td>
+                                                <td>{x.comments}</td>
+                                                <td>{x.created_at}</td>
+                                                <td>
+                                                    {matches!(x.status, UserStatus::Active | UserStatus::Suspended).then(|| view! {
+                                                        <ActionForm action=suspend class="inline">
+                                                            <input type="hidden" name="user_id" value=x.id.to_string()/>
+                                                            <input type="hidden" name="suspended" value=(!suspended).to_string()/>
+                                                            <button type="submit" class="btn btn-sm btn-outline-danger">
+                                                                {if suspended { "Unsuspend" } else { "Suspend" }}
+                                                            </button>
+                                                        </ActionForm>
+                                                    })}
+                                                </td>
+                                            </tr>
+                                        }
+                                    })
+                                    .collect_view()}
+                            </tbody>
+                        </table>
+                        <AdminPager state rows/>
+                    }
+                }))}
+            </ErrorBoundary>
+        </Suspense>
+    }
+}
+
+#[component]
+fn AdminArticles(state: Memo<AdminQuery>) -> impl IntoView {
+    let unpublish = create_server_action::<UnpublishArticleAction>();
+    let articles = create_resource(
+        move || (state.get(), unpublish.version().get()),
+        |(state, _)| admin_articles(state.query, state.filter, state.page),
+    );
+
+    view! {
+        {move || unpublish.value().get().and_then(Result::err).map(|x| view! {
+            <p class="error-messages">{x.to_string()}</p>
+        })}
+        <Suspense fallback=|| view! { <p>"Loading articles..."</p> }>
+            <ErrorBoundary fallback=|_| view! { <AdminError/> }>
+                {move || articles.get().map(|x| x.map(|articles| {
+                    let rows = articles.len();
+                    view! {
+                        <table class="table admin-table">
+                            <thead>
+                                <tr>
+                                    <th>"Article"</th>
+                                    <th>"Author"</th>
+                                    <th>"Comments"</th>
+                                    <th>"Created"</th>
+                                    <th></th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {articles
+                                    .into_iter()
+                                    .map(|x| view! {
+                                        <tr class:text-muted=x.unpublished>
+                                            <td><A href=format!("/article/{}", x.slug)>{x.title}</A></td>
+                                            <td>
+                                                <A href=format!("/profile/{}", x.author_id)>{x.author_username}</A>
+                                            </td>
+                                            <td>{x.comments}</td>
+                                            <td>{x.created_at}</td>
+                                            <td>
+                                                <ActionForm action=unpublish class="inline">
+                                                    <input type="hidden" name="slug" value=x.slug.clone()/>
+                                                    <input type="hidden" name="unpublished" value=(!x.unpublished).to_string()/>
+                                                    <button type="submit" class="btn btn-sm btn-outline-warning">
+                                                        {if x.unpublished { "Republish" } else { "Unpublish" }}
+                                                    </button>
+                                                </ActionForm>
+                                            </td>
+                                        </tr>
+                                    })
+                                    .collect_view()}
+                            </tbody>
+                        </table>
+                        <AdminPager state rows/>
+                    }
+                }))}
+            </ErrorBoundary>
+        </Suspense>
+    }
+}
+
+#[component]
+fn AdminComments(state: Memo<AdminQuery>) -> impl IntoView {
+    let delete = create_server_action::<AdminDeleteCommentAction>();
+    let comments = create_resource(
+        move || (state.get(), delete.version().get()),
+        |(state, _)| admin_comments(state.query, state.filter, state.page),
+    );
+
+    view! {
+        {move || delete.value().get().and_then(Result::err).map(|x| view! {
+            <p class="error-messages">{x.to_string()}</p>
+        })}
+        <Suspense fallback=|| view! { <p>"Loading comments..."</p> }>
+            <ErrorBoundary fallback=|_| view! { <AdminError/> }>
+                {move || comments.get().map(|x| x.map(|comments| {
+                    let rows = comments.len();
+                    view! {
+                        <table class="table admin-table">
+                            <thead>
+                                <tr>
+                                    <th>"Comment"</th>
+                                    <th>"Author"</th>
+                                    <th>"Article"</th>
+                                    <th>"Posted"</th>
+                                    <th></th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {comments
+                                    .into_iter()
+                                    .map(|x| view! {
+                                        <tr class:text-muted=x.hidden>
+                                            <td class="admin-comment-body">{x.body}</td>
+                                            <td><A href=format!("/profile/{}", x.user_id)>{x.username}</A></td>
+                                            <td><A href=format!("/article/{}", x.article_slug)>{x.article_title}</A></td>
+                                            <td>{x.created_at}</td>
+                                            <td>
+                                                <ActionForm action=delete class="inline">
+                                                    <input type="hidden" name="id" value=x.id.to_string()/>
+                                                    <button type="submit" class="btn btn-sm btn-outline-danger">"Delete"</button>
+                                                </ActionForm>
+                                            </td>
+                                        </tr>
+                                    })
+                                    .collect_view()}
+                            </tbody>
+                        </table>
+                        <AdminPager state rows/>
+                    }
+                }))}
+            </ErrorBoundary>
+        </Suspense>
+    }
+}
+
+#[component]
+fn AdminLog() -> impl IntoView {
+    let entries = create_resource(|| (), |_| admin_log());
+
+    view! {
+        <Suspense fallback=|| view! { <p>"Loading the moderation log..."</p> }>
+            <ErrorBoundary fallback=|_| view! { <AdminError/> }>
+                {move || entries.get().map(|x| x.map(|entries| view! {
+                    <ul class="admin-log">
+                        {entries
+                            .into_iter()
+                            .map(|x| view! {
+                                <li>
+                                    <span class="date-posted">{x.created_at}</span>
+                                    " "
+                                    {x.actor_name.unwrap_or_else(|| "The system".into())}
+                                    " "
+                                    {x.action.label()}
+                                    " "
+                                    <span class="text-muted">{x.details}</span>
+                                </li>
+                            })
+                            .collect_view()}
+                    </ul>
+                }))}
+            </ErrorBoundary>
+        </Suspense>
+    }
+}
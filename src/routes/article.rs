@@ -2,19 +2,42 @@ use leptos::*;
 use leptos_meta::*;
 use leptos_router::*;
 
-use crate::components::ArticleMeta;
+use crate::components::{
+    ArticleMeta, CommentReactionTotals, CommentReactions, JsonLd, ReportButton, ResponsiveImage, Seo,
+};
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Default)]
 pub struct ArticleResult {
     pub(super) article: crate::models::Article,
     pub(super) logged_user: Option<crate::models::User>,
+    pub(super) series: Option<crate::models::Series>,
+    /// Whether the logged user was invited to co-author it and has not answered yet.
+    pub(super) invited: bool,
+    /// Whether the logged user may hide or delete the article and its comments.
+    pub(super) can_moderate: bool,
 }
 
 #[server(GetArticleAction, "/api", "GetJson")]
 #[tracing::instrument]
 pub async fn get_article(slug: String) -> Result<ArticleResult, ServerFnError> {
+    let series = crate::models::Series::for_article(&slug, crate::auth::get_user_id())
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while fetching the series of {slug}: {x:?}");
+            ServerFnError::ServerError("Could not retrieve the article, try again later".into())
+        })?;
+    let invited = match crate::auth::get_user_id() {
+        Some(user_id) => crate::models::CoAuthor::is_invited(&slug, user_id)
+            .await
+            .map_err(|x| {
+                tracing::error!("problem while checking invitations to {slug}: {x:?}");
+                ServerFnError::ServerError("Could not retrieve the article, try again later".into())
+            })?,
+        None => false,
+    };
+    let can_moderate = crate::auth::has_permission(crate::models::Permission::ModerateContent).await;
     Ok(ArticleResult {
-        article: crate::models::Article::for_article(slug)
+        article: crate::models::Article::for_article(slug, can_moderate)
             .await
             .map_err(|x| {
                 let err = format!("Error while getting user_profile articles: {x:?}");
@@ -22,9 +45,28 @@ pub async fn get_article(slug: String) -> Result<ArticleResult, ServerFnError> {
                 ServerFnError::ServerError("Could not retrieve articles, try again later".into())
             })?,
         logged_user: crate::auth::current_user().await.ok(),
+        series,
+        invited,
+        can_moderate,
     })
 }
 
+#[server(RespondInvitationAction, "/api")]
+#[tracing::instrument]
+pub async fn respond_invitation(slug: String, accept: bool) -> Result<(), ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError("You must be logged in".into()));
+    };
+
+    crate::models::CoAuthor::respond(&slug, user_id, accept)
+        .await
+        .map(|_| ())
+        .map_err(|x| {
+            tracing::error!("problem while answering the invitation to {slug}: {x:?}");
+            ServerFnError::ServerError("Could not answer the invitation, try again later".into())
+        })
+}
+
 #[tracing::instrument]
 #[component]
 pub fn Article(user_id: crate::auth::UserIdSignal) -> impl IntoView {
@@ -65,7 +107,7 @@ pub fn Article(user_id: crate::auth::UserIdSignal) -> impl IntoView {
                 {move || {
                     article.get().map(move |x| {
                         x.map(move |article_result| {
-                            title.set(article_result.article.slug.to_string());
+                            title.set(article_result.article.title.to_string());
                             view! {
                                 <ArticlePage user_id result=article_result />
                             }
@@ -77,28 +119,202 @@ pub fn Article(user_id: crate::auth::UserIdSignal) -> impl IntoView {
     }
 }
 
+/// Schema.org `BlogPosting` of an article, for search engines.
+fn blog_posting(article: &crate::models::Article) -> serde_json::Value {
+    let site_url = crate::components::site_url();
+    serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "BlogPosting",
+        "headline": article.title,
+        "description": article.description,
+        "image": article.cover_image,
+        "keywords": article.tag_list.join(", "),
+        "url": format!("{site_url}/article/{}", article.slug),
+        "datePublished": article.published_at,
+        "dateModified": article.modified_at,
+        "author": std::iter::once(&article.author)
+            .chain(&article.co_authors)
+            .map(|x| serde_json::json!({
+                "@type": "Person",
+                "name": x.name,
+                "url": format!("{site_url}/profile/{}", x.user_id),
+            }))
+            .collect::<Vec<_>>(),
+        "publisher": {
+            "@type": "Organization",
+            "name": crate::components::SITE_NAME,
+            "url": site_url,
+        },
+    })
+}
+
+/// Table of contents of the series, with the part being read highlighted.
+#[component]
+fn SeriesContents(series: crate::models::Series, slug: String) -> impl IntoView {
+    let count = series.parts.len();
+    let position = series
+        .parts
+        .iter()
+        .position(|x| x.slug == slug)
+        .map_or(0, |x| x + 1);
+
+    view! {
+        <nav class="series-contents" aria-label="Series">
+            <p>
+                <strong>{series.title}</strong>
+                {format!(" · Part {position} of {count}")}
+            </p>
+            <ol>
+                {series
+                    .parts
+                    .into_iter()
+                    .map(|part| {
+                        if part.slug == slug {
+                            view! { <li class="current" aria-current="page">{part.title}</li> }
+                        } else {
+                            view! { <li><A href=format!("/article/{}", part.slug)>{part.title}</A></li> }
+                        }
+                    })
+                    .collect_view()}
+            </ol>
+        </nav>
+    }
+}
+
+#[component]
+fn SeriesNavigation(series: crate::models::Series, slug: String) -> impl IntoView {
+    let (previous, next) = series.neighbours(&slug);
+    let link = |part: Option<&crate::models::SeriesPart>, label: &'static str, class: &'static str| {
+        part.map(|x| {
+            let title = x.title.clone();
+            view! {
+                <A href=format!("/article/{}", x.slug) class=format!("btn btn-outline-primary {class}")>
+                    {label}{title}
+                </A>
+            }
+        })
+    };
+
+    view! {
+        <div class="series-navigation">
+            {link(previous, "← ", "pull-xs-left")}
+            {link(next, "→ ", "pull-xs-right")}
+        </div>
+    }
+}
+
+/// Lets an invited user accept or decline co-authoring the article.
+#[component]
+fn InvitationBanner(slug: String) -> impl IntoView {
+    let respond = create_server_action::<RespondInvitationAction>();
+    let answered = move || respond.value().with(|x| matches!(x, Some(Ok(()))));
+    let slug = store_value(slug);
+    let form = move |accept: bool, label: &'static str, class: &'static str| view! {
+        <ActionForm action=respond class="inline">
+            <input type="hidden" name="slug" value=slug.get_value()/>
+            <input type="hidden" name="accept" value=accept.to_string()/>
+            <button type="submit" class=format!("btn btn-sm {class}") disabled=move || respond.pending().get()>
+                {label}
+            </button>
+        </ActionForm>
+    };
+
+    view! {
+        <Show
+            when=answered
+            fallback=move || view! {
+                <div class="alert alert-info invitation">
+                    "You were invited to co-author this article. "
+                    {form(true, "Accept", "btn-primary")}
+                    {form(false, "Decline", "btn-outline-secondary")}
+                </div>
+            }
+        >
+            <div class="alert alert-success invitation">"Your answer was saved."</div>
+        </Show>
+    }
+}
+
 #[component]
 fn ArticlePage(user_id: crate::auth::UserIdSignal, result: ArticleResult) -> impl IntoView {
     let article_signal = create_rw_signal(result.article.clone());
     let user_signal = create_rw_signal(result.logged_user);
     let tag_list = result.article.tag_list.clone();
+    let comments_changed = create_rw_signal(0_usize);
+    let comment_reactions = create_rw_signal(None);
+    provide_context(CommentReactionTotals(comment_reactions));
+
+    let article_id = result.article.id;
+    create_effect(move |_| {
+        #[cfg(feature = "hydrate")]
+        crate::realtime::subscribe(Some(article_id), move |message| match message.event {
+            crate::realtime::RealtimeEvent::CommentsChanged => {
+                comments_changed.update(|x| *x += 1);
+            }
+            crate::realtime::RealtimeEvent::ReactionsChanged {
+                totals,
+                comment_id: None,
+            } => {
+                article_signal.update(|x| x.apply_reaction_totals(&totals));
+            }
+            crate::realtime::RealtimeEvent::ReactionsChanged {
+                totals,
+                comment_id: Some(comment_id),
+            } => {
+                comment_reactions.set(Some((comment_id, totals)));
+            }
+            crate::realtime::RealtimeEvent::NotificationsChanged => {}
+        });
+        #[cfg(not(feature = "hydrate"))]
+        let _ = (article_id, comments_changed, comment_reactions);
+    });
+
+    let path = format!("/article/{}", result.article.slug);
+    let structured_data = blog_posting(&result.article);
+    let series = result.series.map(|series| (series, result.article.slug.clone()));
+    let series_footer = series.clone();
 
     view! {
+        <Seo
+            title=result.article.title.clone()
+            description=result.article.description.clone()
+            path
+            image=result.article.cover_image.clone()
+            is_article=true
+        />
+        <JsonLd data=structured_data/>
         <article class="article-page">
             <header class="banner">
                 <div class="container">
+                    {result.article.cover_image.clone().map(|x| view! {
+                        <ResponsiveImage
+                            src=x
+                            kind=crate::models::UploadKind::Cover
+                            sizes="(max-width: 1200px) 100vw, 1140px"
+                            alt=result.article.title.clone()
+                            class="cover-image"
+                        />
+                    })}
                     <h1>{result.article.title}</h1>
-                    <ArticleMeta user_id article=article_signal is_preview=false />
+                    <ArticleMeta user_id article=article_signal is_preview=false can_moderate=result.can_moderate/>
+                    {result.invited.then(|| view! { <InvitationBanner slug=result.article.slug.clone()/> })}
+                    <Show when=move || article_signal.with(|x| x.hidden) fallback=|| ()>
+                        <div class="alert alert-warning">
+                            "This article was hidden by a moderator. Only its authors and moderators can see it."
+                        </div>
+                    </Show>
                 </div>
             </header>
 
             <div class="container page">
                 <div class="row article-content">
                     <div class="col-md-12">
+                        {series.map(|(series, slug)| view! { <SeriesContents series slug/> })}
                         <div 
                             class="article-body"
                             inner_html={result.article.body}
                         ></div>
+                        {series_footer.map(|(series, slug)| view! { <SeriesNavigation series slug/> })}
                     </div>
                 </div>
 
@@ -120,12 +336,18 @@ fn ArticlePage(user_id: crate::auth::UserIdSignal, result: ArticleResult) -> imp
 
                 <div class="article-actions">
                     <div class="row" style="justify-content: center;">
-                        <ArticleMeta user_id article=article_signal is_preview=false />
+                        <ArticleMeta user_id article=article_signal is_preview=false can_moderate=result.can_moderate/>
                     </div>
                 </div>
 
                 <div class="row">
-                    <CommentSection user_id article=article_signal user=user_signal />
+                    <CommentSection
+                        user_id
+                        article=article_signal
+                        user=user_signal
+                        comments_changed=comments_changed.read_only()
+                        can_moderate=result.can_moderate
+                    />
                 </div>
             </div>
         </article>
@@ -134,7 +356,11 @@ fn ArticlePage(user_id: crate::auth::UserIdSignal, result: ArticleResult) -> imp
 
 #[server(PostCommentAction, "/api")]
 #[tracing::instrument]
-pub async fn post_comment(article_id: uuid::Uuid, body: String) -> Result<(), ServerFnError> {
+pub async fn post_comment(
+    article_id: uuid::Uuid,
+    parent_id: Option<uuid::Uuid>,
+    body: String,
+) -> Result<(), ServerFnError> {
     if body.trim().is_empty() {
         return Err(ServerFnError::ServerError("Comment cannot be empty".into()));
     }
@@ -142,15 +368,49 @@ pub async fn post_comment(article_id: uuid::Uuid, body: String) -> Result<(), Se
     let Some(user_id) = crate::auth::get_user_id() else {
         return Err(ServerFnError::ServerError("You must be logged in to comment".into()));
     };
+    crate::auth::require_not_blocked(user_id, None, Some(article_id), parent_id).await?;
 
-    crate::models::Comment::insert(article_id, user_id, body.trim().to_string())
+    crate::models::Comment::insert(article_id, user_id, parent_id, body.trim().to_string())
         .await
-        .map(|_| ())
         .map_err(|x| {
             let err = format!("Error while posting a comment: {x:?}");
             tracing::error!("{err}");
             ServerFnError::ServerError("Could not post comment. Please try again later.".into())
-        })
+        })?;
+
+    let notified = match parent_id {
+        Some(parent_id) => {
+            crate::models::Notification::for_comment_author(
+                parent_id,
+                user_id,
+                crate::models::NotificationKind::Reply,
+            )
+            .await
+        }
+        None => {
+            crate::models::Notification::for_article_author(
+                article_id,
+                user_id,
+                crate::models::NotificationKind::Comment,
+            )
+            .await
+        }
+    };
+    if let Err(x) = notified {
+        tracing::error!("problem while notifying a comment: {x:?}");
+    }
+    publish_comments(article_id).await;
+    Ok(())
+}
+
+/// Tells everyone reading an article that its comments changed.
+#[cfg(feature = "ssr")]
+pub(crate) async fn publish_comments(article_id: uuid::Uuid) {
+    crate::realtime::publish(
+        crate::realtime::Topic::Article(article_id),
+        crate::realtime::RealtimeEvent::CommentsChanged,
+    )
+    .await;
 }
 
 #[server(GetCommentsAction, "/api", "GetJson")]
@@ -158,7 +418,8 @@ pub async fn post_comment(article_id: uuid::Uuid, body: String) -> Result<(), Se
 pub async fn get_comments(
     article_id: uuid::Uuid,
 ) -> Result<Vec<crate::models::Comment>, ServerFnError> {
-    crate::models::Comment::get_all(article_id)
+    let moderator = crate::auth::has_permission(crate::models::Permission::ModerateContent).await;
+    crate::models::Comment::get_all(article_id, moderator)
         .await
         .map_err(|x| {
             let err = format!("Error fetching comments: {x:?}");
@@ -174,21 +435,88 @@ pub async fn delete_comment(id: uuid::Uuid) -> Result<(), ServerFnError> {
         return Err(ServerFnError::ServerError("You must be logged in".into()));
     };
 
-    crate::models::Comment::delete(id, user_id)
+    let moderator = crate::auth::has_permission(crate::models::Permission::ModerateContent).await;
+    let article_id = crate::models::Comment::delete(id, user_id, moderator)
         .await
-        .map(|_| ())
         .map_err(|x| {
             let err = format!("Error deleting comment: {x:?}");
             tracing::error!("{err}");
             ServerFnError::ServerError("Could not delete comment. Please try again later.".into())
+        })?;
+    if let Some(article_id) = article_id {
+        publish_comments(article_id).await;
+    }
+    Ok(())
+}
+
+#[server(UpdateCommentAction, "/api")]
+#[tracing::instrument]
+pub async fn update_comment(id: uuid::Uuid, body: String) -> Result<(), ServerFnError> {
+    if body.trim().is_empty() {
+        return Err(ServerFnError::ServerError("Comment cannot be empty".into()));
+    }
+
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError("You must be logged in".into()));
+    };
+
+    let article_id = crate::models::Comment::update(id, user_id, body.trim().to_string())
+        .await
+        .map_err(|x| {
+            let err = format!("Error updating comment: {x:?}");
+            tracing::error!("{err}");
+            ServerFnError::ServerError("Could not update comment. Please try again later.".into())
+        })?;
+    publish_comments(article_id).await;
+    Ok(())
+}
+
+#[server(HideCommentAction, "/api")]
+#[tracing::instrument]
+pub async fn hide_comment(id: uuid::Uuid, hidden: bool) -> Result<(), ServerFnError> {
+    let moderator_id = crate::auth::require_permission(crate::models::Permission::ModerateContent).await?;
+
+    let article_id = crate::models::Comment::set_hidden(id, moderator_id, hidden)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while hiding the comment {id}: {x:?}");
+            ServerFnError::ServerError("Could not hide the comment. Please try again later.".into())
+        })?;
+    publish_comments(article_id).await;
+    Ok(())
+}
+
+#[server(GetCommentHistoryAction, "/api", "GetJson")]
+#[tracing::instrument]
+pub async fn get_comment_history(
+    id: uuid::Uuid,
+) -> Result<Vec<crate::models::CommentRevision>, ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError("You must be logged in".into()));
+    };
+
+    let moderator = crate::auth::has_permission(crate::models::Permission::ModerateContent).await;
+    crate::models::Comment::revisions(id, user_id, moderator)
+        .await
+        .map_err(|x| {
+            let err = format!("Error fetching comment history: {x:?}");
+            tracing::error!("{err}");
+            ServerFnError::ServerError("Could not load the comment history. Please try again later.".into())
         })
 }
 
+/// Replies nested deeper than this are shown at this level.
+const MAX_COMMENT_DEPTH: usize = 4;
+
 #[component]
 fn CommentSection(
     user_id: crate::auth::UserIdSignal,
     article: crate::components::ArticleSignal,
     user: RwSignal<Option<crate::models::User>>,
+    /// Bumped whenever someone else posts a comment on this article.
+    comments_changed: ReadSignal<usize>,
+    can_moderate: bool,
+    #[prop(default = MAX_COMMENT_DEPTH)] max_depth: usize,
 ) -> impl IntoView {
     let comments_action = create_server_action::<PostCommentAction>();
     let result = comments_action.version();
@@ -196,13 +524,16 @@ fn CommentSection(
     let is_submitting = comments_action.pending();
     
     let comments = create_resource(
-        move || (result.get(), article.with(|a| a.id)),
-        move |(_, article_id)| async move {
-            reset_comment.set("");
-            get_comments(article_id).await
-        },
+        move || (result.get(), comments_changed.get(), article.with(|a| a.id)),
+        move |(_, _, article_id)| async move { get_comments(article_id).await },
     );
 
+    // Only our own posts clear the form, live updates keep what is being written
+    create_effect(move |_| {
+        result.get();
+        reset_comment.set("");
+    });
+
     view! {
         <section class="col-xs-12 col-md-8 offset-md-2">
             <Show 
@@ -263,11 +594,12 @@ fn CommentSection(
                     {move || comments.get().map(move |x| x.map(move |c| {
                         view! {
                             <For 
-                                each=move || c.clone().into_iter().enumerate()
-                                key=|(i, _)| *i
-                                children=move |(_, comment)| {
+                                each=move || crate::models::Comment::threaded(c.clone(), max_depth)
+                                key=|(comment, depth)| (comment.id, *depth)
+                                children=move |(comment, depth)| {
                                     let comment = create_rw_signal(comment);
-                                    view!{<Comment user_id comment comments />}
+                                    let article_author_id = article.with_untracked(|x| x.author.user_id);
+                                    view!{<Comment user_id comment comments depth reply=comments_action article_author_id can_moderate />}
                                 }
                             />
                         }
@@ -283,14 +615,53 @@ fn Comment<T: 'static + Clone, S: 'static>(
     user_id: crate::auth::UserIdSignal,
     comment: RwSignal<crate::models::Comment>,
     comments: Resource<T, S>,
+    depth: usize,
+    reply: Action<PostCommentAction, Result<(), ServerFnError>>,
+    article_author_id: uuid::Uuid,
+    can_moderate: bool,
 ) -> impl IntoView {
     let user_link = move || format!("/profile/{}", comment.with(|x| x.user_id.to_string()));
+    let show_reply_form = create_rw_signal(false);
+    let is_replying = reply.pending();
+    let reply_version = reply.version();
+
+    create_effect(move |_| {
+        reply_version.get();
+        show_reply_form.set(false);
+    });
     let user_image = move || comment.with(|x| x.user_image.clone().unwrap_or_default());
     let delete_c = create_server_action::<DeleteCommentsAction>();
     let delete_result = delete_c.value();
     let show_delete_confirm = create_rw_signal(false);
     let is_deleting = delete_c.pending();
 
+    let update_c = create_server_action::<UpdateCommentAction>();
+    let update_result = update_c.value();
+    let is_updating = update_c.pending();
+    let show_edit_form = create_rw_signal(false);
+
+    create_effect(move |_| {
+        if let Some(Ok(())) = update_result.get() {
+            show_edit_form.set(false);
+            comments.refetch();
+        }
+    });
+
+    let hide_c = create_server_action::<HideCommentAction>();
+    let hide_result = hide_c.value();
+
+    create_effect(move |_| {
+        if let Some(Ok(())) = hide_result.get() {
+            comments.refetch();
+        }
+    });
+
+    let history = create_action(|id: &uuid::Uuid| {
+        let id = *id;
+        async move { get_comment_history(id).await }
+    });
+    let show_history = create_rw_signal(false);
+
     create_effect(move |_| {
         if let Some(Ok(())) = delete_result.get() {
             tracing::info!("comment deleted!");
@@ -300,10 +671,71 @@ fn Comment<T: 'static + Clone, S: 'static>(
     });
 
     view! {
-        <div class="card">
+        <div class="card comment" style=format!("margin-left: {}rem;", depth * 2)>
             <div class="card-block">
-                <p class="card-text">{move || comment.with(|x| x.body.to_string())}</p>
+                <Show
+                    when=move || comment.with(|x| !x.deleted)
+                    fallback=|| view! { <p class="card-text text-muted">"This comment was deleted."</p> }
+                >
+                    <Show
+                        when=move || show_edit_form.get()
+                        fallback=move || view! {
+                            <Show
+                                when=move || comment.with(|x| x.hidden && !can_moderate)
+                                fallback=move || view! {
+                                    <p class="card-text">{move || comment.with(|x| x.body.to_string())}</p>
+                                }
+                            >
+                                <p class="card-text text-muted">"This comment was hidden by a moderator."</p>
+                            </Show>
+                        }
+                    >
+                        <ActionForm action=update_c>
+                            <input
+                                type="hidden"
+                                name="id"
+                                value=move || comment.with(|x| x.id.to_string())
+                            />
+                            <textarea
+                                name="body"
+                                class="form-control"
+                                rows="3"
+                                prop:value=move || comment.with(|x| x.body.to_string())
+                                disabled=move || is_updating.get()
+                            ></textarea>
+                            <button
+                                class="btn btn-sm btn-primary"
+                                type="submit"
+                                disabled=move || is_updating.get()
+                            >
+                                {move || if is_updating.get() { "Saving..." } else { "Save" }}
+                            </button>
+                        </ActionForm>
+                    </Show>
+                </Show>
+                <Show when=move || show_history.get() fallback=|| ()>
+                    <ul class="comment-history">
+                        {move || history.value().get().map(|revisions| match revisions {
+                            Ok(revisions) if revisions.is_empty() => {
+                                view! { <li>"No previous versions."</li> }.into_view()
+                            }
+                            Ok(revisions) => revisions
+                                .into_iter()
+                                .map(|x| view! {
+                                    <li>
+                                        <span class="date-posted">{x.replaced_at}</span>
+                                        <p class="card-text text-muted">{x.body}</p>
+                                    </li>
+                                })
+                                .collect_view(),
+                            Err(err) => view! {
+                                <li class="error-messages">{err.to_string()}</li>
+                            }.into_view(),
+                        })}
+                    </ul>
+                </Show>
             </div>
+            <Show when=move || comment.with(|x| !x.deleted) fallback=|| ()>
             <div class="card-footer">
                 <A href=user_link class="comment-author">
                     <img 
@@ -322,6 +754,27 @@ fn Comment<T: 'static + Clone, S: 'static>(
                         x.created_at.to_string()
                     })}
                 </span>
+                <Show when=move || comment.with(|x| x.edited) fallback=|| ()>
+                    <span class="date-posted" title=move || comment.with(|x| format!("Edited {}", x.updated_at))>
+                        " (edited)"
+                    </span>
+                    <Show
+                        when=move || can_moderate || user_id.get() == Some(article_author_id)
+                        fallback=|| ()
+                    >
+                        <button
+                            class="btn btn-sm"
+                            on:click=move |_| {
+                                if !show_history.get_untracked() {
+                                    history.dispatch(comment.with_untracked(|x| x.id));
+                                }
+                                show_history.update(|x| *x = !*x);
+                            }
+                        >
+                            {move || if show_history.get() { "Hide history" } else { "History" }}
+                        </button>
+                    </Show>
+                </Show>
                 <Show
                     when=move || {user_id.get().unwrap_or_default() == comment.with(|x| x.user_id)}
                     fallback=|| ()
@@ -357,6 +810,12 @@ fn Comment<T: 'static + Clone, S: 'static>(
                         } else {
                             view! {
                                 <div class="delete-confirm">  // Wrapping div added here
+                                    <button
+                                        class="btn btn-sm"
+                                        on:click=move |_| show_edit_form.update(|x| *x = !*x)
+                                    >
+                                        {move || if show_edit_form.get() { "Cancel edit" } else { "Edit" }}
+                                    </button>
                                     <button 
                                         class="btn btn-sm"
                                         on:click=move |_| show_delete_confirm.set(true)
@@ -368,7 +827,76 @@ fn Comment<T: 'static + Clone, S: 'static>(
                         }
                     }}
                 </Show>
+                <Show
+                    when=move || can_moderate && user_id.get() != Some(comment.with(|x| x.user_id))
+                    fallback=|| ()
+                >
+                    <ActionForm action=hide_c class="inline">
+                        <input type="hidden" name="id" value=move || comment.with(|x| x.id.to_string())/>
+                        <input type="hidden" name="hidden" value=move || (!comment.with(|x| x.hidden)).to_string()/>
+                        <button class="btn btn-sm" type="submit" disabled=move || hide_c.pending().get()>
+                            {move || if comment.with(|x| x.hidden) { "Unhide" } else { "Hide" }}
+                        </button>
+                    </ActionForm>
+                    <ActionForm action=delete_c class="inline">
+                        <input type="hidden" name="id" value=move || comment.with(|x| x.id.to_string())/>
+                        <button class="btn btn-sm" type="submit" disabled=move || is_deleting.get()>
+                            {move || if is_deleting.get() { "Deleting..." } else { "Delete as moderator" }}
+                        </button>
+                    </ActionForm>
+                </Show>
+                <CommentReactions user_id comment />
+                <Show
+                    when=move || user_id.get().is_some_and(|id| id != comment.with(|x| x.user_id))
+                    fallback=|| ()
+                >
+                    <ReportButton
+                        target=crate::models::ReportTarget::Comment
+                        target_id=comment.with_untracked(|x| x.id)
+                    />
+                </Show>
+                <Show when=move || user_id.with(Option::is_some) fallback=|| ()>
+                    <button
+                        class="btn btn-sm"
+                        on:click=move |_| show_reply_form.update(|x| *x = !*x)
+                    >
+                        {move || if show_reply_form.get() { "Cancel" } else { "Reply" }}
+                    </button>
+                </Show>
             </div>
+            </Show>
+            <Show when=move || show_reply_form.get() fallback=|| ()>
+                <ActionForm action=reply class="card comment-form">
+                    <input
+                        name="article_id"
+                        type="hidden"
+                        value=move || comment.with(|x| x.article_id.to_string())
+                    />
+                    <input
+                        name="parent_id"
+                        type="hidden"
+                        value=move || comment.with(|x| x.id.to_string())
+                    />
+                    <div class="card-block">
+                        <textarea
+                            name="body"
+                            class="form-control"
+                            placeholder="Write a reply..."
+                            rows="2"
+                            disabled=move || is_replying.get()
+                        ></textarea>
+                    </div>
+                    <div class="card-footer">
+                        <button
+                            class="btn btn-sm btn-primary"
+                            type="submit"
+                            disabled=move || is_replying.get()
+                        >
+                            {move || if is_replying.get() { "Posting..." } else { "Post Reply" }}
+                        </button>
+                    </div>
+                </ActionForm>
+            </Show>
         </div>
     }
 }
\ No newline at end of file
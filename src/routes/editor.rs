@@ -15,11 +15,32 @@ struct ArticleUpdate {
     description: String,
     body: String,
     tag_list: Vec<String>,
+    cover_image: Option<String>,
+    series: SeriesChoice,
+    series_position: Option<i32>,
+    organization_id: Option<uuid::Uuid>,
+}
+
+#[derive(Debug)]
+enum SeriesChoice {
+    None,
+    Existing(uuid::Uuid),
+    New(String),
 }
 
 const TITLE_MIN_LENGTH: usize = 4;
 const DESCRIPTION_MIN_LENGTH: usize = 4;
 const BODY_MIN_LENGTH: usize = 10;
+/// Value of the series select that creates a new one.
+const NEW_SERIES: &str = "new";
+
+/// The series fields of the form as sent.
+#[derive(Debug)]
+struct SeriesForm {
+    id: String,
+    new_title: String,
+    position: String,
+}
 
 #[cfg(feature = "ssr")]
 #[tracing::instrument]
@@ -28,6 +49,9 @@ fn validate_article(
     description: String,
     body: String,
     tag_list: String,
+    cover_image: String,
+    series: SeriesForm,
+    organization_id: String,
 ) -> Result<ArticleUpdate, String> {
     if title.len() < TITLE_MIN_LENGTH {
         return Err(format!("Title must be at least {TITLE_MIN_LENGTH} characters"));
@@ -48,37 +72,86 @@ fn validate_article(
         .map(String::from)
         .collect();
 
+    let cover_image = match cover_image.trim() {
+        "" => None,
+        x if crate::storage::is_image_url(x) => Some(x.to_string()),
+        _ => return Err("The cover image must be an uploaded image or a link".into()),
+    };
+
+    let series_position = match series.position.trim() {
+        "" => None,
+        x => Some(
+            x.parse::<i32>()
+                .ok()
+                .filter(|x| *x > 0)
+                .ok_or("The part number must be a positive number")?,
+        ),
+    };
+    let series = match (series.id.as_str(), series.new_title.trim()) {
+        ("", _) => SeriesChoice::None,
+        (NEW_SERIES, "") => return Err("Give a title to the new series".into()),
+        (NEW_SERIES, title) => SeriesChoice::New(title.to_string()),
+        (id, _) => SeriesChoice::Existing(
+            uuid::Uuid::parse_str(id).map_err(|_| "Unknown series".to_string())?,
+        ),
+    };
+
+    let organization_id = match organization_id.as_str() {
+        "" => None,
+        x => Some(uuid::Uuid::parse_str(x).map_err(|_| "Unknown organization".to_string())?),
+    };
+
     Ok(ArticleUpdate {
         title,
         description,
         body,
         tag_list,
+        cover_image,
+        series,
+        series_position,
+        organization_id,
     })
 }
 
 #[cfg(feature = "ssr")]
 #[tracing::instrument]
 async fn update_article(
-    author_id: uuid::Uuid,
+    user_id: uuid::Uuid,
     slug: String,
     article: ArticleUpdate,
 ) -> Result<String, sqlx::Error> {
     let mut transaction = crate::database::get_db().begin().await?;
+    let reading_time = crate::models::Article::reading_time_of(&article.body);
 
-    let (rows_affected, new_slug) = if !slug.is_empty() {
+    // Series belong to the owner, whoever of the co-authors is editing
+    let (owner_id, new_slug) = if !slug.is_empty() {
         // Update existing article
-        let rows = sqlx::query!(
-            "UPDATE Articles SET title=$1, description=$2, body=$3 WHERE slug=$4 and author_id=$5",
+        let owner_id = sqlx::query!(
+            "
+            UPDATE Articles SET title=$1, description=$2, body=$3, cover_image=$6, reading_time=$7, updated_at=NOW()
+            WHERE slug=$4 AND (author_id=$5 OR EXISTS(
+                SELECT 1 FROM ArticleAuthors WHERE article_id=Articles.id AND user_id=$5 AND accepted_at IS NOT NULL
+            ) OR EXISTS(
+                SELECT 1 FROM OrganizationMembers
+                WHERE organization_id=Articles.organization_id AND user_id=$5 AND role IN ('owner', 'editor')
+            ))
+            RETURNING author_id",
             article.title,
             article.description,
             article.body,
             slug,
-            author_id,
+            user_id,
+            article.cover_image,
+            reading_time,
         )
-        .execute(transaction.as_mut())
+        .fetch_optional(transaction.as_mut())
         .await?
-        .rows_affected();
-        (rows, slug)
+        .map(|x| x.author_id);
+        let Some(owner_id) = owner_id else {
+            tracing::error!("{user_id} cannot edit {slug}");
+            return Err(sqlx::Error::RowNotFound);
+        };
+        (owner_id, slug)
     } else {
         // Create new article
         let new_slug = article
@@ -92,46 +165,82 @@ async fn update_article(
             .collect::<String>();
 
         let rows = sqlx::query!(
-            "INSERT INTO Articles(slug, title, description, body, tags, author_id) 
-             VALUES ($1, $2, $3, $4, $5, $6)",
+            "INSERT INTO Articles(slug, title, description, body, tags, author_id, cover_image, reading_time) 
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
             new_slug,
             article.title,
             article.description,
             article.body,
             &article.tag_list,
-            author_id
+            user_id,
+            article.cover_image,
+            reading_time,
         )
         .execute(transaction.as_mut())
         .await?
         .rows_affected();
-        (rows, new_slug)
+        if rows != 1 {
+            tracing::error!("Expected 1 row affected, got {}", rows);
+            return Err(sqlx::Error::RowNotFound);
+        }
+        crate::models::CoAuthor::add_owner(transaction.as_mut(), &new_slug, user_id).await?;
+        (user_id, new_slug)
     };
 
-    if rows_affected != 1 {
-        tracing::error!("Expected 1 row affected, got {}", rows_affected);
-        return Err(sqlx::Error::RowNotFound);
-    }
+    let series_id = match article.series {
+        SeriesChoice::None => None,
+        SeriesChoice::Existing(id) => Some(id),
+        SeriesChoice::New(title) => {
+            Some(crate::models::Series::create(transaction.as_mut(), owner_id, &title).await?)
+        }
+    };
+    crate::models::Series::place(
+        transaction.as_mut(),
+        &new_slug,
+        owner_id,
+        series_id,
+        article.series_position,
+    )
+    .await?;
+    crate::models::Organization::assign(
+        transaction.as_mut(),
+        &new_slug,
+        user_id,
+        article.organization_id,
+    )
+    .await?;
 
     transaction.commit().await?;
     Ok(new_slug)
 }
 
 #[server(EditorAction, "/api")]
+#[allow(clippy::too_many_arguments)]
 #[tracing::instrument]
 pub async fn editor_action(
     title: String,
     description: String,
     body: String,
     tag_list: String,
+    cover_image: String,
+    series_id: String,
+    new_series: String,
+    series_position: String,
+    organization_id: String,
     slug: String,
 ) -> Result<EditorResponse, ServerFnError> {
-    let Some(author_id) = crate::auth::get_user_id() else {
+    let Some(user_id) = crate::auth::get_user_id() else {
         leptos_axum::redirect("/login");
         return Ok(EditorResponse::ValidationError("Authentication required".to_string()));
     };
 
-    match validate_article(title, description, body, tag_list) {
-        Ok(article) => match update_article(author_id, slug, article).await {
+    let series = SeriesForm {
+        id: series_id,
+        new_title: new_series,
+        position: series_position,
+    };
+    match validate_article(title, description, body, tag_list, cover_image, series, organization_id) {
+        Ok(article) => match update_article(user_id, slug, article).await {
             Ok(new_slug) => {
                 leptos_axum::redirect(&format!("/article/{new_slug}"));
                 Ok(EditorResponse::Successful(new_slug))
@@ -145,6 +254,85 @@ pub async fn editor_action(
     }
 }
 
+#[server(GetMySeriesAction, "/api", "GetJson")]
+#[tracing::instrument]
+pub async fn my_series(slug: String) -> Result<Vec<crate::models::Series>, ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError("Authentication required".into()));
+    };
+    let series = if slug.is_empty() {
+        crate::models::Series::for_user(user_id).await
+    } else {
+        crate::models::Series::for_owner_of(&slug).await
+    };
+    series.map_err(|x| {
+        tracing::error!("problem while fetching series: {x:?}");
+        ServerFnError::ServerError("Problem while fetching your series".into())
+    })
+}
+
+#[server(GetMyOrganizationsAction, "/api", "GetJson")]
+#[tracing::instrument]
+pub async fn my_organizations() -> Result<Vec<crate::models::Organization>, ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError("Authentication required".into()));
+    };
+    crate::models::Organization::for_member(user_id).await.map_err(|x| {
+        tracing::error!("problem while fetching organizations: {x:?}");
+        ServerFnError::ServerError("Problem while fetching your organizations".into())
+    })
+}
+
+#[server(GetCoAuthorsAction, "/api", "GetJson")]
+#[tracing::instrument]
+pub async fn get_co_authors(slug: String) -> Result<Vec<crate::models::CoAuthor>, ServerFnError> {
+    crate::models::CoAuthor::for_article(&slug).await.map_err(|x| {
+        tracing::error!("problem while fetching the co-authors of {slug}: {x:?}");
+        ServerFnError::ServerError("Problem while fetching the co-authors".into())
+    })
+}
+
+#[server(InviteCoAuthorAction, "/api")]
+#[tracing::instrument]
+pub async fn invite_co_author(slug: String, username: String) -> Result<(), ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError("You must be logged in".into()));
+    };
+
+    let invited = crate::models::CoAuthor::invite(&slug, user_id, username.trim())
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while inviting {username} to {slug}: {x:?}");
+            ServerFnError::ServerError("Problem while sending the invitation".into())
+        })?;
+    let Some((article_id, invitee_id)) = invited else {
+        return Err(ServerFnError::ServerError(format!(
+            "Could not invite {username}, check the username"
+        )));
+    };
+
+    if let Err(x) = crate::models::Notification::for_invitee(invitee_id, user_id, article_id).await {
+        tracing::error!("could not notify {invitee_id} of the invitation: {x:?}");
+    }
+    Ok(())
+}
+
+#[server(RemoveCoAuthorAction, "/api")]
+#[tracing::instrument]
+pub async fn remove_co_author(slug: String, user_id: uuid::Uuid) -> Result<(), ServerFnError> {
+    let Some(logged_user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError("You must be logged in".into()));
+    };
+
+    crate::models::CoAuthor::remove(&slug, logged_user_id, user_id)
+        .await
+        .map(|_| ())
+        .map_err(|x| {
+            tracing::error!("problem while removing {user_id} from {slug}: {x:?}");
+            ServerFnError::ServerError("Problem while removing the co-author".into())
+        })
+}
+
 #[component]
 pub fn Editor() -> impl IntoView {
     let editor_server_action = create_server_action::<EditorAction>();
@@ -233,6 +421,12 @@ pub fn Editor() -> impl IntoView {
                                                         prop:value=article.article.body.unwrap_or_default()
                                                     ></textarea>
                                                 </fieldset>
+                                                <CoverImageField initial=article.article.cover_image.unwrap_or_default()/>
+                                                <SeriesField
+                                                    current=article.series.clone()
+                                                    slug=article.article.slug.clone()
+                                                />
+                                                <OrganizationField current=article.article.organization.clone()/>
                                                 <fieldset class="form-group">
                                                     <input 
                                                         name="tag_list"
@@ -259,9 +453,255 @@ pub fn Editor() -> impl IntoView {
                                 </ErrorBoundary>
                             </Suspense>
                         </ActionForm>
+                        <Suspense fallback=|| ()>
+                            {move || article_res.get().and_then(Result::ok).and_then(|result| {
+                                let logged_user_id = result.logged_user.and_then(|x| x.id())?;
+                                (!result.article.slug.is_empty()).then(|| view! {
+                                    <CoAuthorsSection
+                                        slug=result.article.slug
+                                        owner_id=result.article.author.user_id
+                                        logged_user_id
+                                    />
+                                })
+                            })}
+                        </Suspense>
                     </div>
                 </div>
             </div>
         </div>
     }
-}
\ No newline at end of file
+}
+
+/// Either a link or an uploaded image, shown as a preview once set.
+#[component]
+fn CoverImageField(initial: String) -> impl IntoView {
+    let cover_image = create_rw_signal(initial);
+    let upload_error = create_rw_signal(None::<String>);
+
+    let on_file = move |ev: leptos::ev::Event| {
+        #[cfg(feature = "hydrate")]
+        {
+            use wasm_bindgen::JsCast;
+            let file = ev
+                .target()
+                .and_then(|x| x.dyn_into::<web_sys::HtmlInputElement>().ok())
+                .and_then(|x| x.files())
+                .and_then(|x| x.get(0));
+            if let Some(file) = file {
+                spawn_local(async move {
+                    match crate::components::upload_image(file, crate::models::UploadKind::Cover).await {
+                        Ok(url) => {
+                            upload_error.set(None);
+                            cover_image.set(url);
+                        }
+                        Err(x) => upload_error.set(Some(x)),
+                    }
+                });
+            }
+        }
+        #[cfg(not(feature = "hydrate"))]
+        let _ = ev;
+    };
+
+    view! {
+        <fieldset class="form-group">
+            <input
+                name="cover_image"
+                type="text"
+                class="form-control"
+                placeholder="Cover image URL"
+                prop:value=move || cover_image.get()
+                on:input=move |ev| cover_image.set(event_target_value(&ev))
+            />
+            <input
+                type="file"
+                accept="image/png,image/jpeg,image/gif,image/webp"
+                on:change=on_file
+            />
+            {move || upload_error.get().map(|x| view! { <p class="error-messages">{x}</p> })}
+            <Show when=move || cover_image.with(|x| !x.is_empty()) fallback=|| ()>
+                <img class="cover-image-preview" src=move || cover_image.get() alt="Cover image preview"/>
+            </Show>
+        </fieldset>
+    }
+}
+
+/// Picks one of the author's series, or starts a new one, and the part number.
+#[component]
+fn SeriesField(current: Option<crate::models::Series>, slug: String) -> impl IntoView {
+    let selected = create_rw_signal(
+        current
+            .as_ref()
+            .map(|x| x.id.to_string())
+            .unwrap_or_default(),
+    );
+    let position = current
+        .as_ref()
+        .and_then(|x| x.position_of(&slug))
+        .map(|x| x.to_string())
+        .unwrap_or_default();
+    let series = create_resource(move || slug.clone(), my_series);
+
+    view! {
+        <fieldset class="form-group">
+            <div class="input-group">
+                <select
+                    name="series_id"
+                    class="form-control"
+                    on:change=move |ev| selected.set(event_target_value(&ev))
+                >
+                    <option value="" selected=move || selected.with(String::is_empty)>
+                        "Not part of a series"
+                    </option>
+                    <Suspense fallback=|| ()>
+                        {move || series.get().and_then(Result::ok).map(|series| {
+                            series
+                                .into_iter()
+                                .map(|x| {
+                                    let id = x.id.to_string();
+                                    let is_selected = {
+                                        let id = id.clone();
+                                        move || selected.with(|x| *x == id)
+                                    };
+                                    view! {
+                                        <option value=id selected=is_selected>{x.title}</option>
+                                    }
+                                })
+                                .collect_view()
+                        })}
+                    </Suspense>
+                    <option value=NEW_SERIES selected=move || selected.with(|x| x == NEW_SERIES)>
+                        "New series..."
+                    </option>
+                </select>
+                <input
+                    name="series_position"
+                    type="number"
+                    min="1"
+                    class="form-control"
+                    placeholder="Part number"
+                    value=position
+                />
+            </div>
+            <input
+                name="new_series"
+                type="text"
+                class="form-control"
+                class:hidden=move || selected.with(|x| x != NEW_SERIES)
+                placeholder="Title of the new series"
+            />
+        </fieldset>
+    }
+}
+
+/// Publishes as oneself or on behalf of one of the user's organizations. The current
+/// organization stays listed even for co-authors who are not members of it.
+#[component]
+fn OrganizationField(current: Option<crate::models::OrganizationPreview>) -> impl IntoView {
+    let current_id = current.as_ref().map(|x| x.id);
+    let current = store_value(current);
+    let organizations = create_resource(|| (), |_| my_organizations());
+
+    view! {
+        <fieldset class="form-group">
+            <select name="organization_id" class="form-control">
+                <option value="" selected=current_id.is_none()>"Publish as yourself"</option>
+                <Suspense fallback=move || current.get_value().map(|x| view! {
+                    <option value=x.id.to_string() selected=true>{format!("On behalf of {}", x.name)}</option>
+                })>
+                    {move || organizations.get().and_then(Result::ok).map(|organizations| {
+                        let listed = organizations.iter().any(|x| Some(x.id) == current_id);
+                        let current = current.get_value().filter(|_| !listed).map(|x| view! {
+                            <option value=x.id.to_string() selected=true>{format!("On behalf of {}", x.name)}</option>
+                        });
+                        view! {
+                            {current}
+                            {organizations
+                                .into_iter()
+                                .map(|x| view! {
+                                    <option value=x.id.to_string() selected=Some(x.id) == current_id>
+                                        {format!("On behalf of {}", x.name)}
+                                    </option>
+                                })
+                                .collect_view()}
+                        }
+                    })}
+                </Suspense>
+            </select>
+        </fieldset>
+    }
+}
+
+/// Who else can edit the article. The owner invites by username and removes people,
+/// while co-authors can only leave.
+#[component]
+fn CoAuthorsSection(slug: String, owner_id: uuid::Uuid, logged_user_id: uuid::Uuid) -> impl IntoView {
+    let invite_action = create_server_action::<InviteCoAuthorAction>();
+    let remove_action = create_server_action::<RemoveCoAuthorAction>();
+    let slug = store_value(slug);
+    let co_authors = create_resource(
+        move || (invite_action.version().get(), remove_action.version().get()),
+        move |_| get_co_authors(slug.get_value()),
+    );
+    let is_owner = owner_id == logged_user_id;
+    let invite_result = invite_action.value();
+
+    view! {
+        <div class="co-authors">
+            <h4>"Co-authors"</h4>
+            <Suspense fallback=move || view! { <p>"Loading co-authors"</p> }>
+                {
+                    move || co_authors.get().and_then(Result::ok).map(|co_authors| {
+                        co_authors
+                            .into_iter()
+                            .map(|x| {
+                                let can_remove = x.role == crate::models::AuthorRole::Editor
+                                    && (is_owner || x.user.user_id == logged_user_id);
+                                let label = if x.user.user_id == logged_user_id { "Leave" } else { "Remove" };
+                                view! {
+                                    <div class="co-author">
+                                        <A href=format!("/profile/{}", x.user.user_id)>{x.user.name}</A>
+                                        <span class="text-muted">
+                                            {match (x.role, x.pending) {
+                                                (crate::models::AuthorRole::Owner, _) => " · owner",
+                                                (_, true) => " · invited",
+                                                (_, false) => " · editor",
+                                            }}
+                                        </span>
+                                        {can_remove.then(|| view! {
+                                            <ActionForm action=remove_action class="inline">
+                                                <input type="hidden" name="slug" value=slug.get_value()/>
+                                                <input type="hidden" name="user_id" value=x.user.user_id.to_string()/>
+                                                <button type="submit" class="btn btn-sm btn-outline-danger">
+                                                    {label}
+                                                </button>
+                                            </ActionForm>
+                                        })}
+                                    </div>
+                                }
+                            })
+                            .collect_view()
+                    })
+                }
+            </Suspense>
+            <Show when=move || is_owner fallback=|| ()>
+                {move || invite_result.get().and_then(Result::err).map(|x| view! {
+                    <p class="error-messages">{x.to_string()}</p>
+                })}
+                <ActionForm action=invite_action>
+                    <input type="hidden" name="slug" value=slug.get_value()/>
+                    <div class="input-group">
+                        <input
+                            name="username"
+                            type="text"
+                            class="form-control"
+                            placeholder="Username to invite"
+                            required=true
+                        />
+                        <button type="submit" class="btn btn-outline-primary">"Invite"</button>
+                    </div>
+                </ActionForm>
+            </Show>
+        </div>
+    }
+}
@@ -1,8 +1,18 @@
-use crate::components::ArticlePreviewList;
+use crate::components::{replace_url, ArticlePreview, ArticlePreviewList, ScrollSentinel, Seo};
 use leptos::*;
 use leptos_meta::*;
 use leptos_router::*;
 
+/// Pages rendered at once when restoring an infinite feed from the URL, the last
+/// one being the page in the URL.
+const INFINITE_RESTORE_PAGES: u32 = 3;
+
+/// Pages of an infinite feed rendered by its first render.
+fn restored_pages(pagination: &crate::models::Pagination) -> std::ops::RangeInclusive<u32> {
+    let page = pagination.get_page();
+    page.saturating_sub(INFINITE_RESTORE_PAGES - 1)..=page
+}
+
 #[server(HomeAction, "/api", "GetJson")]
 async fn home_articles(
     page: u32,
@@ -57,18 +67,58 @@ pub fn HomePage(user_id: crate::auth::UserIdSignal) -> impl IntoView {
         move || pagination.get().unwrap_or_default(),
         move |pagination| async move {
             tracing::debug!("making another request: {pagination:?}");
-            home_articles(
-                pagination.get_page(),
-                pagination.get_amount(),
-                pagination.get_tag().to_string(),
-                pagination.get_my_feed(),
-            )
-            .await
+            if pagination.get_infinite() {
+                // Render the pages leading to the one in the URL, so going back
+                // to the feed lands close to the same content.
+                let mut articles = Vec::new();
+                for page in restored_pages(&pagination) {
+                    let loaded = home_articles(
+                        page,
+                        pagination.get_amount(),
+                        pagination.get_tag().to_string(),
+                        pagination.get_my_feed(),
+                    )
+                    .await?;
+                    let last = loaded.len() < pagination.get_amount() as usize;
+                    articles.extend(loaded);
+                    if last {
+                        break;
+                    }
+                }
+                Ok(articles)
+            } else {
+                home_articles(
+                    pagination.get_page(),
+                    pagination.get_amount(),
+                    pagination.get_tag().to_string(),
+                    pagination.get_my_feed(),
+                )
+                .await
+            }
         },
     );
 
+    let infinite = move || {
+        pagination.with(|x| {
+            x.as_ref()
+                .map(crate::models::Pagination::get_infinite)
+                .unwrap_or_default()
+        })
+    };
+
+    let has_earlier = move || {
+        infinite() && pagination.with(|x| x.as_ref().is_ok_and(|x| *restored_pages(x).start() > 0))
+    };
+
     view! {
         <Title text="Home"/>
+        <Seo
+            title=crate::components::SITE_NAME
+            description=crate::components::SITE_DESCRIPTION
+            path="/"
+        />
+        <Link rel="alternate" type_="application/atom+xml" title="thedeveloper" href="/feed.xml"/>
+        <Link rel="alternate" type_="application/rss+xml" title="thedeveloper" href="/rss.xml"/>
 
         <div class="home-page">
             <div class="banner">
@@ -142,12 +192,34 @@ pub fn HomePage(user_id: crate::auth::UserIdSignal) -> impl IntoView {
                                         <a href=move || pagination.get().unwrap_or_default().reset_page().set_amount(1).to_string() class="btn btn-primary">"1"</a>
                                         <a href=move || pagination.get().unwrap_or_default().reset_page().set_amount(20).to_string() class="btn btn-primary">"20"</a>
                                         <a href=move || pagination.get().unwrap_or_default().reset_page().set_amount(50).to_string() class="btn btn-primary">"50"</a>
+                                        " | "
+                                        <a href=move || pagination.get().unwrap_or_default().reset_page().set_infinite(!infinite()).to_string() class="btn btn-outline-primary">
+                                            {move || if infinite() { "Paged" } else { "Infinite scroll" }}
+                                        </a>
                                     </div>
                                 </li>
                             </ul>
                         </div>
 
+                        <Show
+                            when=has_earlier
+                            fallback=|| ()
+                        >
+                            <a
+                                class="btn btn-outline-primary"
+                                href=move || {
+                                    let pagination = pagination.get().unwrap_or_default();
+                                    let previous = restored_pages(&pagination).start().saturating_sub(1);
+                                    pagination.set_page(previous).to_string()
+                                }
+                            >
+                                "Show earlier articles"
+                            </a>
+                        </Show>
                         <ArticlePreviewList articles user_id/>
+                        <Show when=infinite fallback=|| ()>
+                            <InfiniteFeed user_id pagination articles/>
+                        </Show>
                     </div>
 
                     <div class="col-md-3">
@@ -157,7 +229,7 @@ pub fn HomePage(user_id: crate::auth::UserIdSignal) -> impl IntoView {
                         </div>
                     </div>
 
-                    <ul class="pagination">
+                    <ul class="pagination" class:hidden=infinite>
                         <Show
                             when=move || {pagination.with(|x| x.as_ref().map(crate::models::Pagination::get_page).unwrap_or_default()) > 0}
                             fallback=|| ()
@@ -191,6 +263,163 @@ pub fn HomePage(user_id: crate::auth::UserIdSignal) -> impl IntoView {
     }
 }
 
+/// Signals backing the pages appended below the first render of an infinite feed.
+#[derive(Clone, Copy)]
+struct InfiniteFeedState {
+    pagination: Memo<Result<crate::models::Pagination, ParamsError>>,
+    appended: RwSignal<Vec<crate::models::Article>>,
+    next_page: RwSignal<u32>,
+    prefetched: RwSignal<Option<Vec<crate::models::Article>>>,
+    prefetching: RwSignal<bool>,
+    waiting: RwSignal<bool>,
+    exhausted: RwSignal<bool>,
+    /// Bumped on every reset, so pages asked for before it are dropped on arrival.
+    generation: StoredValue<u64>,
+}
+
+impl InfiniteFeedState {
+    fn new(pagination: Memo<Result<crate::models::Pagination, ParamsError>>) -> Self {
+        Self {
+            pagination,
+            appended: create_rw_signal(Vec::new()),
+            next_page: create_rw_signal(0),
+            prefetched: create_rw_signal(None),
+            prefetching: create_rw_signal(false),
+            waiting: create_rw_signal(false),
+            exhausted: create_rw_signal(false),
+            generation: store_value(0),
+        }
+    }
+
+    fn current(self) -> crate::models::Pagination {
+        self.pagination.get_untracked().unwrap_or_default()
+    }
+
+    /// Starts over after the first render (re)loaded `loaded` articles.
+    fn reset(self, loaded: usize) {
+        let pagination = self.current();
+        let expected = pagination.get_amount() as usize * restored_pages(&pagination).count();
+        self.generation.update_value(|x| *x += 1);
+        self.appended.set(Vec::new());
+        self.prefetched.set(None);
+        self.prefetching.set(false);
+        self.waiting.set(false);
+        self.next_page.set(pagination.get_page().saturating_add(1));
+        self.exhausted.set(loaded < expected);
+        self.prefetch();
+    }
+
+    /// Fetches the next page in the background, unless it is already there or on its way.
+    fn prefetch(self) {
+        if self.exhausted.get_untracked()
+            || self.prefetching.get_untracked()
+            || self.prefetched.with_untracked(Option::is_some)
+        {
+            return;
+        }
+        let pagination = self.current();
+        let page = self.next_page.get_untracked();
+        let generation = self.generation.get_value();
+        self.prefetching.set(true);
+        spawn_local(async move {
+            let articles = home_articles(
+                page,
+                pagination.get_amount(),
+                pagination.get_tag().to_string(),
+                pagination.get_my_feed(),
+            )
+            .await;
+            if self.generation.get_value() != generation {
+                return;
+            }
+            match articles {
+                Ok(articles) if self.waiting.get_untracked() => {
+                    self.waiting.set(false);
+                    self.prefetching.set(false);
+                    self.append(articles);
+                }
+                Ok(articles) => {
+                    self.prefetched.set(Some(articles));
+                    self.prefetching.set(false);
+                }
+                Err(err) => {
+                    tracing::error!("problem while prefetching the next page: {err:?}");
+                    self.waiting.set(false);
+                    self.prefetching.set(false);
+                }
+            }
+        });
+    }
+
+    fn append(self, articles: Vec<crate::models::Article>) {
+        let pagination = self.current();
+        let page = self.next_page.get_untracked();
+        if articles.len() < pagination.get_amount() as usize {
+            self.exhausted.set(true);
+        }
+        if !articles.is_empty() {
+            self.appended.update(|x| x.extend(articles));
+            replace_url(&pagination.set_page(page).to_string());
+        }
+        self.next_page.set(page.saturating_add(1));
+        self.prefetch();
+    }
+
+    /// Called when the reader reaches the end of the list.
+    fn load_more(self) {
+        match self.prefetched.try_update(Option::take).flatten() {
+            Some(articles) => self.append(articles),
+            None => {
+                self.waiting.set(true);
+                self.prefetch();
+            }
+        }
+    }
+}
+
+#[component]
+fn InfiniteFeed(
+    user_id: crate::auth::UserIdSignal,
+    pagination: Memo<Result<crate::models::Pagination, ParamsError>>,
+    articles: Resource<crate::models::Pagination, Result<Vec<crate::models::Article>, ServerFnError>>,
+) -> impl IntoView {
+    let state = InfiniteFeedState::new(pagination);
+
+    create_effect(move |_| {
+        if let Some(Ok(loaded)) = articles.get() {
+            state.reset(loaded.len());
+        }
+    });
+
+    view! {
+        <For
+            each=move || state.appended.get()
+            key=|article| article.slug.clone()
+            children=move |article| {
+                let article = create_rw_signal(article);
+                view! { <ArticlePreview article user_id /> }
+            }
+        />
+        <ScrollSentinel
+            on_visible=Callback::new(move |()| state.load_more())
+            active=Signal::derive(move || !state.exhausted.get() && !state.waiting.get())
+        />
+        <div class="article-preview">
+            <p class="text-xs-center">
+                {move || {
+                    if state.waiting.get() {
+                        "Loading more articles..."
+                    } else if state.exhausted.get() {
+                        "You're all caught up."
+                    } else {
+                        ""
+                    }
+                }}
+            </p>
+        </div>
+    }
+}
+
 #[component]
 fn TagList(pagination: Memo<Result<crate::models::Pagination, ParamsError>>) -> impl IntoView {
     let tag_list = create_resource(|| (), |_| async { get_tags().await });
@@ -1,14 +1,24 @@
+mod admin;
 mod article;
 mod editor;
 mod home;
 mod login;
+mod moderation;
+mod notifications;
+mod organization;
 mod profile;
+mod reading_list;
 mod settings;
 mod signup;
+pub use admin::*;
 pub use article::*;
 pub use editor::*;
 pub use home::*;
 pub use login::*;
+pub use moderation::*;
+pub use notifications::*;
+pub use organization::*;
 pub use profile::*;
+pub use reading_list::*;
 pub use settings::*;
 pub use signup::*;
@@ -0,0 +1,143 @@
+use leptos::*;
+use leptos_meta::*;
+use leptos_router::*;
+
+#[server(GetReportQueueAction, "/api", "GetJson")]
+#[tracing::instrument]
+pub async fn get_report_queue() -> Result<Vec<crate::models::ReportedItem>, ServerFnError> {
+    crate::auth::require_permission(crate::models::Permission::ModerateContent).await?;
+
+    crate::models::Report::queue().await.map_err(|x| {
+        tracing::error!("problem while fetching the moderation queue: {x:?}");
+        ServerFnError::ServerError("Could not retrieve the moderation queue, try again later".into())
+    })
+}
+
+#[server(DecideReportsAction, "/api")]
+#[tracing::instrument]
+pub async fn decide_reports(
+    target: crate::models::ReportTarget,
+    target_id: uuid::Uuid,
+    decision: crate::models::ReportDecision,
+) -> Result<(), ServerFnError> {
+    use crate::models::{Permission, ReportDecision, ReportTarget};
+
+    // Taking action on a profile suspends the user, which only admins do
+    let permission = if target == ReportTarget::User && decision == ReportDecision::TakeAction {
+        Permission::Administer
+    } else {
+        Permission::ModerateContent
+    };
+    let moderator_id = crate::auth::require_permission(permission).await?;
+
+    crate::models::Report::decide(target, target_id, moderator_id, decision)
+        .await
+        .map(|_| ())
+        .map_err(|x| {
+            tracing::error!("problem while closing the reports on {target_id}: {x:?}");
+            ServerFnError::ServerError("Could not close the reports, try again later".into())
+        })
+}
+
+#[tracing::instrument]
+#[component]
+pub fn Moderation() -> impl IntoView {
+    let decide = create_server_action::<DecideReportsAction>();
+    let queue = create_resource(move || decide.version().get(), |_| get_report_queue());
+
+    view! {
+        <Title text="Moderation"/>
+        <div class="moderation-page">
+            <div class="container page">
+                <h1>"Moderation queue"</h1>
+                {move || decide.value().get().and_then(Result::err).map(|x| view! {
+                    <p class="error-messages">{x.to_string()}</p>
+                })}
+                <Suspense fallback=|| view! { <p>"Loading reports..."</p> }>
+                    <ErrorBoundary fallback=|_| view! {
+                        <p class="error-messages text-xs-center">
+                            "Only moderators can see this page, or something went wrong. Try again later."
+                        </p>
+                    }>
+                        {move || queue.get().map(|x| x.map(|items| {
+                            if items.is_empty() {
+                                return view! { <p>"Nothing to review, all reports are closed."</p> }.into_view();
+                            }
+                            items
+                                .into_iter()
+                                .map(|item| view! { <ReportedItemCard item decide/> })
+                                .collect_view()
+                        }))}
+                    </ErrorBoundary>
+                </Suspense>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn ReportedItemCard(
+    item: crate::models::ReportedItem,
+    decide: Action<DecideReportsAction, Result<(), ServerFnError>>,
+) -> impl IntoView {
+    use crate::models::{ReportDecision, ReportTarget};
+
+    let summary = item
+        .summary
+        .map(|x| x.chars().take(200).collect::<String>())
+        .unwrap_or_else(|| format!("This {} no longer exists", item.target.as_str()));
+    let reasons = item
+        .reasons
+        .iter()
+        .map(|x| x.label())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let hidden_label = if item.target == ReportTarget::User {
+        "Suspended"
+    } else {
+        "Hidden"
+    };
+    let take_action_label = if item.target == ReportTarget::User {
+        "Suspend user"
+    } else {
+        "Hide"
+    };
+    let target = item.target.as_str();
+    let target_id = item.target_id;
+    let form = move |decision: ReportDecision, label: &'static str, class: &'static str| {
+        view! {
+            <ActionForm action=decide class="inline">
+                <input type="hidden" name="target" value=target/>
+                <input type="hidden" name="target_id" value=target_id.to_string()/>
+                <input type="hidden" name="decision" value=decision.as_str()/>
+                <button type="submit" class=format!("btn btn-sm {class}") disabled=decide.pending()>
+                    {label}
+                </button>
+            </ActionForm>
+        }
+    };
+
+    view! {
+        <div class="card reported-item">
+            <div class="card-block">
+                <p class="text-muted">
+                    {item.target.label()}
+                    {format!(" · {} reports since {} · {reasons}", item.reports, item.first_reported_at)}
+                    {item.hidden.then(|| view! { <span class="tag-default tag-pill">{hidden_label}</span> })}
+                </p>
+                {match item.href {
+                    Some(href) => view! { <A href>{summary}</A> }.into_view(),
+                    None => summary.into_view(),
+                }}
+                <ul>
+                    {item.details.into_iter().map(|x| view! { <li>{x}</li> }).collect_view()}
+                </ul>
+            </div>
+            <div class="card-footer">
+                {(!item.hidden).then(|| form(ReportDecision::TakeAction, take_action_label, "btn-danger"))}
+                {form(ReportDecision::Resolve, "Resolve", "btn-outline-primary")}
+                {form(ReportDecision::Dismiss, "Dismiss", "btn-outline-secondary")}
+            </div>
+        </div>
+    }
+}
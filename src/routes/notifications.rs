@@ -0,0 +1,153 @@
+use leptos::*;
+use leptos_meta::*;
+use leptos_router::*;
+
+#[server(UnreadNotificationsAction, "/api", "GetJson")]
+#[tracing::instrument]
+pub async fn unread_notifications() -> Result<i64, ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Ok(0);
+    };
+
+    crate::models::Notification::unread_count(user_id)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while counting notifications: {x:?}");
+            ServerFnError::ServerError("Problem while counting notifications".into())
+        })
+}
+
+#[server(GetNotificationsAction, "/api", "GetJson")]
+#[tracing::instrument]
+pub async fn get_notifications() -> Result<Vec<crate::models::NotificationGroup>, ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        leptos_axum::redirect("/login");
+        return Err(ServerFnError::ServerError("Authentication required".into()));
+    };
+
+    crate::models::Notification::grouped(user_id)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while fetching notifications: {x:?}");
+            ServerFnError::ServerError("Problem while fetching notifications".into())
+        })
+}
+
+#[server(MarkNotificationsReadAction, "/api")]
+#[tracing::instrument]
+pub async fn mark_notifications_read(
+    kind: crate::models::NotificationKind,
+    article_id: Option<uuid::Uuid>,
+) -> Result<(), ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError("You must be logged in".into()));
+    };
+
+    crate::models::Notification::mark_read(user_id, kind, article_id)
+        .await
+        .map(|_| ())
+        .map_err(|x| {
+            tracing::error!("problem while marking notifications as read: {x:?}");
+            ServerFnError::ServerError("Problem while updating notifications".into())
+        })
+}
+
+#[server(MarkAllNotificationsReadAction, "/api")]
+#[tracing::instrument]
+pub async fn mark_all_notifications_read() -> Result<(), ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError("You must be logged in".into()));
+    };
+
+    crate::models::Notification::mark_all_read(user_id)
+        .await
+        .map(|_| ())
+        .map_err(|x| {
+            tracing::error!("problem while marking notifications as read: {x:?}");
+            ServerFnError::ServerError("Problem while updating notifications".into())
+        })
+}
+
+#[component]
+pub fn Notifications() -> impl IntoView {
+    let mark_read = create_server_action::<MarkNotificationsReadAction>();
+    let mark_all_read = create_server_action::<MarkAllNotificationsReadAction>();
+    let notifications = create_resource(
+        move || (mark_read.version().get(), mark_all_read.version().get()),
+        |_| async { get_notifications().await },
+    );
+
+    view! {
+        <Title text="Notifications"/>
+        <div class="settings-page">
+            <div class="container page">
+                <div class="row">
+                    <div class="col-md-8 offset-md-2 col-xs-12">
+                        <h1 class="text-xs-center">"Notifications"</h1>
+                        <ActionForm action=mark_all_read class="text-xs-right">
+                            <button
+                                type="submit"
+                                class="btn btn-sm btn-outline-secondary"
+                                disabled=move || mark_all_read.pending().get()
+                            >
+                                "Mark all as read"
+                            </button>
+                        </ActionForm>
+
+                        <Suspense fallback=move || view! { <p>"Loading notifications..."</p> }>
+                            <ErrorBoundary fallback=|_| view! {
+                                <p class="error-messages text-xs-center">
+                                    "Error loading notifications. Please try again later."
+                                </p>
+                            }>
+                                {move || notifications.get().map(move |x| x.map(move |groups| {
+                                    if groups.is_empty() {
+                                        return view! { <p>"You're all caught up."</p> }.into_view();
+                                    }
+                                    view! {
+                                        <ul class="notification-list">
+                                            {groups
+                                                .into_iter()
+                                                .map(|group| view! { <NotificationItem group mark_read /> })
+                                                .collect_view()}
+                                        </ul>
+                                    }.into_view()
+                                }))}
+                            </ErrorBoundary>
+                        </Suspense>
+                    </div>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn NotificationItem(
+    group: crate::models::NotificationGroup,
+    mark_read: Action<MarkNotificationsReadAction, Result<(), ServerFnError>>,
+) -> impl IntoView {
+    let unread = group.unread;
+    let kind = group.kind;
+    let article_id = group.article_id;
+    let message = group.message();
+
+    view! {
+        <li class="notification" class:unread=unread>
+            <i class=kind.icon()></i>
+            " "
+            <A href=group.href()>{message}</A>
+            " "
+            <span class="date-posted">{group.latest_at}</span>
+            <Show when=move || unread fallback=|| ()>
+                <ActionForm action=mark_read class="inline">
+                    <input type="hidden" name="kind" value=kind.as_str() />
+                    {article_id.map(|id| view! {
+                        <input type="hidden" name="article_id" value=id.to_string() />
+                    })}
+                    <button type="submit" class="btn btn-sm">"Mark read"</button>
+                </ActionForm>
+            </Show>
+        </li>
+    }
+}
@@ -0,0 +1,333 @@
+use crate::components::{ArticlePreviewList, ButtonFollowOrganization, ResponsiveImage, Seo};
+use leptos::*;
+use leptos_meta::*;
+use leptos_router::*;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+pub struct OrganizationResult {
+    organization: crate::models::Organization,
+    members: Vec<crate::models::OrganizationMember>,
+}
+
+#[server(GetOrganizationAction, "/api", "GetJson")]
+#[tracing::instrument]
+pub async fn get_organization(slug: String) -> Result<OrganizationResult, ServerFnError> {
+    let organization = crate::models::Organization::by_slug(&slug).await.map_err(|x| {
+        tracing::error!("problem while fetching the organization {slug}: {x:?}");
+        ServerFnError::ServerError("Could not retrieve the organization, try again later".into())
+    })?;
+    let members = crate::models::Organization::members(organization.id)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while fetching the members of {slug}: {x:?}");
+            ServerFnError::ServerError("Could not retrieve the organization, try again later".into())
+        })?;
+    Ok(OrganizationResult {
+        organization,
+        members,
+    })
+}
+
+#[server(OrganizationArticlesAction, "/api", "GetJson")]
+#[tracing::instrument]
+pub async fn organization_articles(
+    organization_id: uuid::Uuid,
+) -> Result<Vec<crate::models::Article>, ServerFnError> {
+    crate::models::Article::for_organization(organization_id)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while fetching the articles of {organization_id}: {x:?}");
+            ServerFnError::ServerError("Could not retrieve articles, try again later".into())
+        })
+}
+
+#[server(CreateOrganizationAction, "/api")]
+#[tracing::instrument]
+pub async fn create_organization(
+    slug: String,
+    name: String,
+    description: String,
+) -> Result<(), ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError("You must be logged in".into()));
+    };
+    let slug = slug.trim().to_lowercase();
+    crate::models::Organization::validate_slug(&slug).map_err(ServerFnError::ServerError)?;
+    if name.trim().is_empty() {
+        return Err(ServerFnError::ServerError("The organization needs a name".into()));
+    }
+
+    let slug = crate::models::Organization::create(user_id, &slug, name.trim(), description.trim())
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while creating the organization {slug}: {x:?}");
+            ServerFnError::ServerError(format!("Could not create {slug}, the handle may be taken"))
+        })?;
+    leptos_axum::redirect(&format!("/org/{slug}"));
+    Ok(())
+}
+
+#[server(SetMemberAction, "/api")]
+#[tracing::instrument]
+pub async fn set_member(
+    organization_id: uuid::Uuid,
+    username: String,
+    role: crate::models::OrgRole,
+) -> Result<(), ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError("You must be logged in".into()));
+    };
+
+    let rows = crate::models::Organization::set_member(organization_id, user_id, username.trim(), role)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while adding {username} to {organization_id}: {x:?}");
+            ServerFnError::ServerError("Problem while adding the member".into())
+        })?
+        .rows_affected();
+    if rows == 0 {
+        return Err(ServerFnError::ServerError(format!(
+            "Could not add {username}, check the username"
+        )));
+    }
+    Ok(())
+}
+
+#[server(RemoveMemberAction, "/api")]
+#[tracing::instrument]
+pub async fn remove_member(organization_id: uuid::Uuid, user_id: uuid::Uuid) -> Result<(), ServerFnError> {
+    let Some(logged_user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError("You must be logged in".into()));
+    };
+
+    crate::models::Organization::remove_member(organization_id, logged_user_id, user_id)
+        .await
+        .map(|_| ())
+        .map_err(|x| {
+            tracing::error!("problem while removing {user_id} from {organization_id}: {x:?}");
+            ServerFnError::ServerError("Problem while removing the member".into())
+        })
+}
+
+#[tracing::instrument]
+#[component]
+pub fn OrganizationPage(user_id: crate::auth::UserIdSignal) -> impl IntoView {
+    let params = use_params_map();
+    let set_member = create_server_action::<SetMemberAction>();
+    let remove_member = create_server_action::<RemoveMemberAction>();
+    let organization = create_resource(
+        move || {
+            (
+                params.with(|x| x.get("slug").cloned().unwrap_or_default()),
+                set_member.version().get(),
+                remove_member.version().get(),
+            )
+        },
+        |(slug, _, _)| get_organization(slug),
+    );
+
+    view! {
+        <div class="profile-page">
+            <Suspense fallback=move || view! { <p>"Loading organization"</p> }>
+                <ErrorBoundary fallback=|_| view! {
+                    <p class="error-messages text-xs-center">
+                        "There was a problem while fetching the organization, try again later"
+                    </p>
+                }>
+                    {move || organization.get().map(|x| x.map(|result| view! {
+                        <OrganizationHeader user_id organization=result.organization.clone()/>
+                        <div class="container">
+                            <div class="row">
+                                <div class="col-xs-12 col-md-10 offset-md-1">
+                                    <OrganizationArticles user_id organization_id=result.organization.id/>
+                                    <OrganizationMembers
+                                        user_id
+                                        organization=result.organization
+                                        members=result.members
+                                        set_member
+                                        remove_member
+                                    />
+                                </div>
+                            </div>
+                        </div>
+                    }))}
+                </ErrorBoundary>
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn OrganizationHeader(
+    user_id: crate::auth::UserIdSignal,
+    organization: crate::models::Organization,
+) -> impl IntoView {
+    let path = format!("/org/{}", organization.slug);
+    let description = if organization.description.is_empty() {
+        format!("Articles published by {}", organization.name)
+    } else {
+        organization.description.clone()
+    };
+    let logo = organization.image.clone().unwrap_or_default();
+    let logo_alt = format!("{}'s logo", organization.name);
+
+    view! {
+        <Title text=organization.name.clone()/>
+        <Seo title=organization.name.clone() description=description.clone() path image=organization.image.clone()/>
+        <div class="user-info">
+            <div class="container">
+                <div class="row">
+                    <div class="col-xs-12 col-md-10 offset-md-1">
+                        <ResponsiveImage
+                            src=logo
+                            kind=crate::models::UploadKind::Avatar
+                            sizes="100px"
+                            alt=logo_alt
+                            class="user-img"
+                        />
+                        <h4>{organization.name}</h4>
+                        <p>{description}</p>
+                        <ButtonFollowOrganization
+                            logged_user_id=user_id
+                            organization_id=organization.id
+                            following=organization.following
+                        />
+                    </div>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn OrganizationArticles(user_id: crate::auth::UserIdSignal, organization_id: uuid::Uuid) -> impl IntoView {
+    let articles = create_resource(move || organization_id, organization_articles);
+
+    view! {
+        <div class="articles-toggle">
+            <ul class="nav nav-pills outline-active">
+                <li class="nav-item">
+                    <span class="nav-link active">"Articles"</span>
+                </li>
+            </ul>
+        </div>
+        <ArticlePreviewList user_id articles/>
+    }
+}
+
+/// Everybody in the organization. Owners add people by username, change their role
+/// and remove them, while other members can leave.
+#[component]
+fn OrganizationMembers(
+    user_id: crate::auth::UserIdSignal,
+    organization: crate::models::Organization,
+    members: Vec<crate::models::OrganizationMember>,
+    set_member: Action<SetMemberAction, Result<(), ServerFnError>>,
+    remove_member: Action<RemoveMemberAction, Result<(), ServerFnError>>,
+) -> impl IntoView {
+    let is_owner = organization.role == Some(crate::models::OrgRole::Owner);
+    let organization_id = organization.id;
+    let set_result = set_member.value();
+
+    view! {
+        <div class="organization-members">
+            <h4>"Members"</h4>
+            {members
+                .into_iter()
+                .map(|x| {
+                    let is_self = user_id.get_untracked() == Some(x.user.user_id);
+                    let can_remove = x.role != crate::models::OrgRole::Owner && (is_owner || is_self);
+                    view! {
+                        <div class="organization-member">
+                            <A href=format!("/profile/{}", x.user.user_id)>{x.user.name}</A>
+                            <span class="text-muted">{format!(" · {}", x.role.label())}</span>
+                            {can_remove.then(|| view! {
+                                <ActionForm action=remove_member class="inline">
+                                    <input type="hidden" name="organization_id" value=organization_id.to_string()/>
+                                    <input type="hidden" name="user_id" value=x.user.user_id.to_string()/>
+                                    <button type="submit" class="btn btn-sm btn-outline-danger">
+                                        {if is_self { "Leave" } else { "Remove" }}
+                                    </button>
+                                </ActionForm>
+                            })}
+                        </div>
+                    }
+                })
+                .collect_view()}
+            {is_owner.then(|| view! {
+                {move || set_result.get().and_then(Result::err).map(|x| view! {
+                    <p class="error-messages">{x.to_string()}</p>
+                })}
+                <ActionForm action=set_member>
+                    <input type="hidden" name="organization_id" value=organization_id.to_string()/>
+                    <div class="input-group">
+                        <input
+                            name="username"
+                            type="text"
+                            class="form-control"
+                            placeholder="Username"
+                            required=true
+                        />
+                        <select name="role" class="form-control">
+                            {[crate::models::OrgRole::Writer, crate::models::OrgRole::Editor, crate::models::OrgRole::Owner]
+                                .into_iter()
+                                .map(|x| view! { <option value=x.as_str()>{x.label()}</option> })
+                                .collect_view()}
+                        </select>
+                        <button type="submit" class="btn btn-outline-primary">"Add or change role"</button>
+                    </div>
+                </ActionForm>
+            })}
+        </div>
+    }
+}
+
+/// Starts a new organization, owned by the logged user, and lists theirs.
+#[component]
+pub fn OrganizationsSettings() -> impl IntoView {
+    let create = create_server_action::<CreateOrganizationAction>();
+    let result = create.value();
+    let organizations = create_resource(|| (), |_| super::my_organizations());
+
+    view! {
+        <h4>"Organizations"</h4>
+        <Suspense fallback=|| ()>
+            {move || organizations.get().and_then(Result::ok).map(|organizations| view! {
+                <ul>
+                    {organizations
+                        .into_iter()
+                        .map(|x| view! {
+                            <li>
+                                <A href=format!("/org/{}", x.slug)>{x.name}</A>
+                                {x.role.map(|x| format!(" · {}", x.label()))}
+                            </li>
+                        })
+                        .collect_view()}
+                </ul>
+            })}
+        </Suspense>
+        {move || result.get().and_then(Result::err).map(|x| view! {
+            <div class="alert alert-danger">{x.to_string()}</div>
+        })}
+        <ActionForm action=create>
+            <fieldset class="form-group">
+                <input name="name" class="form-control" type="text" placeholder="Name" required=true/>
+            </fieldset>
+            <fieldset class="form-group">
+                <input
+                    name="slug"
+                    class="form-control"
+                    type="text"
+                    placeholder="Handle, as in /org/handle"
+                    required=true
+                />
+            </fieldset>
+            <fieldset class="form-group">
+                <textarea name="description" class="form-control" rows="3" placeholder="What does it publish?"></textarea>
+            </fieldset>
+            <button class="btn btn-outline-primary pull-xs-right" type="submit">
+                "Create organization"
+            </button>
+        </ActionForm>
+    }
+}
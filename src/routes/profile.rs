@@ -1,5 +1,6 @@
 use crate::components::ArticlePreviewList;
-use crate::components::ButtonFollow;
+use crate::components::{ButtonFollow, ButtonsBlockMute};
+use crate::components::{ReportButton, ResponsiveImage, Seo};
 use leptos::*;
 use leptos_meta::*;
 use leptos_router::*;
@@ -23,6 +24,11 @@ pub async fn profile_articles(
 pub struct UserProfileModel {
     user: crate::models::User,
     following: Option<bool>,
+    role: crate::models::Role,
+    /// Whether the logged user blocked or muted this user.
+    relationship: crate::models::Relationship,
+    /// Whether the logged user may change `role`.
+    can_manage_roles: bool,
 }
 
 #[server(UserProfileAction, "/api", "GetJson")]
@@ -33,6 +39,20 @@ pub async fn user_profile(user_id: uuid::Uuid) -> Result<UserProfileModel, Serve
         tracing::error!("{err}");
         ServerFnError::ServerError("Could not retrieve articles, try again later".into())
     })?;
+    let role = crate::models::Role::of(user_id).await.map_err(|x| {
+        tracing::error!("problem while reading the role of {user_id}: {x:?}");
+        ServerFnError::ServerError("Could not retrieve the user, try again later".into())
+    })?;
+    let can_manage_roles = crate::auth::has_permission(crate::models::Permission::ManageRoles).await;
+    let relationship = match crate::auth::get_user_id() {
+        Some(logged_user_id) => crate::models::Relationship::between(logged_user_id, user_id)
+            .await
+            .map_err(|x| {
+                tracing::error!("problem while reading the relationship with {user_id}: {x:?}");
+                ServerFnError::ServerError("Could not retrieve the user, try again later".into())
+            })?,
+        None => crate::models::Relationship::default(),
+    };
     match crate::auth::get_user_id() {
         Some(logged_user_id) => sqlx::query!(
             "SELECT EXISTS(SELECT * FROM Follows WHERE follower_id=$1 and influencer_id=$2)",
@@ -49,14 +69,36 @@ pub async fn user_profile(user_id: uuid::Uuid) -> Result<UserProfileModel, Serve
         .map(|x| UserProfileModel {
             user,
             following: x.exists,
+            role,
+            relationship,
+            can_manage_roles,
         }),
         None => Ok(UserProfileModel {
             user,
             following: None,
+            role,
+            relationship,
+            can_manage_roles,
         }),
     }
 }
 
+#[server(SetRoleAction, "/api")]
+#[tracing::instrument]
+pub async fn set_role(user_id: uuid::Uuid, role: crate::models::Role) -> Result<(), ServerFnError> {
+    let admin_id = crate::auth::require_permission(crate::models::Permission::ManageRoles).await?;
+    if admin_id == user_id {
+        return Err(ServerFnError::ServerError("You cannot change your own role".into()));
+    }
+
+    crate::models::Role::set(user_id, role, admin_id)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while giving {user_id} the role {role:?}: {x:?}");
+            ServerFnError::ServerError("Could not change the role, try again later".into())
+        })
+}
+
 #[component]
 fn ProfileTabs(
     route_user_id: Memo<Option<uuid::Uuid>>,
@@ -117,6 +159,10 @@ pub fn Profile(user_id: crate::auth::UserIdSignal) -> impl IntoView {
 
     view! {
         <Title text=move || format!("{}'s profile", route_user_id.get().map_or("Unknown User".to_string(), |id| id.to_string())) />
+        {move || route_user_id.get().map(|id| view! {
+            <Link rel="alternate" type_="application/atom+xml" href=format!("/profile/{id}/feed.xml")/>
+            <Link rel="alternate" type_="application/rss+xml" href=format!("/profile/{id}/rss.xml")/>
+        })}
         <div class="profile-page">
             <UserInfo logged_user_id=user_id />
 
@@ -202,9 +248,25 @@ pub fn UserInfo(logged_user_id: crate::auth::UserIdSignal) -> impl IntoView {
                                         let username = u.user.name();
                                         let bio = u.user.bio();
                                         let author_id = create_rw_signal(u.user.id()).read_only();
+                                        let path = format!("/profile/{}", u.user.id().unwrap_or_default());
+                                        let avatar = image.clone().unwrap_or_default();
+                                        let avatar_alt = format!("{username}'s avatar");
 
                                         view!{
-                                            <img src=image class="user-img" />
+                                            <Title text=username.clone()/>
+                                            <Seo
+                                                title=username.clone()
+                                                description=bio.clone().unwrap_or_else(|| format!("Articles by {username}"))
+                                                path
+                                                image
+                                            />
+                                            <ResponsiveImage
+                                                src=avatar
+                                                kind=crate::models::UploadKind::Avatar
+                                                sizes="100px"
+                                                alt=avatar_alt
+                                                class="user-img"
+                                            />
                                             <h4>{username}</h4>
                                             <p>{bio.unwrap_or("No bio available".into())}</p>
                                             <ButtonFollow
@@ -212,6 +274,25 @@ pub fn UserInfo(logged_user_id: crate::auth::UserIdSignal) -> impl IntoView {
                                                 author_id
                                                 following=u.following.unwrap_or_default()
                                             />
+                                            <Show
+                                                when=move || logged_user_id.get().is_some_and(|id| Some(id) != author_id.get())
+                                                fallback=|| ()
+                                            >
+                                                <ButtonsBlockMute
+                                                    other_user_id=author_id.get_untracked().unwrap_or_default()
+                                                    relationship=u.relationship
+                                                />
+                                                <ReportButton
+                                                    target=crate::models::ReportTarget::User
+                                                    target_id=author_id.get_untracked().unwrap_or_default()
+                                                />
+                                            </Show>
+                                            {(u.role != crate::models::Role::User).then(|| view! {
+                                                <span class="tag-default tag-pill role-badge">{u.role.label()}</span>
+                                            })}
+                                            {u.can_manage_roles.then(|| view! {
+                                                <RoleSelect user_id=u.user.id().unwrap_or_default() role=u.role/>
+                                            })}
                                         }
                                     })
                                 })
@@ -223,4 +304,30 @@ pub fn UserInfo(logged_user_id: crate::auth::UserIdSignal) -> impl IntoView {
             </div>
         </div>
     }
-}
\ No newline at end of file
+}
+
+/// Lets an admin give the user another role.
+#[component]
+fn RoleSelect(user_id: uuid::Uuid, role: crate::models::Role) -> impl IntoView {
+    let set_role = create_server_action::<SetRoleAction>();
+    let result = set_role.value();
+
+    view! {
+        <ActionForm action=set_role class="inline role-select">
+            <input type="hidden" name="user_id" value=user_id.to_string()/>
+            <select name="role" class="form-control form-control-sm">
+                {crate::models::Role::ALL
+                    .into_iter()
+                    .map(|x| view! { <option value=x.as_str() selected=x == role>{x.label()}</option> })
+                    .collect_view()}
+            </select>
+            <button type="submit" class="btn btn-sm btn-outline-secondary" disabled=set_role.pending()>
+                "Change role"
+            </button>
+            {move || result.get().map(|x| match x {
+                Ok(()) => view! { <span class="text-muted">" Saved"</span> }.into_view(),
+                Err(x) => view! { <span class="error-messages">{format!(" {x}")}</span> }.into_view(),
+            })}
+        </ActionForm>
+    }
+}
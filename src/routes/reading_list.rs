@@ -0,0 +1,126 @@
+use crate::components::ArticlePreview;
+use leptos::*;
+use leptos_meta::*;
+use leptos_router::*;
+
+#[server(ReadingListAction, "/api", "GetJson")]
+#[tracing::instrument]
+pub async fn reading_list(
+    archived: Option<bool>,
+) -> Result<Vec<crate::models::Article>, ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        leptos_axum::redirect("/login");
+        return Err(ServerFnError::ServerError("Authentication required".into()));
+    };
+
+    crate::models::Article::for_reading_list(user_id, archived.unwrap_or_default())
+        .await
+        .map_err(|x| {
+            let err = format!("Error while getting the reading list: {x:?}");
+            tracing::error!("{err}");
+            ServerFnError::ServerError("Could not retrieve the reading list, try again later".into())
+        })
+}
+
+#[server(ArchiveBookmarkAction, "/api")]
+#[tracing::instrument]
+pub async fn archive_bookmark(article_id: uuid::Uuid, archived: bool) -> Result<(), ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError("You must be logged in".into()));
+    };
+
+    crate::models::Article::set_bookmark_archived(article_id, user_id, archived)
+        .await
+        .map(|_| ())
+        .map_err(|x| {
+            let err = format!("Error while archiving a bookmark: {x:?}");
+            tracing::error!("{err}");
+            ServerFnError::ServerError("Could not update the reading list, try again later".into())
+        })
+}
+
+#[component]
+pub fn ReadingList(user_id: crate::auth::UserIdSignal) -> impl IntoView {
+    let query = use_query_map();
+    let archived = create_memo(move |_| {
+        query.with(|x| x.get("archived").map(|v| v == "true"))
+    });
+
+    let archive = create_server_action::<ArchiveBookmarkAction>();
+    let articles = create_resource(
+        move || (archived.get(), archive.version().get()),
+        move |(archived, _)| async move { reading_list(archived).await },
+    );
+
+    view! {
+        <Title text="Reading list"/>
+        <div class="profile-page">
+            <div class="container page">
+                <div class="row">
+                    <div class="col-xs-12 col-md-10 offset-md-1">
+                        <div class="articles-toggle">
+                            <ul class="nav nav-pills outline-active">
+                                <li class="nav-item">
+                                    <a class="nav-link"
+                                        class:active=move || !archived.get().unwrap_or_default()
+                                        href="/reading-list">
+                                        "Saved"
+                                    </a>
+                                </li>
+                                <li class="nav-item">
+                                    <a class="nav-link"
+                                        class:active=move || archived.get().unwrap_or_default()
+                                        href="/reading-list?archived=true">
+                                        "Archived"
+                                    </a>
+                                </li>
+                            </ul>
+                        </div>
+
+                        <Suspense fallback=move || view! {
+                            <div class="article-preview">"Loading articles..."</div>
+                        }>
+                            <ErrorBoundary fallback=|_| view! {
+                                <div class="article-preview">
+                                    "Error loading the reading list. Please try again later."
+                                </div>
+                            }>
+                                {move || articles.get().map(move |x| x.map(move |articles| {
+                                    if articles.is_empty() {
+                                        return view! {
+                                            <div class="article-preview">
+                                                "Nothing saved here... yet."
+                                            </div>
+                                        }.into_view();
+                                    }
+                                    articles
+                                        .into_iter()
+                                        .map(|article| {
+                                            let article_id = article.id;
+                                            let article = create_rw_signal(article);
+                                            let now_archived = !archived.get_untracked().unwrap_or_default();
+                                            view! {
+                                                <ArticlePreview article user_id />
+                                                <ActionForm action=archive class="inline">
+                                                    <input type="hidden" name="article_id" value=article_id.to_string() />
+                                                    <input type="hidden" name="archived" value=now_archived.to_string() />
+                                                    <button
+                                                        type="submit"
+                                                        class="btn btn-sm btn-outline-secondary"
+                                                        disabled=move || archive.pending().get()
+                                                    >
+                                                        {if now_archived { "Archive" } else { "Move back to reading list" }}
+                                                    </button>
+                                                </ActionForm>
+                                            }
+                                        })
+                                        .collect_view()
+                                }))}
+                            </ErrorBoundary>
+                        </Suspense>
+                    </div>
+                </div>
+            </div>
+        </div>
+    }
+}
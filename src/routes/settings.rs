@@ -85,6 +85,96 @@ pub async fn settings_get() -> Result<crate::models::User, ServerFnError> {
     get_user().await
 }
 
+#[tracing::instrument]
+#[server(GetDigestSettingsAction, "/api", "GetJson")]
+pub async fn get_digest_settings() -> Result<crate::models::DigestPreferences, ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError("Authentication required".into()));
+    };
+    crate::models::DigestPreferences::get(user_id)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while fetching digest preferences: {x:?}");
+            ServerFnError::ServerError("Problem while fetching digest preferences".into())
+        })
+}
+
+#[tracing::instrument]
+#[server(DigestSettingsAction, "/api")]
+pub async fn digest_settings(frequency: String, tags: String) -> Result<(), ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError("Authentication required".into()));
+    };
+    let frequency = frequency
+        .parse::<crate::models::DigestFrequency>()
+        .map_err(ServerFnError::ServerError)?;
+    let mut tags = tags
+        .split_ascii_whitespace()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    tags.sort();
+    tags.dedup();
+
+    crate::models::DigestPreferences { frequency, tags }
+        .save(user_id)
+        .await
+        .map(|_| ())
+        .map_err(|x| {
+            tracing::error!("problem while saving digest preferences: {x:?}");
+            ServerFnError::ServerError("Problem while saving digest preferences".into())
+        })
+}
+
+#[tracing::instrument]
+#[server(GetDataExportAction, "/api", "GetJson")]
+pub async fn get_data_export() -> Result<Option<crate::models::DataExport>, ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError("Authentication required".into()));
+    };
+    crate::models::DataExport::latest(user_id).await.map_err(|x| {
+        tracing::error!("problem while fetching the data export of {user_id}: {x:?}");
+        ServerFnError::ServerError("Problem while fetching your data export".into())
+    })
+}
+
+/// Starts building an archive of the logged user's data in the background.
+#[tracing::instrument]
+#[server(RequestDataExportAction, "/api")]
+pub async fn request_data_export() -> Result<(), ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError("Authentication required".into()));
+    };
+    let export_id = crate::models::DataExport::request(user_id)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while requesting the data export of {user_id}: {x:?}");
+            ServerFnError::ServerError("Problem while requesting your data export".into())
+        })?
+        .ok_or_else(|| ServerFnError::ServerError("Your data export is already being prepared".into()))?;
+    crate::export::spawn_export(export_id, user_id);
+    Ok(())
+}
+
+/// Deactivates the logged user's account, which gets deleted once the grace period
+/// is over. Returns when that happens.
+#[tracing::instrument(skip(password))]
+#[server(DeleteAccountAction, "/api")]
+pub async fn delete_account(password: String) -> Result<String, ServerFnError> {
+    let Some(user_id) = crate::auth::get_user_id() else {
+        return Err(ServerFnError::ServerError("Authentication required".into()));
+    };
+
+    let delete_after = crate::models::User::deactivate(user_id, &password)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while deactivating {user_id}: {x:?}");
+            ServerFnError::ServerError("Could not delete your account, try again later".into())
+        })?
+        .ok_or_else(|| ServerFnError::ServerError("Wrong password".into()))?;
+    tracing::info!("{user_id} asked for their account to be deleted");
+    Ok(delete_after.format(crate::models::DATE_FORMAT).to_string())
+}
+
 #[component]
 pub fn Settings(logout: crate::auth::LogoutSignal) -> impl IntoView {
     let settings_resource = create_resource(|| (), |_| async move { settings_get().await });
@@ -122,12 +212,27 @@ pub fn Settings(logout: crate::auth::LogoutSignal) -> impl IntoView {
                             </ErrorBoundary>
                         </Suspense>
 
+                        <hr/>
+                        <DigestSettingsForm/>
+
+                        <hr/>
+                        <super::OrganizationsSettings/>
+
+                        <hr/>
+                        <ArticleFilesForm/>
+
+                        <hr/>
+                        <DataExportForm/>
+
                         <hr/>
                         <ActionForm action=logout>
                             <button type="submit" class="btn btn-outline-danger">
                                 "Click here to logout"
                             </button>
                         </ActionForm>
+
+                        <hr/>
+                        <DeleteAccountForm logout/>
                     </div>
                 </div>
             </div>
@@ -185,6 +290,14 @@ fn SettingsViewForm(user: crate::models::User) -> impl IntoView {
                 }
             })}
 
+            // A plain form, as the upload is multipart and the server sends it back here
+            <form method="post" action="/uploads/avatar" enctype="multipart/form-data" class="form-inline">
+                <fieldset class="form-group">
+                    <input name="file" type="file" accept="image/png,image/jpeg,image/gif,image/webp" required=true/>
+                    <button class="btn btn-sm btn-outline-primary" type="submit">"Upload picture"</button>
+                </fieldset>
+            </form>
+
             <ActionForm action=settings_action>
                 <fieldset disabled=move || is_pending()>
                     <fieldset class="form-group">
@@ -264,4 +377,170 @@ fn SettingsViewForm(user: crate::models::User) -> impl IntoView {
             </ActionForm>
         </div>
     }
-}
\ No newline at end of file
+}
+
+#[component]
+fn DigestSettingsForm() -> impl IntoView {
+    let digest_action = create_server_action::<DigestSettingsAction>();
+    let result = digest_action.value();
+    let preferences = create_resource(
+        move || digest_action.version().get(),
+        |_| get_digest_settings(),
+    );
+
+    view! {
+        <h4>"Email digest"</h4>
+        <p class="text-muted">
+            "New articles from the authors you follow, and from the tags below."
+        </p>
+        {move || result.get().map(|r| match r {
+            Ok(()) => view! { <div class="alert alert-success">"Digest preferences saved"</div> },
+            Err(x) => view! { <div class="alert alert-danger">{x.to_string()}</div> },
+        })}
+        <Suspense fallback=move || view! { <p>"Loading digest preferences"</p> }>
+            {move || preferences.get().map(|x| x.map(|preferences| view! {
+                <ActionForm action=digest_action>
+                    <fieldset class="form-group">
+                        <select name="frequency" class="form-control">
+                            {crate::models::DigestFrequency::ALL
+                                .into_iter()
+                                .map(|x| view! {
+                                    <option value=x.as_str() selected=x == preferences.frequency>
+                                        {x.label()}
+                                    </option>
+                                })
+                                .collect_view()}
+                        </select>
+                    </fieldset>
+                    <fieldset class="form-group">
+                        <input
+                            name="tags"
+                            class="form-control"
+                            type="text"
+                            placeholder="Tags, separated by spaces"
+                            value=preferences.tags.join(" ")
+                        />
+                    </fieldset>
+                    <button class="btn btn-outline-primary pull-xs-right" type="submit">
+                        "Save digest preferences"
+                    </button>
+                </ActionForm>
+            }))}
+        </Suspense>
+    }
+}
+
+#[component]
+fn ArticleFilesForm() -> impl IntoView {
+    view! {
+        <h4>"Import and export articles"</h4>
+        <p class="text-muted">
+            "A ZIP of Markdown files with a front matter (title, description, tags, date and "
+            "cover image), as static site generators use. Files named after the slug of one "
+            "of your articles update it."
+        </p>
+        // A plain form, as the upload is multipart and the server answers with a page
+        <form method="post" action="/import/articles" enctype="multipart/form-data" class="form-inline">
+            <fieldset class="form-group">
+                <input name="file" type="file" accept=".zip,application/zip" required=true/>
+                <button class="btn btn-sm btn-outline-primary" type="submit">"Import articles"</button>
+            </fieldset>
+        </form>
+        <p>
+            <a href="/export/articles" rel="external">"Download your articles"</a>
+        </p>
+    }
+}
+
+#[component]
+fn DataExportForm() -> impl IntoView {
+    use crate::models::ExportStatus;
+
+    let export_action = create_server_action::<RequestDataExportAction>();
+    let result = export_action.value();
+    let export = create_resource(
+        move || export_action.version().get(),
+        |_| get_data_export(),
+    );
+
+    view! {
+        <h4>"Your data"</h4>
+        <p class="text-muted">
+            "An archive of your profile, articles, comments, follows and favorites, as JSON "
+            "and Markdown files. Building it takes a moment, come back here to download it."
+        </p>
+        {move || result.get().and_then(Result::err).map(|x| view! {
+            <div class="alert alert-danger">{x.to_string()}</div>
+        })}
+        <Suspense fallback=move || view! { <p>"Loading your data export"</p> }>
+            {move || export.get().and_then(Result::ok).flatten().map(|x| match x.status {
+                ExportStatus::Pending => view! {
+                    <p>
+                        {format!("The export you asked for on {} is being prepared. ", x.requested_at)}
+                        <button class="btn btn-sm btn-link" on:click=move |_| export.refetch()>
+                            "Check again"
+                        </button>
+                    </p>
+                }.into_view(),
+                ExportStatus::Ready => view! {
+                    <p>
+                        <a href=x.download_url.unwrap_or_default() rel="external">"Download your data"</a>
+                        {format!(", exported on {}. The link expires a week after the export.", x.requested_at)}
+                    </p>
+                }.into_view(),
+                ExportStatus::Failed => view! {
+                    <p class="error-messages">
+                        {format!("The export you asked for on {} failed, try again.", x.requested_at)}
+                    </p>
+                }.into_view(),
+            })}
+        </Suspense>
+        <ActionForm action=export_action>
+            <button class="btn btn-outline-primary pull-xs-right" type="submit" disabled=export_action.pending()>
+                "Export my data"
+            </button>
+        </ActionForm>
+    }
+}
+
+#[component]
+fn DeleteAccountForm(logout: crate::auth::LogoutSignal) -> impl IntoView {
+    let delete_action = create_server_action::<DeleteAccountAction>();
+    let result = delete_action.value();
+
+    // The session is over once the account is deactivated
+    create_effect(move |_| {
+        if let Some(Ok(_)) = result.get() {
+            logout.dispatch(crate::auth::LogoutAction {});
+        }
+    });
+
+    view! {
+        <h4>"Delete your account"</h4>
+        <p class="text-muted">
+            "Your account is deactivated right away and deleted after a grace period, logging in "
+            "before then cancels it. Deleting it unpublishes your articles, and your comments stay "
+            "without your name."
+        </p>
+        {move || result.get().map(|r| match r {
+            Ok(date) => view! {
+                <div class="alert alert-success">{format!("Your account will be deleted on {date}")}</div>
+            },
+            Err(x) => view! { <div class="alert alert-danger">{x.to_string()}</div> },
+        })}
+        <ActionForm action=delete_action>
+            <fieldset class="form-group">
+                <input
+                    name="password"
+                    class="form-control"
+                    type="password"
+                    placeholder="Your password, to confirm"
+                    required=true
+                />
+            </fieldset>
+            <button class="btn btn-danger pull-xs-right" type="submit" disabled=delete_action.pending()>
+                "Delete my account"
+            </button>
+        </ActionForm>
+    }
+}
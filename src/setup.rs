@@ -14,6 +14,13 @@ pub async fn init_app(configuration_path: Option<&str>) {
     crate::database::init_db()
         .await
         .expect("problem during initialization of the database");
+    crate::realtime::init()
+        .await
+        .expect("problem while listening for realtime events");
+    crate::storage::init_store().expect("problem during initialization of the storage");
+    crate::digest::spawn_scheduler(std::sync::Arc::new(crate::mail::FileMailer::from_env()));
+    crate::accounts::spawn_deletion_scheduler();
+    crate::export::spawn_cleanup_scheduler();
 
     // Get leptos configuration
     let conf = get_configuration(configuration_path).await.unwrap();
@@ -30,6 +37,33 @@ pub async fn init_app(configuration_path: Option<&str>) {
             "/api/*fn_name",
             axum::routing::post(leptos_axum::handle_server_fns).get(leptos_axum::handle_server_fns),
         )
+        .route("/events", axum::routing::get(crate::realtime::sse_handler))
+        .route("/exports/download", axum::routing::get(crate::export::download_handler))
+        .route("/export/articles", axum::routing::get(crate::export::articles_handler))
+        .route(
+            "/import/articles",
+            axum::routing::post(crate::import::import_handler)
+                .layer(axum::extract::DefaultBodyLimit::max(crate::import::MAX_IMPORT_BYTES)),
+        )
+        .route("/robots.txt", axum::routing::get(crate::sitemap::robots_handler))
+        .route("/sitemap.xml", axum::routing::get(crate::sitemap::sitemap_handler))
+        .route("/feed.xml", axum::routing::get(crate::feed::global_atom))
+        .route("/rss.xml", axum::routing::get(crate::feed::global_rss))
+        .route("/tag/:name/feed.xml", axum::routing::get(crate::feed::tag_atom))
+        .route("/tag/:name/rss.xml", axum::routing::get(crate::feed::tag_rss))
+        .route("/profile/:user_id/feed.xml", axum::routing::get(crate::feed::author_atom))
+        .route("/profile/:user_id/rss.xml", axum::routing::get(crate::feed::author_rss))
+        .route(
+            "/uploads/:name",
+            axum::routing::get(crate::storage::serve_handler)
+                .post(crate::storage::upload_handler)
+                .layer(axum::extract::DefaultBodyLimit::max(crate::storage::MAX_UPLOAD_BYTES)),
+        )
+        .route(
+            "/unsubscribe",
+            axum::routing::get(crate::digest::unsubscribe_page)
+                .post(crate::digest::unsubscribe_handler),
+        )
         .leptos_routes(&leptos_options, routes, || view! { <App/> })
         .fallback_service(serve_dir)
         .layer(
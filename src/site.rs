@@ -0,0 +1,5 @@
+/// Absolute URL of the site, configured with `SITE_URL`. Links in emails, feeds,
+/// the sitemap and page metadata all start with it.
+pub fn url() -> String {
+    std::env::var("SITE_URL").unwrap_or_else(|_| "http://localhost:3000".to_string())
+}
@@ -0,0 +1,68 @@
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+use crate::feed::escape;
+
+/// Pages that are either private or not worth indexing.
+const DISALLOWED: [&str; 6] = [
+    "/api/",
+    "/editor",
+    "/settings",
+    "/notifications",
+    "/reading-list",
+    "/unsubscribe",
+];
+
+#[tracing::instrument]
+pub async fn robots_handler() -> Response {
+    let mut robots = String::from("User-agent: *\n");
+    for path in DISALLOWED {
+        robots.push_str(&format!("Disallow: {path}\n"));
+    }
+    robots.push_str(&format!("\nSitemap: {}/sitemap.xml\n", crate::site::url()));
+    ([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], robots).into_response()
+}
+
+/// Every article plus the authors who wrote them.
+#[tracing::instrument]
+pub async fn sitemap_handler() -> Response {
+    let articles = match crate::models::Article::for_sitemap().await {
+        Ok(x) => x,
+        Err(x) => {
+            tracing::error!("problem while fetching the sitemap articles: {x:?}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let authors = match crate::models::Article::authors_for_sitemap().await {
+        Ok(x) => x,
+        Err(x) => {
+            tracing::error!("problem while fetching the sitemap authors: {x:?}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let site_url = crate::site::url();
+    let mut xml = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n<url><loc>{}/</loc></url>\n",
+        escape(&site_url)
+    );
+    for (slug, updated_at) in articles {
+        xml.push_str(&format!(
+            "<url><loc>{}</loc><lastmod>{}</lastmod></url>\n",
+            escape(&format!("{site_url}/article/{slug}")),
+            updated_at.format("%Y-%m-%d"),
+        ));
+    }
+    for (author_id, updated_at) in authors {
+        xml.push_str(&format!(
+            "<url><loc>{}</loc><lastmod>{}</lastmod></url>\n",
+            escape(&format!("{site_url}/profile/{author_id}")),
+            updated_at.format("%Y-%m-%d"),
+        ));
+    }
+    xml.push_str("</urlset>\n");
+
+    ([(header::CONTENT_TYPE, "application/xml; charset=utf-8")], xml).into_response()
+}
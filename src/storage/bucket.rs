@@ -0,0 +1,111 @@
+use futures::future::BoxFuture;
+use s3::{creds::Credentials, Bucket, Region};
+
+use super::{BlobStore, StorageError};
+
+/// Keeps blobs in an S3 bucket, or anything speaking the same API such as MinIO.
+pub struct S3Store {
+    bucket: Bucket,
+}
+
+impl S3Store {
+    pub fn new(
+        bucket: &str,
+        endpoint: String,
+        region: String,
+        access_key: &str,
+        secret_key: &str,
+    ) -> Result<Self, StorageError> {
+        let credentials = Credentials::new(Some(access_key), Some(secret_key), None, None, None)
+            .map_err(|x| StorageError::Remote(x.to_string()))?;
+        let bucket = Bucket::new(bucket, Region::Custom { region, endpoint }, credentials)
+            .map_err(|x| StorageError::Remote(x.to_string()))?
+            // Virtual-hosted buckets need DNS that local stand-ins don't have
+            .with_path_style();
+        Ok(Self { bucket })
+    }
+
+    /// Configured with `S3_BUCKET`, `S3_ENDPOINT`, `S3_REGION`, `S3_ACCESS_KEY` and `S3_SECRET_KEY`.
+    pub fn from_env() -> Result<Self, StorageError> {
+        let var = |name: &str| {
+            std::env::var(name).map_err(|_| StorageError::Remote(format!("{name} is not set")))
+        };
+        Self::new(
+            &var("S3_BUCKET")?,
+            var("S3_ENDPOINT")?,
+            var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+            &var("S3_ACCESS_KEY")?,
+            &var("S3_SECRET_KEY")?,
+        )
+    }
+}
+
+impl BlobStore for S3Store {
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        bytes: Vec<u8>,
+        content_type: &'a str,
+    ) -> BoxFuture<'a, Result<(), StorageError>> {
+        Box::pin(async move {
+            let response = self
+                .bucket
+                .put_object_with_content_type(key, &bytes, content_type)
+                .await
+                .map_err(|x| StorageError::Remote(x.to_string()))?;
+            match response.status_code() {
+                200..=299 => Ok(()),
+                status => Err(StorageError::Remote(format!("upload of {key} failed with {status}"))),
+            }
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<Vec<u8>>, StorageError>> {
+        Box::pin(async move {
+            match self.bucket.get_object(key).await {
+                Ok(x) if x.status_code() == 404 => Ok(None),
+                Ok(x) if (200..300).contains(&x.status_code()) => Ok(Some(x.bytes().to_vec())),
+                Ok(x) => Err(StorageError::Remote(format!(
+                    "download of {key} failed with {}",
+                    x.status_code()
+                ))),
+                Err(s3::error::S3Error::Http(404, _)) => Ok(None),
+                Err(x) => Err(StorageError::Remote(x.to_string())),
+            }
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), StorageError>> {
+        Box::pin(async move {
+            match self.bucket.delete_object(key).await {
+                Ok(x) if (200..300).contains(&x.status_code()) || x.status_code() == 404 => Ok(()),
+                Ok(x) => Err(StorageError::Remote(format!(
+                    "deletion of {key} failed with {}",
+                    x.status_code()
+                ))),
+                Err(s3::error::S3Error::Http(404, _)) => Ok(()),
+                Err(x) => Err(StorageError::Remote(x.to_string())),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Needs a bucket, e.g. `docker run -p 9000:9000 minio/minio server /data` with
+    /// the `S3_*` variables pointing at it.
+    #[tokio::test]
+    #[ignore]
+    async fn round_trip() {
+        let store = S3Store::from_env().unwrap();
+        let key = format!("{}.png", uuid::Uuid::new_v4().simple());
+
+        assert!(store.get(&key).await.unwrap().is_none());
+        store.put(&key, b"content".to_vec(), "image/png").await.unwrap();
+        assert_eq!(store.get(&key).await.unwrap().as_deref(), Some(&b"content"[..]));
+        store.delete(&key).await.unwrap();
+        assert!(store.get(&key).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,83 @@
+use futures::future::BoxFuture;
+use std::path::PathBuf;
+
+use super::{BlobStore, StorageError};
+
+/// Keeps blobs as files in one directory, for local development and single servers.
+pub struct LocalStore {
+    dir: PathBuf,
+}
+
+impl LocalStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Configured with `UPLOAD_DIR`.
+    pub fn from_env() -> Self {
+        Self::new(std::env::var("UPLOAD_DIR").unwrap_or_else(|_| "target/uploads".to_string()))
+    }
+}
+
+impl BlobStore for LocalStore {
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        bytes: Vec<u8>,
+        _content_type: &'a str,
+    ) -> BoxFuture<'a, Result<(), StorageError>> {
+        Box::pin(async move {
+            let path = self.dir.join(key);
+            if tokio::fs::try_exists(&path).await? {
+                return Ok(());
+            }
+            tokio::fs::create_dir_all(&self.dir).await?;
+            // Written aside first so a reader never sees half a file
+            let partial = self.dir.join(format!("{key}.{}.part", uuid::Uuid::new_v4()));
+            tokio::fs::write(&partial, bytes).await?;
+            tokio::fs::rename(partial, path).await?;
+            Ok(())
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<Vec<u8>>, StorageError>> {
+        Box::pin(async move {
+            match tokio::fs::read(self.dir.join(key)).await {
+                Ok(x) => Ok(Some(x)),
+                Err(x) if x.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(x) => Err(x.into()),
+            }
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), StorageError>> {
+        Box::pin(async move {
+            match tokio::fs::remove_file(self.dir.join(key)).await {
+                Err(x) if x.kind() != std::io::ErrorKind::NotFound => Err(x.into()),
+                _ => Ok(()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn round_trip() {
+        let dir = std::env::temp_dir().join(format!("uploads-{}", uuid::Uuid::new_v4()));
+        let store = LocalStore::new(&dir);
+
+        assert!(store.get("missing.png").await.unwrap().is_none());
+        store.put("a.png", b"content".to_vec(), "image/png").await.unwrap();
+        store.put("a.png", b"content".to_vec(), "image/png").await.unwrap();
+        assert_eq!(store.get("a.png").await.unwrap().as_deref(), Some(&b"content"[..]));
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        store.delete("a.png").await.unwrap();
+        store.delete("a.png").await.unwrap();
+        assert!(store.get("a.png").await.unwrap().is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,212 @@
+//! Where uploaded files live. Files are named after the SHA-256 of their content,
+//! so the same image uploaded twice is stored once and can be cached forever.
+
+mod bucket;
+mod local;
+mod process;
+mod upload;
+
+use futures::future::BoxFuture;
+use std::sync::OnceLock;
+
+pub use bucket::S3Store;
+pub use local::LocalStore;
+pub use process::{process, MAX_DIMENSION};
+pub use upload::{serve_handler, upload_handler, MAX_UPLOAD_BYTES};
+
+#[derive(Debug)]
+pub enum StorageError {
+    Io(std::io::Error),
+    Remote(String),
+}
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageError::Io(x) => write!(f, "{x}"),
+            StorageError::Remote(x) => write!(f, "{x}"),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<std::io::Error> for StorageError {
+    fn from(value: std::io::Error) -> Self {
+        StorageError::Io(value)
+    }
+}
+
+/// A flat namespace of immutable blobs.
+pub trait BlobStore: Send + Sync {
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        bytes: Vec<u8>,
+        content_type: &'a str,
+    ) -> BoxFuture<'a, Result<(), StorageError>>;
+
+    /// `None` when nothing is stored under `key`.
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<Vec<u8>>, StorageError>>;
+
+    /// Succeeds when nothing is stored under `key`, so it can be retried.
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), StorageError>>;
+}
+
+/// Image formats that get stored. Uploads are recognised from their content rather
+/// than from what the client claims, and AVIF is only ever generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageType {
+    Png,
+    Jpeg,
+    Gif,
+    Webp,
+    Avif,
+}
+
+impl ImageType {
+    const ALL: [ImageType; 5] = [
+        ImageType::Png,
+        ImageType::Jpeg,
+        ImageType::Gif,
+        ImageType::Webp,
+        ImageType::Avif,
+    ];
+
+    pub fn sniff(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(ImageType::Png)
+        } else if bytes.starts_with(b"\xff\xd8\xff") {
+            Some(ImageType::Jpeg)
+        } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            Some(ImageType::Gif)
+        } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+            Some(ImageType::Webp)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageType::Png => "png",
+            ImageType::Jpeg => "jpg",
+            ImageType::Gif => "gif",
+            ImageType::Webp => "webp",
+            ImageType::Avif => "avif",
+        }
+    }
+
+    #[inline]
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ImageType::Png => "image/png",
+            ImageType::Jpeg => "image/jpeg",
+            ImageType::Gif => "image/gif",
+            ImageType::Webp => "image/webp",
+            ImageType::Avif => "image/avif",
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|x| x.extension() == extension)
+    }
+}
+
+/// Content-addressed name of a blob.
+pub fn blob_key(bytes: &[u8], image_type: ImageType) -> String {
+    use sha2::{Digest, Sha256};
+    format!("{:x}.{}", Sha256::digest(bytes), image_type.extension())
+}
+
+/// The image type of a well formed key, as produced by [`blob_key`] or
+/// [`crate::models::variant_key`].
+pub fn parse_key(key: &str) -> Option<ImageType> {
+    let (name, extension) = key.split_once('.')?;
+    let hash = match name.split_once('-') {
+        Some((hash, width)) => {
+            let digits = width.strip_suffix('w')?;
+            if digits.is_empty() || !digits.bytes().all(|x| x.is_ascii_digit()) {
+                return None;
+            }
+            hash
+        }
+        None => name,
+    };
+    if hash.len() != 64 || !hash.bytes().all(|x| matches!(x, b'0'..=b'9' | b'a'..=b'f')) {
+        return None;
+    }
+    ImageType::from_extension(extension)
+}
+
+/// Whether `url` is an image the site can show: an upload stored here, or an
+/// absolute http(s) link.
+pub fn is_image_url(url: &str) -> bool {
+    match url.strip_prefix("/uploads/") {
+        Some(key) => parse_key(key).is_some(),
+        None => url::Url::parse(url)
+            .is_ok_and(|x| matches!(x.scheme(), "http" | "https") && x.host().is_some()),
+    }
+}
+
+/// Public path of a stored blob.
+pub fn blob_url(key: &str) -> String {
+    format!("/uploads/{key}")
+}
+
+static STORE: OnceLock<Box<dyn BlobStore>> = OnceLock::new();
+
+/// Uses S3 when `STORAGE=s3`, and the local filesystem otherwise.
+#[tracing::instrument]
+pub fn init_store() -> Result<(), StorageError> {
+    let store: Box<dyn BlobStore> = match std::env::var("STORAGE").as_deref() {
+        Ok("s3") => Box::new(S3Store::from_env()?),
+        _ => Box::new(LocalStore::from_env()),
+    };
+    STORE
+        .set(store)
+        .map_err(|_| StorageError::Remote("Storage already initialized".into()))
+}
+
+pub fn get_store() -> &'static dyn BlobStore {
+    STORE
+        .get()
+        .expect("Storage not initialized. Call init_store() first")
+        .as_ref()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn images_are_sniffed_from_content() {
+        assert_eq!(ImageType::sniff(b"\x89PNG\r\n\x1a\nrest"), Some(ImageType::Png));
+        assert_eq!(ImageType::sniff(b"\xff\xd8\xff\xe0"), Some(ImageType::Jpeg));
+        assert_eq!(ImageType::sniff(b"RIFF\0\0\0\0WEBPVP8 "), Some(ImageType::Webp));
+        assert_eq!(ImageType::sniff(b"<svg xmlns=..."), None);
+    }
+
+    #[test]
+    fn keys_are_content_addressed() {
+        let key = blob_key(b"same bytes", ImageType::Png);
+        assert_eq!(key, blob_key(b"same bytes", ImageType::Png));
+        assert_ne!(key, blob_key(b"other bytes", ImageType::Png));
+        assert_eq!(parse_key(&key), Some(ImageType::Png));
+        assert_eq!(parse_key("../../etc/passwd.png"), None);
+        assert_eq!(parse_key(&key.replace(".png", ".svg")), None);
+        let variant = crate::models::variant_key(&key, 128, "avif");
+        assert_eq!(parse_key(&variant), Some(ImageType::Avif));
+        assert_eq!(parse_key(&variant.replace("-128w", "-w")), None);
+    }
+
+    #[test]
+    fn image_urls_are_uploads_or_links() {
+        assert!(is_image_url(&blob_url(&blob_key(b"bytes", ImageType::Png))));
+        assert!(is_image_url("https://example.com/a.png"));
+        assert!(!is_image_url("/uploads/../settings"));
+        assert!(!is_image_url("httpfoo"));
+        assert!(!is_image_url("javascript:alert(1)"));
+    }
+}
@@ -0,0 +1,176 @@
+use image::{
+    codecs::{avif::AvifEncoder, jpeg::JpegEncoder, png::PngEncoder, webp::WebPEncoder},
+    imageops::FilterType,
+    DynamicImage, ImageDecoder, ImageReader, Limits,
+};
+use std::io::Cursor;
+
+use super::ImageType;
+use crate::models::{UploadKind, VARIANT_FORMATS};
+
+/// AVIF encoding effort, from 1 (slowest, smallest) to 10.
+const AVIF_SPEED: u8 = 8;
+const AVIF_QUALITY: u8 = 70;
+const JPEG_QUALITY: u8 = 90;
+/// Largest width and height decoded, as a few KiB of PNG can claim any size.
+pub const MAX_DIMENSION: u32 = 8000;
+/// Largest buffer a decoded image may take, enough for the largest RGBA image.
+const MAX_ALLOC: u64 = 256 * 1024 * 1024;
+
+/// An upload ready to be stored.
+pub struct Processed {
+    /// The image re-encoded in its own format, without any metadata.
+    pub original: Vec<u8>,
+    /// Width, extension and content of each responsive variant.
+    pub variants: Vec<(u32, &'static str, Vec<u8>)>,
+}
+
+/// Strips EXIF and the like by decoding and re-encoding the image, after turning it
+/// the way its orientation tag asked, then renders its responsive variants. GIFs are
+/// kept untouched so they stay animated.
+///
+/// Images larger than [`MAX_DIMENSION`] or [`MAX_ALLOC`] fail with
+/// [`image::ImageError::Limits`] before being decoded.
+///
+/// This is CPU bound, so async callers should run it on a blocking thread.
+pub fn process(
+    bytes: &[u8],
+    image_type: ImageType,
+    kind: UploadKind,
+) -> Result<Processed, image::ImageError> {
+    if image_type == ImageType::Gif {
+        return Ok(Processed {
+            original: bytes.to_vec(),
+            variants: Vec::new(),
+        });
+    }
+
+    let image = decode(bytes)?;
+    let original = encode(&image, image_type)?;
+    let mut variants = Vec::new();
+    for width in kind.variant_widths() {
+        let resized = resize(&image, *width);
+        for (extension, _) in VARIANT_FORMATS {
+            let image_type = ImageType::from_extension(extension)
+                .expect("variant formats are known image types");
+            variants.push((*width, extension, encode(&resized, image_type)?));
+        }
+    }
+
+    Ok(Processed { original, variants })
+}
+
+/// One responsive variant of a stored image, for images used as something else
+/// than what they were uploaded for, whose variants don't exist yet.
+///
+/// This is CPU bound, so async callers should run it on a blocking thread.
+pub fn variant(bytes: &[u8], width: u32, image_type: ImageType) -> Result<Vec<u8>, image::ImageError> {
+    encode(&resize(&decode(bytes)?, width), image_type)
+}
+
+fn decode(bytes: &[u8]) -> Result<DynamicImage, image::ImageError> {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+    limits.max_alloc = Some(MAX_ALLOC);
+    let mut reader = ImageReader::new(Cursor::new(bytes)).with_guessed_format()?;
+    reader.limits(limits.clone());
+    let mut decoder = reader.into_decoder()?;
+    // Decoders only check the dimensions, not the buffer the image is decoded into
+    limits.reserve(decoder.total_bytes())?;
+    let orientation = decoder.orientation()?;
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+/// Never upscaled, so every width exists even for small images.
+fn resize(image: &DynamicImage, width: u32) -> DynamicImage {
+    if image.width() > width {
+        image.resize(width, u32::MAX, FilterType::Lanczos3)
+    } else {
+        image.clone()
+    }
+}
+
+fn encode(image: &DynamicImage, image_type: ImageType) -> Result<Vec<u8>, image::ImageError> {
+    let mut bytes = Vec::new();
+    match image_type {
+        ImageType::Png => image.write_with_encoder(PngEncoder::new(&mut bytes))?,
+        // JPEG has no alpha channel
+        ImageType::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY))?,
+        ImageType::Webp => DynamicImage::ImageRgba8(image.to_rgba8())
+            .write_with_encoder(WebPEncoder::new_lossless(&mut bytes))?,
+        ImageType::Avif => DynamicImage::ImageRgba8(image.to_rgba8()).write_with_encoder(
+            AvifEncoder::new_with_speed_quality(&mut bytes, AVIF_SPEED, AVIF_QUALITY),
+        )?,
+        ImageType::Gif => unreachable!("GIFs are stored as uploaded"),
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jpeg_with_exif(width: u32, height: u32) -> Vec<u8> {
+        let image = DynamicImage::ImageRgb8(image::RgbImage::new(width, height));
+        let jpeg = encode(&image, ImageType::Jpeg).unwrap();
+        // An APP1 segment right after the start of image marker
+        let payload = b"Exif\0\0GPS somewhere";
+        let length = (payload.len() + 2) as u16;
+        let mut bytes = vec![0xff, 0xd8, 0xff, 0xe1];
+        bytes.extend_from_slice(&length.to_be_bytes());
+        bytes.extend_from_slice(payload);
+        bytes.extend_from_slice(&jpeg[2..]);
+        bytes
+    }
+
+    #[test]
+    fn metadata_is_stripped_and_variants_generated() {
+        let upload = jpeg_with_exif(300, 150);
+        assert!(upload.windows(4).any(|x| x == b"Exif"));
+
+        let processed = process(&upload, ImageType::Jpeg, UploadKind::Avatar).unwrap();
+        assert!(!processed.original.windows(4).any(|x| x == b"Exif"));
+        assert_eq!(
+            ImageType::sniff(&processed.original),
+            Some(ImageType::Jpeg)
+        );
+
+        let widths = UploadKind::Avatar.variant_widths();
+        assert_eq!(processed.variants.len(), widths.len() * VARIANT_FORMATS.len());
+        let (_, _, smallest) = processed
+            .variants
+            .iter()
+            .find(|(width, extension, _)| *width == widths[0] && *extension == "webp")
+            .unwrap();
+        let smallest = image::load_from_memory(smallest).unwrap();
+        assert_eq!((smallest.width(), smallest.height()), (widths[0], widths[0] / 2));
+    }
+
+    #[test]
+    fn huge_images_are_not_decoded() {
+        let image = DynamicImage::ImageLuma8(image::GrayImage::new(MAX_DIMENSION + 1, 1));
+        let png = encode(&image, ImageType::Png).unwrap();
+        assert!(matches!(
+            process(&png, ImageType::Png, UploadKind::Inline),
+            Err(image::ImageError::Limits(_))
+        ));
+    }
+
+    #[test]
+    fn missing_variants_can_be_rendered_later() {
+        let processed = process(&jpeg_with_exif(300, 150), ImageType::Jpeg, UploadKind::Inline).unwrap();
+        let webp = variant(&processed.original, 64, ImageType::Webp).unwrap();
+        let webp = image::load_from_memory(&webp).unwrap();
+        assert_eq!((webp.width(), webp.height()), (64, 32));
+    }
+
+    #[test]
+    fn inline_images_have_no_variants() {
+        let processed = process(&jpeg_with_exif(10, 10), ImageType::Jpeg, UploadKind::Inline).unwrap();
+        assert!(processed.variants.is_empty());
+    }
+}
@@ -0,0 +1,227 @@
+use axum::{
+    extract::{Multipart, Path},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+    Json,
+};
+
+use super::{
+    blob_key, blob_url, get_store, parse_key, process, process::variant, ImageType, StorageError,
+    MAX_DIMENSION,
+};
+use crate::models::{variant_key, Upload, UploadKind, VARIANT_FORMATS};
+
+/// Largest body the upload route accepts, leaving room for the multipart framing.
+pub const MAX_UPLOAD_BYTES: usize = 5 * 1024 * 1024 + 64 * 1024;
+
+#[derive(Debug)]
+enum UploadError {
+    Unauthorized,
+    UnknownKind,
+    MissingFile,
+    TooLarge(usize),
+    TooManyPixels,
+    UnsupportedType,
+    Internal,
+}
+
+impl IntoResponse for UploadError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            UploadError::Unauthorized => (StatusCode::UNAUTHORIZED, "You need to be logged in".into()),
+            UploadError::UnknownKind => (StatusCode::NOT_FOUND, "Unknown kind of upload".into()),
+            UploadError::MissingFile => (StatusCode::BAD_REQUEST, "Missing the file field".into()),
+            UploadError::TooLarge(max) => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("Images must be smaller than {} KiB", max / 1024),
+            ),
+            UploadError::TooManyPixels => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("Images must be at most {MAX_DIMENSION} pixels wide and high"),
+            ),
+            UploadError::UnsupportedType => (
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "Only valid PNG, JPEG, GIF and WebP images are accepted".into(),
+            ),
+            UploadError::Internal => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Problem while storing the image, try again later".into(),
+            ),
+        };
+        (status, Json(serde_json::json!({ "error": message }))).into_response()
+    }
+}
+
+/// `POST /uploads/:kind` with the image in a multipart `file` field. Answers with
+/// the URL of the stored image, or sends plain HTML forms back where they came from.
+/// Avatars also become the user's image right away.
+#[tracing::instrument(skip(headers, multipart))]
+pub async fn upload_handler(
+    headers: HeaderMap,
+    Path(kind): Path<String>,
+    multipart: Multipart,
+) -> Response {
+    match upload(&headers, &kind, multipart).await {
+        Ok(_) if accepts_html(&headers) => {
+            let back = headers
+                .get(header::REFERER)
+                .and_then(|x| x.to_str().ok())
+                .unwrap_or("/");
+            Redirect::to(back).into_response()
+        }
+        Ok(url) => (StatusCode::CREATED, Json(serde_json::json!({ "url": url }))).into_response(),
+        Err(x) => x.into_response(),
+    }
+}
+
+fn accepts_html(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|x| x.to_str().ok())
+        .is_some_and(|x| x.contains("text/html"))
+}
+
+async fn upload(headers: &HeaderMap, kind: &str, mut multipart: Multipart) -> Result<String, UploadError> {
+    let user_id =
+        crate::auth::get_user_id_from_headers(headers).ok_or(UploadError::Unauthorized)?;
+    let kind = kind.parse::<UploadKind>().map_err(|_| UploadError::UnknownKind)?;
+
+    let mut bytes = None;
+    while let Some(field) = multipart.next_field().await.map_err(|x| {
+        tracing::error!("invalid multipart upload: {x:?}");
+        UploadError::MissingFile
+    })? {
+        if field.name() == Some("file") {
+            // The body limit of the route already caps this at the largest kind
+            bytes = Some(field.bytes().await.map_err(|_| UploadError::TooLarge(kind.max_bytes()))?);
+            break;
+        }
+    }
+    let bytes = bytes.filter(|x| !x.is_empty()).ok_or(UploadError::MissingFile)?;
+    if bytes.len() > kind.max_bytes() {
+        return Err(UploadError::TooLarge(kind.max_bytes()));
+    }
+    let image_type = ImageType::sniff(&bytes).ok_or(UploadError::UnsupportedType)?;
+
+    let processed = tokio::task::spawn_blocking(move || process(&bytes, image_type, kind))
+        .await
+        .map_err(|x| {
+            tracing::error!("image processing panicked: {x:?}");
+            UploadError::Internal
+        })?
+        .map_err(|x| match x {
+            image::ImageError::Limits(_) => UploadError::TooManyPixels,
+            x => {
+                tracing::info!("rejected an image that could not be decoded: {x:?}");
+                UploadError::UnsupportedType
+            }
+        })?;
+
+    let key = blob_key(&processed.original, image_type);
+    let size = processed.original.len() as i64;
+    let store = get_store();
+    for (width, extension, bytes) in processed.variants {
+        let content_type = ImageType::from_extension(extension)
+            .map_or("application/octet-stream", ImageType::content_type);
+        store
+            .put(&variant_key(&key, width, extension), bytes, content_type)
+            .await
+            .map_err(|x| {
+                tracing::error!("problem while storing a variant of {key}: {x}");
+                UploadError::Internal
+            })?;
+    }
+    // Stored last, so whoever sees the image can rely on its variants
+    store
+        .put(&key, processed.original, image_type.content_type())
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while storing {key}: {x}");
+            UploadError::Internal
+        })?;
+    Upload::record(&key, user_id, kind, image_type.content_type(), size)
+        .await
+        .map_err(|x| {
+            tracing::error!("problem while recording the upload {key}: {x:?}");
+            UploadError::Internal
+        })?;
+
+    let url = blob_url(&key);
+    if kind == UploadKind::Avatar {
+        crate::models::User::set_avatar(user_id, &url)
+            .await
+            .map_err(|x| {
+                tracing::error!("problem while setting the avatar of {user_id}: {x:?}");
+                UploadError::Internal
+            })?;
+    }
+    Ok(url)
+}
+
+/// `GET /uploads/:key`, cached for good since a key never changes content.
+/// Variants missing from the store are rendered and stored on the first request.
+#[tracing::instrument]
+pub async fn serve_handler(Path(key): Path<String>) -> Response {
+    let Some(image_type) = parse_key(&key) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let stored = match get_store().get(&key).await {
+        Ok(None) => missing_variant(&key, image_type).await,
+        x => x,
+    };
+    match stored {
+        Ok(Some(bytes)) => (
+            [
+                (header::CONTENT_TYPE, image_type.content_type()),
+                (header::CACHE_CONTROL, "public, max-age=31536000, immutable"),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(x) => {
+            tracing::error!("problem while reading {key}: {x}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Renders the variant `key` of an image uploaded for a kind without that variant,
+/// such as an inline image later used as a cover. `None` when `key` is not a variant
+/// of any kind or its image doesn't exist.
+async fn missing_variant(key: &str, image_type: ImageType) -> Result<Option<Vec<u8>>, StorageError> {
+    let Some((hash, width)) = key
+        .split_once('.')
+        .and_then(|(name, _)| name.split_once('-'))
+        .and_then(|(hash, width)| Some((hash, width.strip_suffix('w')?.parse::<u32>().ok()?)))
+    else {
+        return Ok(None);
+    };
+    let is_variant = UploadKind::ALL.iter().any(|x| x.variant_widths().contains(&width))
+        && VARIANT_FORMATS.iter().any(|(extension, _)| *extension == image_type.extension());
+    if !is_variant {
+        return Ok(None);
+    }
+
+    let store = get_store();
+    let mut original = None;
+    for original_type in [ImageType::Png, ImageType::Jpeg, ImageType::Webp] {
+        original = store.get(&format!("{hash}.{}", original_type.extension())).await?;
+        if original.is_some() {
+            break;
+        }
+    }
+    let Some(original) = original else {
+        return Ok(None);
+    };
+
+    let bytes = match tokio::task::spawn_blocking(move || variant(&original, width, image_type)).await {
+        Ok(Ok(x)) => x,
+        x => {
+            tracing::error!("problem while rendering the variant {key}: {x:?}");
+            return Ok(None);
+        }
+    };
+    store.put(key, bytes.clone(), image_type.content_type()).await?;
+    Ok(Some(bytes))
+}